@@ -3,6 +3,7 @@
 in vec3 v_position;
 in vec3 v_normal;
 in vec2 v_tex_coord; 
+in vec4 v_tangent;
 
 out vec4 color;
 
@@ -10,6 +11,9 @@ uniform mat4 view;
 uniform vec3 viewPos;
 uniform sampler2D diffuse_tex;
 uniform bool has_texture; 
+uniform sampler2D normal_tex;
+uniform bool has_normal_map;
+uniform float normal_strength;
 uniform int cascadeCount;
 
 uniform sampler2DArray shadow_map;
@@ -204,8 +208,21 @@ vec3 calSpotLight(Light l, vec3 normal, int i) {
     return l.intensity * (1 - shadow) * (diffuse + spec) * attenuation;
 }
 
+// MikkTSpace 约定：副切线在像素着色器中由插值后（未归一化）的法线与切线重建
+vec3 getNormal() {
+    vec3 n = normalize(v_normal);
+    if (!has_normal_map) {
+        return n;
+    }
+    vec3 ts = texture(normal_tex, v_tex_coord).xyz * 2.0 - 1.0;
+    ts.xy *= normal_strength;
+    vec3 bitangent = v_tangent.w * cross(v_normal, v_tangent.xyz);
+    vec3 mapped = ts.x * v_tangent.xyz + ts.y * bitangent + ts.z * v_normal;
+    return length(mapped) > 0.0 ? normalize(mapped) : n;
+}
+
 void main() {
-    vec3 normal = normalize(v_normal);
+    vec3 normal = getNormal();
     vec3 light_color = vec3(0.0f);
 
     for (int i = 0; i < num_lights; i++) {
@@ -3,10 +3,12 @@
 in vec3 position;
 in vec3 normal;
 in vec2 tex_coord; 
+in vec4 tangent;
 
 out vec3 v_position;
 out vec3 v_normal;
 out vec2 v_tex_coord;
+out vec4 v_tangent;
 
 uniform mat4 perspective;
 uniform mat4 view;
@@ -16,6 +18,7 @@ void main() {
     v_position = vec3(model * vec4(position, 1.0));
     v_normal = mat3(transpose(inverse(model))) * normal;
     v_tex_coord = tex_coord; 
+    v_tangent = vec4(mat3(model) * tangent.xyz, tangent.w);
     
 
 
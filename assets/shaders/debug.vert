@@ -6,9 +6,10 @@ uniform mat4 view;
 uniform mat4 projection;
 uniform mat4 model;
 uniform int selected_idx;
+uniform bool highlight_all;
 
 void main() {
-    if(gl_VertexID == selected_idx){
+    if(highlight_all || gl_VertexID == selected_idx){
         u_color = vec4(1.0, 1.0, 0.0, 1.0);
     } else {
         u_color = vec4(0.0, 0.0, 1.0, 1.0);
@@ -75,3 +75,25 @@ impl Lambertian {
         data
     }
 }
+
+/// 法线贴图来源：内置的程序化贴图或切线空间法线贴图文件
+#[derive(Clone, Debug, PartialEq)]
+pub enum NormalMap {
+    Bricks,
+    Tiles,
+    WoodGrain,
+    File(String),
+}
+
+impl NormalMap {
+    pub const BUILTIN: [NormalMap; 3] = [NormalMap::Bricks, NormalMap::Tiles, NormalMap::WoodGrain];
+
+    pub fn label(&self) -> &str {
+        match self {
+            NormalMap::Bricks => "砖墙",
+            NormalMap::Tiles => "地砖",
+            NormalMap::WoodGrain => "木纹",
+            NormalMap::File(path) => path,
+        }
+    }
+}
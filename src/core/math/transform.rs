@@ -45,6 +45,15 @@ impl Transform {
     pub fn get_matrix(&self) -> glam::f32::Mat4 {
         glam::f32::Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.position)
     }
+
+    /// 在两个变换之间插值，旋转使用球面插值
+    pub fn lerp(&self, other: &Transform, t: f32) -> Transform {
+        Transform::new(
+            self.position.lerp(other.position, t),
+            self.rotation.slerp(other.rotation, t),
+            self.scale.lerp(other.scale, t),
+        )
+    }
 }
 
 pub fn look_at_rh(
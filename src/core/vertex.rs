@@ -5,8 +5,10 @@ pub struct Vertex {
     pub position: [f32; 3],
     pub tex_coord: [f32; 2],
     pub normal: [f32; 3],
+    /// 切线，w 为副切线方向
+    pub tangent: [f32; 4],
 }
-implement_vertex!(Vertex, position, tex_coord, normal);
+implement_vertex!(Vertex, position, tex_coord, normal, tangent);
 
 #[derive(Copy, Clone)]
 pub struct Triangle<'a> {
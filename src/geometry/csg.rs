@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+
+use glam::{DMat3, DVec2, DVec3, Mat4};
+
+use crate::geometry::shape::mesh::Mesh;
+use crate::physics::boundingbox::{AABB, BoundingVolume};
+
+// BSP 树布尔运算（参考 csg.js 的算法）
+//
+// 网格先变换到同一坐标系，转为多边形后在 f64 下切分，最后扇形三角化回 `Mesh`。
+// 输入为闭合网格时结果也是闭合的（切分处可能留下 T 形顶点，不影响渲染与碰撞）。
+
+const EPSILON: f64 = 1e-5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CsgOp {
+    #[default]
+    Union,
+    Difference,
+    Intersection,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CsgVertex {
+    pos: DVec3,
+    normal: DVec3,
+    uv: DVec2,
+}
+
+impl CsgVertex {
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+    }
+
+    fn lerp(&self, other: &CsgVertex, t: f64) -> CsgVertex {
+        CsgVertex {
+            pos: self.pos.lerp(other.pos, t),
+            normal: self.normal.lerp(other.normal, t),
+            uv: self.uv.lerp(other.uv, t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Plane {
+    normal: DVec3,
+    w: f64,
+}
+
+impl Plane {
+    fn from_points(a: DVec3, b: DVec3, c: DVec3) -> Option<Plane> {
+        let n = (b - a).cross(c - a);
+        if n.length_squared() < 1e-24 {
+            return None;
+        }
+        let normal = n.normalize();
+        Some(Plane {
+            normal,
+            w: normal.dot(a),
+        })
+    }
+
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+
+    /// 按平面切分多边形，共面的多边形按朝向放入前 / 后共面列表
+    fn split_polygon(
+        &self,
+        polygon: &Polygon,
+        coplanar_front: &mut Vec<Polygon>,
+        coplanar_back: &mut Vec<Polygon>,
+        front: &mut Vec<Polygon>,
+        back: &mut Vec<Polygon>,
+    ) {
+        const COPLANAR: u8 = 0;
+        const FRONT: u8 = 1;
+        const BACK: u8 = 2;
+        const SPANNING: u8 = 3;
+
+        let mut polygon_type = 0;
+        let types: Vec<u8> = polygon
+            .vertices
+            .iter()
+            .map(|v| {
+                let t = self.normal.dot(v.pos) - self.w;
+                let ty = if t < -EPSILON {
+                    BACK
+                } else if t > EPSILON {
+                    FRONT
+                } else {
+                    COPLANAR
+                };
+                polygon_type |= ty;
+                ty
+            })
+            .collect();
+
+        match polygon_type {
+            COPLANAR => {
+                if self.normal.dot(polygon.plane.normal) > 0.0 {
+                    coplanar_front.push(polygon.clone());
+                } else {
+                    coplanar_back.push(polygon.clone());
+                }
+            }
+            FRONT => front.push(polygon.clone()),
+            BACK => back.push(polygon.clone()),
+            _ => {
+                let mut f = Vec::new();
+                let mut b = Vec::new();
+                let n = polygon.vertices.len();
+                for i in 0..n {
+                    let j = (i + 1) % n;
+                    let (ti, tj) = (types[i], types[j]);
+                    let (vi, vj) = (&polygon.vertices[i], &polygon.vertices[j]);
+                    if ti != BACK {
+                        f.push(*vi);
+                    }
+                    if ti != FRONT {
+                        b.push(*vi);
+                    }
+                    if (ti | tj) == SPANNING {
+                        let t = (self.w - self.normal.dot(vi.pos)) / self.normal.dot(vj.pos - vi.pos);
+                        let v = vi.lerp(vj, t);
+                        f.push(v);
+                        b.push(v);
+                    }
+                }
+                if f.len() >= 3 {
+                    front.push(Polygon {
+                        vertices: f,
+                        plane: polygon.plane,
+                    });
+                }
+                if b.len() >= 3 {
+                    back.push(Polygon {
+                        vertices: b,
+                        plane: polygon.plane,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Polygon {
+    vertices: Vec<CsgVertex>,
+    plane: Plane,
+}
+
+impl Polygon {
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        for v in &mut self.vertices {
+            v.flip();
+        }
+        self.plane.flip();
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<Box<Node>>,
+    back: Option<Box<Node>>,
+    polygons: Vec<Polygon>,
+}
+
+impl Node {
+    fn new(polygons: Vec<Polygon>) -> Node {
+        let mut node = Node::default();
+        node.build(polygons);
+        node
+    }
+
+    /// 实体与空间互换
+    fn invert(&mut self) {
+        for p in &mut self.polygons {
+            p.flip();
+        }
+        if let Some(plane) = &mut self.plane {
+            plane.flip();
+        }
+        if let Some(front) = &mut self.front {
+            front.invert();
+        }
+        if let Some(back) = &mut self.back {
+            back.invert();
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// 去掉落在本树实体内部的多边形
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        let Some(plane) = self.plane else {
+            return polygons;
+        };
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for p in &polygons {
+            let mut cf = Vec::new();
+            let mut cb = Vec::new();
+            plane.split_polygon(p, &mut cf, &mut cb, &mut front, &mut back);
+            front.append(&mut cf);
+            back.append(&mut cb);
+        }
+        let mut front = match &self.front {
+            Some(node) => node.clip_polygons(front),
+            None => front,
+        };
+        let back = match &self.back {
+            Some(node) => node.clip_polygons(back),
+            None => Vec::new(),
+        };
+        front.extend(back);
+        front
+    }
+
+    fn clip_to(&mut self, other: &Node) {
+        self.polygons = other.clip_polygons(std::mem::take(&mut self.polygons));
+        if let Some(front) = &mut self.front {
+            front.clip_to(other);
+        }
+        if let Some(back) = &mut self.back {
+            back.clip_to(other);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<Polygon> {
+        let mut result = self.polygons.clone();
+        if let Some(front) = &self.front {
+            result.extend(front.all_polygons());
+        }
+        if let Some(back) = &self.back {
+            result.extend(back.all_polygons());
+        }
+        result
+    }
+
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        if polygons.is_empty() {
+            return;
+        }
+        let plane = *self.plane.get_or_insert(polygons[0].plane);
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for p in &polygons {
+            let mut cf = Vec::new();
+            let mut cb = Vec::new();
+            plane.split_polygon(p, &mut cf, &mut cb, &mut front, &mut back);
+            self.polygons.append(&mut cf);
+            self.polygons.append(&mut cb);
+        }
+        if !front.is_empty() {
+            self.front.get_or_insert_with(Default::default).build(front);
+        }
+        if !back.is_empty() {
+            self.back.get_or_insert_with(Default::default).build(back);
+        }
+    }
+}
+
+fn to_polygons(mesh: &Mesh, transform: Mat4) -> Vec<Polygon> {
+    let m = transform.as_dmat4();
+    let normal_matrix = DMat3::from_mat4(m).inverse().transpose();
+    let vertex = |i: u16| {
+        let i = i as usize;
+        let p = DVec3::from(mesh.vertices[i].map(|x| x as f64));
+        let n = mesh.normals.get(i).map_or(DVec3::ZERO, |n| DVec3::from(n.map(|x| x as f64)));
+        let uv = mesh.tex_coords.get(i).map_or(DVec2::ZERO, |t| DVec2::new(t[0] as f64, t[1] as f64));
+        CsgVertex {
+            pos: m.transform_point3(p),
+            normal: (normal_matrix * n).normalize_or_zero(),
+            uv,
+        }
+    };
+    mesh.indices
+        .chunks_exact(3)
+        .filter_map(|t| {
+            let vertices = vec![vertex(t[0]), vertex(t[1]), vertex(t[2])];
+            let plane = Plane::from_points(vertices[0].pos, vertices[1].pos, vertices[2].pos)?;
+            Some(Polygon { vertices, plane })
+        })
+        .collect()
+}
+
+/// 结果顶点超出 u16 索引范围时返回错误，不截断索引
+fn to_mesh(polygons: &[Polygon]) -> Result<Mesh, String> {
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+    let mut tex_coords = Vec::new();
+    let mut indices = Vec::new();
+    let mut aabb = AABB::default();
+    let mut lookup: HashMap<[u32; 8], u16> = HashMap::new();
+
+    let mut index = |v: &CsgVertex| -> u16 {
+        let p = v.pos.as_vec3().to_array();
+        let n = v.normal.normalize_or_zero().as_vec3().to_array();
+        let t = v.uv.as_vec2().to_array();
+        let key = [p[0], p[1], p[2], n[0], n[1], n[2], t[0], t[1]].map(f32::to_bits);
+        *lookup.entry(key).or_insert_with(|| {
+            aabb.union_point_array(p);
+            vertices.push(p);
+            normals.push(n);
+            tex_coords.push(t);
+            (vertices.len() - 1) as u16
+        })
+    };
+
+    for polygon in polygons {
+        let first = index(&polygon.vertices[0]);
+        for k in 1..polygon.vertices.len() - 1 {
+            let b = index(&polygon.vertices[k]);
+            let c = index(&polygon.vertices[k + 1]);
+            if first != b && b != c && c != first {
+                indices.extend_from_slice(&[first, b, c]);
+            }
+        }
+    }
+    if vertices.len() > u16::MAX as usize {
+        return Err(format!("布尔运算结果有 {} 个顶点，超出 16 位索引上限 {}", vertices.len(), u16::MAX));
+    }
+
+    Ok(Mesh {
+        vertices,
+        normals,
+        tex_coords,
+        indices,
+        bounding_volume: BoundingVolume::AABB(aabb),
+        tangents: Vec::new(),
+        smoothing_groups: Vec::new(),
+    })
+}
+
+/// 对两个网格做布尔运算，`a_transform` / `b_transform` 把各自网格变换到同一坐标系；
+/// 结果超出 16 位索引范围时返回错误
+pub fn boolean(a: &Mesh, a_transform: Mat4, b: &Mesh, b_transform: Mat4, op: CsgOp) -> Result<Mesh, String> {
+    let mut a = Node::new(to_polygons(a, a_transform));
+    let mut b = Node::new(to_polygons(b, b_transform));
+
+    match op {
+        CsgOp::Union => {
+            a.clip_to(&b);
+            b.clip_to(&a);
+            b.invert();
+            b.clip_to(&a);
+            b.invert();
+            a.build(b.all_polygons());
+        }
+        CsgOp::Difference => {
+            a.invert();
+            a.clip_to(&b);
+            b.clip_to(&a);
+            b.invert();
+            b.clip_to(&a);
+            b.invert();
+            a.build(b.all_polygons());
+            a.invert();
+        }
+        CsgOp::Intersection => {
+            a.invert();
+            b.clip_to(&a);
+            b.invert();
+            a.clip_to(&b);
+            b.clip_to(&a);
+            a.build(b.all_polygons());
+            a.invert();
+        }
+    }
+
+    to_mesh(&a.all_polygons())
+}
+
+/// 闭合网格的体积（散度定理），用于检验布尔结果
+pub fn mesh_volume(mesh: &Mesh) -> f32 {
+    mesh.indices
+        .chunks_exact(3)
+        .map(|t| {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| glam::Vec3::from(mesh.vertices[i as usize]));
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+#[test]
+fn test_cube_difference_volume() {
+    use crate::geometry::halfedge::HalfEdgeMesh;
+    use crate::geometry::shape::cube::Cube;
+    use crate::geometry::shape::mesh::AsMesh;
+
+    let cube = Cube {
+        width: 2.0,
+        height: 2.0,
+        depth: 2.0,
+    }
+    .as_mesh();
+    let offset = Mat4::from_translation(glam::vec3(1.0, 1.0, 1.0));
+
+    let diff = boolean(&cube, Mat4::IDENTITY, &cube, offset, CsgOp::Difference).unwrap();
+    assert!((mesh_volume(&diff) - 7.0).abs() < 1e-3, "{}", mesh_volume(&diff));
+
+    let union = boolean(&cube, Mat4::IDENTITY, &cube, offset, CsgOp::Union).unwrap();
+    assert!((mesh_volume(&union) - 15.0).abs() < 1e-3);
+
+    let inter = boolean(&cube, Mat4::IDENTITY, &cube, offset, CsgOp::Intersection).unwrap();
+    assert!((mesh_volume(&inter) - 1.0).abs() < 1e-3);
+
+    // 结果按位置焊接后没有边界
+    let he = HalfEdgeMesh::from_mesh(&inter, 1e-4);
+    assert!(he.boundary_loops().is_empty());
+}
+
+#[test]
+fn test_boolean_rejects_index_overflow() {
+    // 每个三角形三个独立顶点，超出 u16 索引范围
+    let polygons: Vec<Polygon> = (0..22_000)
+        .map(|i| {
+            let base = DVec3::new(i as f64, 0.0, 0.0);
+            let pos = [base, base + DVec3::X * 0.5, base + DVec3::Y];
+            let vertices = pos.map(|pos| CsgVertex { pos, normal: DVec3::Z, uv: DVec2::ZERO }).to_vec();
+            Polygon { vertices, plane: Plane::from_points(pos[0], pos[1], pos[2]).unwrap() }
+        })
+        .collect();
+    assert!(to_mesh(&polygons).is_err());
+    assert!(to_mesh(&polygons[..20_000]).is_ok());
+}
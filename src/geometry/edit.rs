@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+use crate::geometry::halfedge::{Corner, HalfEdgeMesh};
+use crate::geometry::shape::mesh::Mesh;
+
+// 低模建模操作：在多边形拓扑上编辑后重新生成三角网格
+//
+// 新生成的角法线记为零向量，最后统一用面法线补齐；未改动的面保留原有法线。
+// 结果顶点超出 u16 索引范围时操作返回 None，调用者保留原网格。
+
+const WELD_EPSILON: f32 = 1e-5;
+const NEW_NORMAL: [f32; 3] = [0.0, 0.0, 0.0];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    #[default]
+    Vertex,
+    Edge,
+    Face,
+}
+
+/// 网格编辑状态：面用多边形拓扑中的索引，边用焊接后的顶点对表示
+#[derive(Clone, Debug)]
+pub struct MeshSelection {
+    pub mode: SelectionMode,
+    pub faces: Vec<usize>,
+    pub edges: Vec<(usize, usize)>,
+    /// 选中元素的轮廓线（模型空间线段列表），供调试通道绘制
+    pub highlight: Vec<[f32; 3]>,
+    pub extrude_distance: f32,
+    pub inset_amount: f32,
+    pub bevel_height: f32,
+    pub cut_ratio: f32,
+    pub crease_weight: f32,
+    pub simplify_by_error: bool,
+    pub simplify_ratio: f32,
+    pub simplify_error: f32,
+    /// 上一次编辑的结果超出 u16 索引范围，已被拒绝
+    pub rejected: bool,
+}
+
+impl Default for MeshSelection {
+    fn default() -> Self {
+        Self {
+            mode: SelectionMode::Vertex,
+            faces: vec![],
+            edges: vec![],
+            highlight: vec![],
+            extrude_distance: 0.5,
+            inset_amount: 0.2,
+            bevel_height: 0.2,
+            cut_ratio: 0.5,
+            crease_weight: 1.0,
+            simplify_by_error: false,
+            simplify_ratio: 0.5,
+            simplify_error: 0.01,
+            rejected: false,
+        }
+    }
+}
+
+impl MeshSelection {
+    pub fn clear(&mut self) {
+        self.faces.clear();
+        self.edges.clear();
+        self.highlight.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty() && self.edges.is_empty()
+    }
+
+    /// 根据当前选择重新计算轮廓线
+    pub fn update_highlight(&mut self, mesh: &Mesh) {
+        let (topo, _) = editable_topology(mesh);
+        self.highlight.clear();
+        for &f in &self.faces {
+            if f >= topo.faces.len() {
+                continue;
+            }
+            let verts = topo.face_vertices(f);
+            for k in 0..verts.len() {
+                self.highlight.push(topo.vertices[verts[k]].position);
+                self.highlight
+                    .push(topo.vertices[verts[(k + 1) % verts.len()]].position);
+            }
+        }
+        for &(a, b) in &self.edges {
+            if a < topo.vertices.len() && b < topo.vertices.len() {
+                self.highlight.push(topo.vertices[a].position);
+                self.highlight.push(topo.vertices[b].position);
+            }
+        }
+    }
+
+    /// 选中或取消选中一个面，`additive` 为 false 时替换当前选择
+    pub fn toggle_face(&mut self, face: usize, additive: bool) {
+        if !additive {
+            self.faces.clear();
+        }
+        if let Some(pos) = self.faces.iter().position(|&f| f == face) {
+            self.faces.remove(pos);
+        } else {
+            self.faces.push(face);
+        }
+    }
+
+    pub fn toggle_edge(&mut self, edge: (usize, usize), additive: bool) {
+        let key = edge_key(edge.0, edge.1);
+        if !additive {
+            self.edges.clear();
+        }
+        if let Some(pos) = self.edges.iter().position(|&e| e == key) {
+            self.edges.remove(pos);
+        } else {
+            self.edges.push(key);
+        }
+    }
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// 编辑用拓扑：焊接顶点并把共面三角形合并为四边形
+///
+/// 构建过程是确定的，同一个 `Mesh` 总是得到相同的面编号。
+pub fn editable_topology(mesh: &Mesh) -> (HalfEdgeMesh, Vec<usize>) {
+    let (topo, remap) = HalfEdgeMesh::from_mesh_welded(mesh, WELD_EPSILON);
+    (topo.merge_coplanar_triangles(1f32.to_radians()), remap)
+}
+
+/// 拾取射线（模型空间）击中的多边形面
+pub fn pick_face(mesh: &Mesh, origin: [f32; 3], direction: [f32; 3]) -> Option<(usize, [f32; 3])> {
+    let (tri, hit) = mesh.compute_intersecting_face(origin, direction)?;
+    let (topo, remap) = editable_topology(mesh);
+    let tri = tri.map(|i| remap[i as usize]);
+    (0..topo.faces.len())
+        .find(|&f| {
+            let verts = topo.face_vertices(f);
+            tri.iter().all(|v| verts.contains(v))
+        })
+        .map(|f| (f, hit))
+}
+
+/// 拾取射线击中的面上离击中点最近的边
+pub fn pick_edge(mesh: &Mesh, origin: [f32; 3], direction: [f32; 3]) -> Option<(usize, usize)> {
+    let (face, hit) = pick_face(mesh, origin, direction)?;
+    let (topo, _) = editable_topology(mesh);
+    let hit = Vec3::from(hit);
+    let verts = topo.face_vertices(face);
+    (0..verts.len())
+        .map(|k| {
+            let a = verts[k];
+            let b = verts[(k + 1) % verts.len()];
+            let pa = Vec3::from(topo.vertices[a].position);
+            let pb = Vec3::from(topo.vertices[b].position);
+            let ab = pb - pa;
+            let t = ((hit - pa).dot(ab) / ab.length_squared().max(1e-12)).clamp(0.0, 1.0);
+            ((pa + ab * t).distance(hit), edge_key(a, b))
+        })
+        .min_by(|x, y| x.0.total_cmp(&y.0))
+        .map(|(_, e)| e)
+}
+
+fn face_normal(positions: &[[f32; 3]], face: &[Corner]) -> Vec3 {
+    let mut n = Vec3::ZERO;
+    for k in 0..face.len() {
+        let a = Vec3::from(positions[face[k].vertex]);
+        let b = Vec3::from(positions[face[(k + 1) % face.len()].vertex]);
+        n += Vec3::new(
+            (a.y - b.y) * (a.z + b.z),
+            (a.z - b.z) * (a.x + b.x),
+            (a.x - b.x) * (a.y + b.y),
+        );
+    }
+    n.normalize_or_zero()
+}
+
+fn finish(positions: Vec<[f32; 3]>, mut faces: Vec<Vec<Corner>>) -> Option<Mesh> {
+    for face in &mut faces {
+        if face.iter().any(|c| c.normal == NEW_NORMAL) {
+            let n = face_normal(&positions, face).to_array();
+            for c in face.iter_mut().filter(|c| c.normal == NEW_NORMAL) {
+                c.normal = n;
+            }
+        }
+    }
+    HalfEdgeMesh::from_faces(positions, &faces).to_mesh()
+}
+
+fn lerp2(a: [f32; 2], b: [f32; 2], t: f32) -> [f32; 2] {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+}
+
+fn lerp_normal(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    Vec3::from(a).lerp(Vec3::from(b), t).normalize_or_zero().to_array()
+}
+
+/// 整体挤出选中的面区域，区域边界生成侧面
+pub fn extrude_faces(mesh: &Mesh, selected: &[usize], distance: f32) -> Option<Mesh> {
+    let (topo, _) = editable_topology(mesh);
+    let mut positions = topo.positions();
+    let mut faces = topo.to_faces();
+    let in_sel: Vec<bool> = (0..faces.len()).map(|f| selected.contains(&f)).collect();
+
+    // 每个顶点沿相邻选中面法线的平均方向移动
+    let mut offset: HashMap<usize, Vec3> = HashMap::new();
+    for f in (0..faces.len()).filter(|&f| in_sel[f]) {
+        let n = topo.face_normal(f);
+        for v in topo.face_vertices(f) {
+            *offset.entry(v).or_insert(Vec3::ZERO) += n;
+        }
+    }
+    let mut sorted: Vec<usize> = offset.keys().copied().collect();
+    sorted.sort_unstable();
+    let mut moved: HashMap<usize, usize> = HashMap::new();
+    for v in sorted {
+        let p = Vec3::from(positions[v]) + offset[&v].normalize_or_zero() * distance;
+        positions.push(p.to_array());
+        moved.insert(v, positions.len() - 1);
+    }
+
+    for f in (0..faces.len()).filter(|&f| in_sel[f]) {
+        for h in topo.face_half_edges(f) {
+            let he = &topo.half_edges[h];
+            let boundary = he.twin.is_none_or(|t| !in_sel[topo.half_edges[t].face]);
+            if !boundary {
+                continue;
+            }
+            let u = he.origin;
+            let v = topo.dest(h);
+            let corner = |vertex, tex_coord| Corner {
+                vertex,
+                tex_coord,
+                normal: NEW_NORMAL,
+            };
+            faces.push(vec![
+                corner(u, [0.0, 0.0]),
+                corner(v, [1.0, 0.0]),
+                corner(moved[&v], [1.0, 1.0]),
+                corner(moved[&u], [0.0, 1.0]),
+            ]);
+        }
+        for c in &mut faces[f] {
+            c.vertex = moved[&c.vertex];
+        }
+    }
+
+    finish(positions, faces)
+}
+
+/// 逐面内插：`amount` 为向面中心收缩的比例，`height` 为内插面沿法线的偏移
+///
+/// `height` 非零时即为面倒角（3ds Max 式：内插后再挤出）。
+pub fn inset_faces(mesh: &Mesh, selected: &[usize], amount: f32, height: f32) -> Option<Mesh> {
+    let (topo, _) = editable_topology(mesh);
+    let mut positions = topo.positions();
+    let mut faces = topo.to_faces();
+    let amount = amount.clamp(0.0, 0.99);
+
+    for &f in selected {
+        if f >= topo.faces.len() {
+            continue;
+        }
+        let outer = faces[f].clone();
+        let n = topo.face_normal(f);
+        let center = topo.face_centroid(f);
+        let center_uv = outer.iter().fold([0.0, 0.0], |acc, c| {
+            [acc[0] + c.tex_coord[0], acc[1] + c.tex_coord[1]]
+        });
+        let center_uv = [
+            center_uv[0] / outer.len() as f32,
+            center_uv[1] / outer.len() as f32,
+        ];
+
+        let inner: Vec<Corner> = outer
+            .iter()
+            .map(|c| {
+                let p = Vec3::from(positions[c.vertex]).lerp(center, amount) + n * height;
+                positions.push(p.to_array());
+                Corner {
+                    vertex: positions.len() - 1,
+                    tex_coord: lerp2(c.tex_coord, center_uv, amount),
+                    normal: if height == 0.0 { c.normal } else { NEW_NORMAL },
+                }
+            })
+            .collect();
+
+        let len = outer.len();
+        for k in 0..len {
+            let ring = |c: Corner| Corner {
+                normal: NEW_NORMAL,
+                ..c
+            };
+            faces.push(vec![
+                ring(outer[k]),
+                ring(outer[(k + 1) % len]),
+                ring(inner[(k + 1) % len]),
+                ring(inner[k]),
+            ]);
+        }
+        faces[f] = inner;
+    }
+
+    finish(positions, faces)
+}
+
+/// 沿经过指定边的四边形环插入一圈新边，`ratio` 为切割位置
+pub fn loop_cut(mesh: &Mesh, edge: (usize, usize), ratio: f32) -> Option<Mesh> {
+    let (topo, _) = editable_topology(mesh);
+    let mut positions = topo.positions();
+    let ratio = ratio.clamp(0.01, 0.99);
+
+    let Some(start) = (0..topo.half_edges.len())
+        .find(|&h| edge_key(topo.half_edges[h].origin, topo.dest(h)) == edge_key(edge.0, edge.1))
+    else {
+        return Some(mesh.clone());
+    };
+
+    // 收集四边形环：(面, 进入该面的半边)
+    let mut ring: Vec<(usize, usize)> = Vec::new();
+    let mut visited = vec![false; topo.faces.len()];
+    let mut walk = |mut h: usize, ring: &mut Vec<(usize, usize)>| loop {
+        let f = topo.half_edges[h].face;
+        if visited[f] || topo.face_half_edges(f).len() != 4 {
+            break;
+        }
+        visited[f] = true;
+        ring.push((f, h));
+        let opposite = topo.half_edges[topo.half_edges[h].next].next;
+        match topo.half_edges[opposite].twin {
+            Some(t) => h = t,
+            None => break,
+        }
+    };
+    walk(start, &mut ring);
+    if let Some(t) = topo.half_edges[start].twin {
+        walk(t, &mut ring);
+    }
+    if ring.is_empty() {
+        return Some(mesh.clone());
+    }
+
+    // 被切割的边 -> (新顶点, 起点, 比例)
+    let mut cuts: HashMap<(usize, usize), (usize, usize, f32)> = HashMap::new();
+    let mut cut = |from: usize, to: usize, t: f32, positions: &mut Vec<[f32; 3]>| {
+        *cuts.entry(edge_key(from, to)).or_insert_with(|| {
+            let p = Vec3::from(positions[from]).lerp(Vec3::from(positions[to]), t);
+            positions.push(p.to_array());
+            (positions.len() - 1, from, t)
+        })
+    };
+    for &(_, h) in &ring {
+        let p = topo.half_edges[h].origin;
+        let q = topo.dest(h);
+        let opposite = topo.half_edges[topo.half_edges[h].next].next;
+        let r = topo.half_edges[opposite].origin;
+        let s = topo.dest(opposite);
+        cut(p, q, ratio, &mut positions);
+        cut(s, r, ratio, &mut positions);
+    }
+
+    let split_corner = |a: &Corner, b: &Corner| -> Option<Corner> {
+        let &(m, from, t) = cuts.get(&edge_key(a.vertex, b.vertex))?;
+        let t = if a.vertex == from { t } else { 1.0 - t };
+        Some(Corner {
+            vertex: m,
+            tex_coord: lerp2(a.tex_coord, b.tex_coord, t),
+            normal: lerp_normal(a.normal, b.normal, t),
+        })
+    };
+
+    let ring_entry: HashMap<usize, usize> = ring.iter().copied().collect();
+    let mut faces = Vec::with_capacity(topo.faces.len() + ring.len());
+    for (f, corners) in topo.to_faces().into_iter().enumerate() {
+        if let Some(&h) = ring_entry.get(&f) {
+            let hs = topo.face_half_edges(f);
+            let k = hs.iter().position(|&x| x == h).unwrap();
+            let c: Vec<Corner> = (0..4).map(|i| corners[(k + i) % 4]).collect();
+            let m1 = split_corner(&c[0], &c[1]).unwrap();
+            let m2 = split_corner(&c[2], &c[3]).unwrap();
+            faces.push(vec![c[0], m1, m2, c[3]]);
+            faces.push(vec![m1, c[1], c[2], m2]);
+        } else {
+            // 相邻的非环面在被切的边上插入新顶点，避免 T 形接缝
+            let mut out = Vec::with_capacity(corners.len() + 1);
+            for k in 0..corners.len() {
+                out.push(corners[k]);
+                if let Some(m) = split_corner(&corners[k], &corners[(k + 1) % corners.len()]) {
+                    out.push(m);
+                }
+            }
+            faces.push(out);
+        }
+    }
+
+    finish(positions, faces)
+}
+
+/// 删除选中的面
+pub fn delete_faces(mesh: &Mesh, selected: &[usize]) -> Option<Mesh> {
+    let (topo, _) = editable_topology(mesh);
+    let faces = topo
+        .to_faces()
+        .into_iter()
+        .enumerate()
+        .filter(|(f, _)| !selected.contains(f))
+        .map(|(_, c)| c)
+        .collect();
+    finish(topo.positions(), faces)
+}
+
+/// 删除与选中边相邻的面
+pub fn delete_edges(mesh: &Mesh, edges: &[(usize, usize)]) -> Option<Mesh> {
+    let (topo, _) = editable_topology(mesh);
+    let doomed: Vec<usize> = (0..topo.half_edges.len())
+        .filter(|&h| edges.contains(&edge_key(topo.half_edges[h].origin, topo.dest(h))))
+        .map(|h| topo.half_edges[h].face)
+        .collect();
+    delete_faces(mesh, &doomed)
+}
+
+/// 删除使用指定 `Mesh` 顶点的所有面
+pub fn delete_vertex(mesh: &Mesh, vertex: usize) -> Option<Mesh> {
+    let (topo, remap) = editable_topology(mesh);
+    let Some(&v) = remap.get(vertex) else {
+        return Some(mesh.clone());
+    };
+    let doomed: Vec<usize> = (0..topo.faces.len())
+        .filter(|&f| topo.face_vertices(f).contains(&v))
+        .collect();
+    delete_faces(mesh, &doomed)
+}
+
+#[test]
+fn test_cube_operators() {
+    use crate::geometry::shape::cube::Cube;
+    use crate::geometry::shape::mesh::AsMesh;
+
+    let cube = Cube {
+        width: 1.0,
+        height: 1.0,
+        depth: 1.0,
+    }
+    .as_mesh();
+    let (topo, _) = editable_topology(&cube);
+    assert_eq!(topo.faces.len(), 6);
+
+    // 挤出一个面：6 + 4 个侧面，仍然闭合
+    let extruded = extrude_faces(&cube, &[0], 0.5).unwrap();
+    let (topo, _) = editable_topology(&extruded);
+    assert_eq!(topo.faces.len(), 10);
+    assert!(topo.is_closed() && topo.is_manifold());
+
+    // 倒角一个面：内插面 + 4 个环面
+    let beveled = inset_faces(&cube, &[0], 0.25, 0.1).unwrap();
+    let (topo, _) = editable_topology(&beveled);
+    assert_eq!(topo.faces.len(), 10);
+    assert!(topo.is_closed());
+
+    // 环切穿过 4 个侧面，面数 6 -> 10
+    let (topo, _) = editable_topology(&cube);
+    let h = topo.faces[0].halfedge;
+    let edge = (topo.half_edges[h].origin, topo.dest(h));
+    let cut = loop_cut(&cube, edge, 0.5).unwrap();
+    let (topo, _) = editable_topology(&cut);
+    assert!(topo.is_closed() && topo.is_manifold());
+    assert_eq!(topo.euler_characteristic(), 2);
+
+    let deleted = delete_faces(&cube, &[0]).unwrap();
+    let (topo, _) = editable_topology(&deleted);
+    assert_eq!(topo.boundary_loops().len(), 1);
+}
@@ -0,0 +1,629 @@
+use std::collections::HashMap;
+
+use crate::geometry::shape::mesh::Mesh;
+use crate::physics::boundingbox::{AABB, BoundingVolume};
+
+/// 多边形的一个角：顶点索引 + 该角上的纹理坐标和法线
+///
+/// 纹理坐标和法线按角存储，焊接后接缝处的顶点共享拓扑但保留各自的属性。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Corner {
+    pub vertex: usize,
+    pub tex_coord: [f32; 2],
+    pub normal: [f32; 3],
+}
+
+#[derive(Clone, Debug)]
+pub struct HeVertex {
+    pub position: [f32; 3],
+    /// 任意一条以该顶点为起点的半边
+    pub halfedge: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HalfEdge {
+    pub origin: usize,
+    pub twin: Option<usize>,
+    pub next: usize,
+    pub prev: usize,
+    pub face: usize,
+    pub tex_coord: [f32; 2],
+    pub normal: [f32; 3],
+}
+
+#[derive(Clone, Debug)]
+pub struct HeFace {
+    pub halfedge: usize,
+}
+
+/// 半边网格，支持任意多边形面
+///
+/// 同一条无向边上超过两个面时，只配对前两条半边，其余视为边界并计入非流形边。
+#[derive(Clone, Debug, Default)]
+pub struct HalfEdgeMesh {
+    pub vertices: Vec<HeVertex>,
+    pub half_edges: Vec<HalfEdge>,
+    pub faces: Vec<HeFace>,
+    non_manifold_edges: Vec<(usize, usize)>,
+}
+
+/// 按位置焊接顶点，返回 (焊接后的位置, 原索引 -> 新索引)
+pub fn weld_positions(positions: &[[f32; 3]], epsilon: f32) -> (Vec<[f32; 3]>, Vec<usize>) {
+    let cell = epsilon.max(1e-7);
+    let key = |p: [f32; 3]| {
+        (
+            (p[0] / cell).floor() as i64,
+            (p[1] / cell).floor() as i64,
+            (p[2] / cell).floor() as i64,
+        )
+    };
+    let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+    let mut welded: Vec<[f32; 3]> = Vec::new();
+    let mut remap = Vec::with_capacity(positions.len());
+
+    for p in positions {
+        let (kx, ky, kz) = key(*p);
+        let mut found = None;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if let Some(list) = grid.get(&(kx + dx, ky + dy, kz + dz)) {
+                        for &w in list {
+                            let q = welded[w];
+                            let d2 = (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2);
+                            if d2 <= epsilon * epsilon {
+                                found = Some(w);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let idx = match found {
+            Some(w) => w,
+            None => {
+                welded.push(*p);
+                grid.entry((kx, ky, kz)).or_default().push(welded.len() - 1);
+                welded.len() - 1
+            }
+        };
+        remap.push(idx);
+    }
+    (welded, remap)
+}
+
+impl HalfEdgeMesh {
+    /// 从三角网格构建，`weld_epsilon` 内的顶点合并为同一拓扑顶点
+    pub fn from_mesh(mesh: &Mesh, weld_epsilon: f32) -> Self {
+        Self::from_mesh_welded(mesh, weld_epsilon).0
+    }
+
+    /// 同 `from_mesh`，额外返回 `Mesh` 顶点索引到拓扑顶点索引的映射
+    pub fn from_mesh_welded(mesh: &Mesh, weld_epsilon: f32) -> (Self, Vec<usize>) {
+        let (positions, remap) = weld_positions(&mesh.vertices, weld_epsilon);
+        let mut faces = Vec::with_capacity(mesh.indices.len() / 3);
+        for tri in mesh.indices.chunks(3) {
+            if tri.len() < 3 {
+                continue;
+            }
+            let face: Vec<Corner> = tri
+                .iter()
+                .map(|&i| Corner {
+                    vertex: remap[i as usize],
+                    tex_coord: mesh.tex_coords.get(i as usize).copied().unwrap_or([0.0, 0.0]),
+                    normal: mesh.normals.get(i as usize).copied().unwrap_or([0.0, 0.0, 0.0]),
+                })
+                .collect();
+            faces.push(face);
+        }
+        (Self::from_faces(positions, &faces), remap)
+    }
+
+    /// 从顶点位置和多边形面列表构建，退化面（少于 3 个不同顶点）会被跳过
+    pub fn from_faces(positions: Vec<[f32; 3]>, faces: &[Vec<Corner>]) -> Self {
+        let mut mesh = HalfEdgeMesh {
+            vertices: positions
+                .into_iter()
+                .map(|position| HeVertex {
+                    position,
+                    halfedge: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut directed: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+        for face in faces {
+            let mut corners: Vec<Corner> = Vec::with_capacity(face.len());
+            for c in face {
+                if corners.last().is_none_or(|l| l.vertex != c.vertex) {
+                    corners.push(*c);
+                }
+            }
+            while corners.len() > 1 && corners[0].vertex == corners[corners.len() - 1].vertex {
+                corners.pop();
+            }
+            if corners.len() < 3 {
+                continue;
+            }
+
+            let face_idx = mesh.faces.len();
+            let base = mesh.half_edges.len();
+            let n = corners.len();
+            for (k, c) in corners.iter().enumerate() {
+                mesh.half_edges.push(HalfEdge {
+                    origin: c.vertex,
+                    twin: None,
+                    next: base + (k + 1) % n,
+                    prev: base + (k + n - 1) % n,
+                    face: face_idx,
+                    tex_coord: c.tex_coord,
+                    normal: c.normal,
+                });
+                let v = &mut mesh.vertices[c.vertex];
+                if v.halfedge.is_none() {
+                    v.halfedge = Some(base + k);
+                }
+                directed
+                    .entry((c.vertex, corners[(k + 1) % n].vertex))
+                    .or_default()
+                    .push(base + k);
+            }
+            mesh.faces.push(HeFace { halfedge: base });
+        }
+
+        // 配对
+        let mut keys: Vec<(usize, usize)> = directed.keys().copied().collect();
+        keys.sort_unstable();
+        for (a, b) in keys {
+            if a > b {
+                continue;
+            }
+            let forward = directed.get(&(a, b)).cloned().unwrap_or_default();
+            let backward = directed.get(&(b, a)).cloned().unwrap_or_default();
+            if forward.len() + backward.len() > 2 {
+                mesh.non_manifold_edges.push((a, b));
+            }
+            if let (Some(&h0), Some(&h1)) = (forward.first(), backward.first()) {
+                mesh.half_edges[h0].twin = Some(h1);
+                mesh.half_edges[h1].twin = Some(h0);
+            }
+        }
+
+        // 顶点优先指向边界半边，便于遍历一环邻域
+        for h in 0..mesh.half_edges.len() {
+            if mesh.half_edges[h].twin.is_none() {
+                let v = mesh.half_edges[h].origin;
+                mesh.vertices[v].halfedge = Some(h);
+            }
+        }
+
+        mesh
+    }
+
+    pub fn dest(&self, h: usize) -> usize {
+        self.half_edges[self.half_edges[h].next].origin
+    }
+
+    /// 面的全部半边（按环绕顺序）
+    pub fn face_half_edges(&self, f: usize) -> Vec<usize> {
+        let start = self.faces[f].halfedge;
+        let mut result = vec![start];
+        let mut h = self.half_edges[start].next;
+        while h != start {
+            result.push(h);
+            h = self.half_edges[h].next;
+        }
+        result
+    }
+
+    pub fn face_vertices(&self, f: usize) -> Vec<usize> {
+        self.face_half_edges(f)
+            .into_iter()
+            .map(|h| self.half_edges[h].origin)
+            .collect()
+    }
+
+    /// 从顶点出发的所有半边（沿扇形遍历，非流形顶点只能遍历到其中一个扇）
+    pub fn outgoing(&self, v: usize) -> Vec<usize> {
+        let Some(start) = self.vertices[v].halfedge else {
+            return Vec::new();
+        };
+        let mut result = vec![start];
+        // 顶点指向边界半边时，沿 prev.twin 方向即可走完整个扇
+        let mut h = start;
+        loop {
+            let Some(t) = self.half_edges[self.half_edges[h].prev].twin else {
+                return result;
+            };
+            if t == start {
+                return result;
+            }
+            result.push(t);
+            h = t;
+        }
+    }
+
+    /// 一环邻居顶点
+    pub fn vertex_neighbors(&self, v: usize) -> Vec<usize> {
+        let mut result = Vec::new();
+        for h in self.outgoing(v) {
+            let d = self.dest(h);
+            if !result.contains(&d) {
+                result.push(d);
+            }
+            let p = self.half_edges[self.half_edges[h].prev].origin;
+            if !result.contains(&p) {
+                result.push(p);
+            }
+        }
+        result
+    }
+
+    pub fn vertex_faces(&self, v: usize) -> Vec<usize> {
+        self.outgoing(v)
+            .into_iter()
+            .map(|h| self.half_edges[h].face)
+            .collect()
+    }
+
+    /// 与面共享边的相邻面
+    pub fn face_neighbors(&self, f: usize) -> Vec<usize> {
+        self.face_half_edges(f)
+            .into_iter()
+            .filter_map(|h| self.half_edges[h].twin)
+            .map(|t| self.half_edges[t].face)
+            .collect()
+    }
+
+    pub fn is_boundary_edge(&self, h: usize) -> bool {
+        self.half_edges[h].twin.is_none()
+    }
+
+    pub fn is_boundary_vertex(&self, v: usize) -> bool {
+        self.vertices[v]
+            .halfedge
+            .is_some_and(|h| self.is_boundary_edge(h))
+    }
+
+    /// 所有边界环，每个环为按顺序排列的边界半边
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut by_dest: HashMap<usize, Vec<usize>> = HashMap::new();
+        for h in 0..self.half_edges.len() {
+            if self.is_boundary_edge(h) {
+                by_dest.entry(self.dest(h)).or_default().push(h);
+            }
+        }
+
+        let mut visited = vec![false; self.half_edges.len()];
+        let mut loops = Vec::new();
+        for start in 0..self.half_edges.len() {
+            if !self.is_boundary_edge(start) || visited[start] {
+                continue;
+            }
+            let mut current_loop = Vec::new();
+            let mut h = start;
+            loop {
+                visited[h] = true;
+                current_loop.push(h);
+                // 边界环沿面的反方向前进：下一条边界半边终止于当前半边的起点
+                let origin = self.half_edges[h].origin;
+                let next = by_dest
+                    .get(&origin)
+                    .and_then(|c| c.iter().copied().find(|&c| !visited[c]));
+                match next {
+                    Some(n) => h = n,
+                    None => break,
+                }
+            }
+            loops.push(current_loop);
+        }
+        loops
+    }
+
+    /// 无向边数
+    pub fn edge_count(&self) -> usize {
+        let paired = self.half_edges.iter().filter(|h| h.twin.is_some()).count();
+        let unpaired = self.half_edges.len() - paired;
+        paired / 2 + unpaired
+    }
+
+    /// 被面引用的顶点数
+    pub fn used_vertex_count(&self) -> usize {
+        self.vertices.iter().filter(|v| v.halfedge.is_some()).count()
+    }
+
+    /// 欧拉示性数 V - E + F
+    pub fn euler_characteristic(&self) -> i64 {
+        self.used_vertex_count() as i64 - self.edge_count() as i64 + self.faces.len() as i64
+    }
+
+    pub fn non_manifold_edges(&self) -> &[(usize, usize)] {
+        &self.non_manifold_edges
+    }
+
+    /// 扇形遍历无法覆盖全部相邻面的顶点（如两个锥尖相接）
+    pub fn non_manifold_vertices(&self) -> Vec<usize> {
+        let mut face_count = vec![0usize; self.vertices.len()];
+        for h in &self.half_edges {
+            face_count[h.origin] += 1;
+        }
+        (0..self.vertices.len())
+            .filter(|&v| face_count[v] > 0 && self.outgoing(v).len() != face_count[v])
+            .collect()
+    }
+
+    pub fn is_manifold(&self) -> bool {
+        self.non_manifold_edges.is_empty() && self.non_manifold_vertices().is_empty()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.half_edges.iter().all(|h| h.twin.is_some())
+    }
+
+    /// 面法线（Newell 方法，适用于非平面多边形）
+    pub fn face_normal(&self, f: usize) -> glam::Vec3 {
+        let verts = self.face_vertices(f);
+        let mut n = glam::Vec3::ZERO;
+        for i in 0..verts.len() {
+            let a = glam::Vec3::from(self.vertices[verts[i]].position);
+            let b = glam::Vec3::from(self.vertices[verts[(i + 1) % verts.len()]].position);
+            n += glam::vec3(
+                (a.y - b.y) * (a.z + b.z),
+                (a.z - b.z) * (a.x + b.x),
+                (a.x - b.x) * (a.y + b.y),
+            );
+        }
+        n.normalize_or_zero()
+    }
+
+    pub fn face_centroid(&self, f: usize) -> glam::Vec3 {
+        let verts = self.face_vertices(f);
+        let sum: glam::Vec3 = verts
+            .iter()
+            .map(|&v| glam::Vec3::from(self.vertices[v].position))
+            .sum();
+        sum / verts.len() as f32
+    }
+
+    /// 导出为按角展开的面列表，便于重新构建或编辑
+    pub fn to_faces(&self) -> Vec<Vec<Corner>> {
+        (0..self.faces.len())
+            .map(|f| {
+                self.face_half_edges(f)
+                    .into_iter()
+                    .map(|h| {
+                        let he = &self.half_edges[h];
+                        Corner {
+                            vertex: he.origin,
+                            tex_coord: he.tex_coord,
+                            normal: he.normal,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn positions(&self) -> Vec<[f32; 3]> {
+        self.vertices.iter().map(|v| v.position).collect()
+    }
+
+    /// 选择扇形三角化的起点，使最小三角形面积最大，避免边上插入的共线点产生退化三角形
+    fn fan_start(&self, hs: &[usize]) -> usize {
+        let n = hs.len();
+        if n <= 3 || n > 16 {
+            return 0;
+        }
+        let p = |k: usize| glam::Vec3::from(self.vertices[self.half_edges[hs[k % n]].origin].position);
+        let mut best = (0, f32::NEG_INFINITY);
+        for s in 0..n {
+            let min_area = (1..n - 1)
+                .map(|k| (p(s + k) - p(s)).cross(p(s + k + 1) - p(s)).length())
+                .fold(f32::INFINITY, f32::min);
+            if min_area > best.1 + 1e-9 {
+                best = (s, min_area);
+            }
+        }
+        best.0
+    }
+
+    /// 把共面且拼成凸四边形的相邻三角形合并为四边形，接缝（属性不同）处的边不合并
+    pub fn merge_coplanar_triangles(&self, angle_tolerance: f32) -> HalfEdgeMesh {
+        let cos_tol = angle_tolerance.cos();
+        let mut merged = vec![false; self.faces.len()];
+        let mut faces: Vec<Vec<Corner>> = Vec::with_capacity(self.faces.len());
+        let corner = |h: usize| {
+            let he = &self.half_edges[h];
+            Corner {
+                vertex: he.origin,
+                tex_coord: he.tex_coord,
+                normal: he.normal,
+            }
+        };
+
+        for f in 0..self.faces.len() {
+            if merged[f] {
+                continue;
+            }
+            let hs = self.face_half_edges(f);
+            if hs.len() != 3 {
+                merged[f] = true;
+                faces.push(hs.iter().map(|&h| corner(h)).collect());
+                continue;
+            }
+            let n_f = self.face_normal(f);
+            let mut quad = None;
+            // 优先跨最长边合并，三角化四边形时对角线通常是最长边
+            let mut candidates = hs.clone();
+            let edge_len = |h: usize| {
+                glam::Vec3::from(self.vertices[self.half_edges[h].origin].position)
+                    .distance(glam::Vec3::from(self.vertices[self.dest(h)].position))
+            };
+            candidates.sort_by(|&a, &b| edge_len(b).total_cmp(&edge_len(a)));
+            for &h in &candidates {
+                let Some(t) = self.half_edges[h].twin else {
+                    continue;
+                };
+                let g = self.half_edges[t].face;
+                if merged[g] || g == f || self.face_half_edges(g).len() != 3 {
+                    continue;
+                }
+                if n_f.dot(self.face_normal(g)) < cos_tol {
+                    continue;
+                }
+                // 共享边两端的属性必须一致
+                let same = |a: Corner, b: Corner| a.tex_coord == b.tex_coord && a.normal == b.normal;
+                let h_next = self.half_edges[h].next;
+                let t_next = self.half_edges[t].next;
+                if !same(corner(h), corner(t_next)) || !same(corner(h_next), corner(t)) {
+                    continue;
+                }
+                // f: a -> b -> c, g: b -> a -> d，合并为 a -> d -> b -> c
+                let d = self.half_edges[self.half_edges[t].next].next;
+                let c = self.half_edges[h_next].next;
+                let candidate = vec![corner(h), corner(d), corner(h_next), corner(c)];
+                let pos = |c: &Corner| glam::Vec3::from(self.vertices[c.vertex].position);
+                let convex = (0..4).all(|k| {
+                    let a = pos(&candidate[k]);
+                    let b = pos(&candidate[(k + 1) % 4]);
+                    let c = pos(&candidate[(k + 2) % 4]);
+                    (b - a).cross(c - b).dot(n_f) > 1e-8
+                });
+                if convex {
+                    quad = Some((g, candidate));
+                    break;
+                }
+            }
+            merged[f] = true;
+            match quad {
+                Some((g, candidate)) => {
+                    merged[g] = true;
+                    faces.push(candidate);
+                }
+                None => faces.push(hs.iter().map(|&h| corner(h)).collect()),
+            }
+        }
+
+        HalfEdgeMesh::from_faces(self.positions(), &faces)
+    }
+
+    /// 转回三角网格：多边形按扇形三角化，属性不同的角拆成不同的顶点；
+    /// 顶点数超出 u16 索引范围时返回 None
+    pub fn to_mesh(&self) -> Option<Mesh> {
+        let mut aabb = AABB::default();
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut tex_coords = Vec::new();
+        let mut indices = Vec::new();
+        let mut lookup: HashMap<(usize, [u32; 2], [u32; 3]), u16> = HashMap::new();
+
+        let mut corner_index = |he: &HalfEdge| -> u16 {
+            let key = (
+                he.origin,
+                he.tex_coord.map(f32::to_bits),
+                he.normal.map(f32::to_bits),
+            );
+            *lookup.entry(key).or_insert_with(|| {
+                let p = self.vertices[he.origin].position;
+                aabb.union_point_array(p);
+                vertices.push(p);
+                normals.push(he.normal);
+                tex_coords.push(he.tex_coord);
+                (vertices.len() - 1) as u16
+            })
+        };
+
+        for f in 0..self.faces.len() {
+            let mut hs = self.face_half_edges(f);
+            let start = self.fan_start(&hs);
+            hs.rotate_left(start);
+            let first = corner_index(&self.half_edges[hs[0]]);
+            for k in 1..hs.len() - 1 {
+                let b = corner_index(&self.half_edges[hs[k]]);
+                let c = corner_index(&self.half_edges[hs[k + 1]]);
+                indices.extend_from_slice(&[first, b, c]);
+            }
+        }
+        if vertices.len() > u16::MAX as usize {
+            return None;
+        }
+
+        Some(Mesh {
+            vertices,
+            normals,
+            tex_coords,
+            indices,
+            bounding_volume: BoundingVolume::AABB(aabb),
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
+        })
+    }
+}
+
+#[test]
+fn test_half_edge_cube_topology() {
+    use crate::geometry::shape::cube::Cube;
+    use crate::geometry::shape::mesh::AsMesh;
+
+    let mesh = Cube {
+        width: 1.0,
+        height: 1.0,
+        depth: 1.0,
+    }
+    .as_mesh();
+    let he = HalfEdgeMesh::from_mesh(&mesh, 1e-5);
+
+    assert_eq!(he.used_vertex_count(), 8);
+    assert_eq!(he.faces.len(), 12);
+    assert_eq!(he.edge_count(), 18);
+    assert_eq!(he.euler_characteristic(), 2);
+    assert!(he.is_manifold());
+    assert!(he.is_closed());
+    assert!(he.boundary_loops().is_empty());
+    for v in 0..8 {
+        let n = he.vertex_neighbors(v).len();
+        assert!(n == 4 || n == 5, "vertex {v} has {n} neighbours");
+    }
+
+    // 接缝处的法线/UV 在回转时保留，顶点数恢复为 24
+    let back = he.to_mesh().unwrap();
+    assert_eq!(back.vertices.len(), 24);
+    assert_eq!(back.indices.len(), mesh.indices.len());
+}
+
+#[test]
+fn test_half_edge_open_quad_boundary() {
+    let corner = |vertex| Corner {
+        vertex,
+        tex_coord: [0.0, 0.0],
+        normal: [0.0, 0.0, 1.0],
+    };
+    let positions = vec![
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [2.0, 1.0, 0.0],
+    ];
+    let faces = vec![
+        vec![corner(0), corner(1), corner(2), corner(3)],
+        vec![corner(1), corner(4), corner(5), corner(2)],
+    ];
+    let he = HalfEdgeMesh::from_faces(positions, &faces);
+
+    assert_eq!(he.edge_count(), 7);
+    assert_eq!(he.euler_characteristic(), 1);
+    assert!(he.is_manifold());
+    assert_eq!(he.face_neighbors(0), vec![1]);
+    let loops = he.boundary_loops();
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 6);
+    assert!(he.is_boundary_vertex(0));
+    let mut n = he.vertex_neighbors(1);
+    n.sort();
+    assert_eq!(n, vec![0, 2, 4]);
+}
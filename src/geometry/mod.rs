@@ -1 +1,11 @@
+pub mod csg;
+pub mod edit;
+pub mod halfedge;
+pub mod normals;
+pub mod repair;
 pub mod shape;
+pub mod simplify;
+pub mod subdivision;
+pub mod tangent;
+pub mod triangulation;
+pub mod uv;
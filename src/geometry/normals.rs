@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+use crate::geometry::halfedge::weld_positions;
+use crate::geometry::shape::mesh::Mesh;
+
+/// 面法线的加权方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum NormalWeighting {
+    Uniform,
+    /// 按三角形面积加权
+    Area,
+    /// 按顶点处的内角加权，不受三角剖分方式影响
+    #[default]
+    Angle,
+}
+
+/// 法线修改器：按平滑角度在硬边处拆分顶点
+#[derive(Clone, Debug, PartialEq)]
+pub struct NormalModifier {
+    /// 相邻面夹角超过该值（度）时视为硬边
+    pub crease_angle: f32,
+    pub weighting: NormalWeighting,
+    /// 网格带有 OBJ 平滑组时，只在同一平滑组内平滑
+    pub use_smoothing_groups: bool,
+}
+
+impl Default for NormalModifier {
+    fn default() -> Self {
+        Self {
+            crease_angle: 60.0,
+            weighting: NormalWeighting::Angle,
+            use_smoothing_groups: true,
+        }
+    }
+}
+
+impl NormalModifier {
+    pub fn apply(&self, mesh: &Mesh) -> Mesh {
+        let n = mesh.vertices.len();
+        let tris: Vec<[usize; 3]> = mesh
+            .indices
+            .chunks_exact(3)
+            .map(|c| [c[0] as usize, c[1] as usize, c[2] as usize])
+            .filter(|t| t.iter().all(|&i| i < n))
+            .collect();
+        let has_groups = mesh.smoothing_groups.len() == tris.len();
+        let groups = (self.use_smoothing_groups && has_groups).then_some(mesh.smoothing_groups.as_slice());
+
+        // 面法线与每个角的权重
+        let mut face_normals = Vec::with_capacity(tris.len());
+        let mut corner_weights = Vec::with_capacity(tris.len());
+        for t in &tris {
+            let p = t.map(|i| Vec3::from(mesh.vertices[i]));
+            let cross = (p[1] - p[0]).cross(p[2] - p[0]);
+            face_normals.push(cross.normalize_or_zero());
+            corner_weights.push(match self.weighting {
+                NormalWeighting::Uniform => [1.0; 3],
+                NormalWeighting::Area => [cross.length() * 0.5; 3],
+                NormalWeighting::Angle => std::array::from_fn(|k| {
+                    let e0 = p[(k + 1) % 3] - p[k];
+                    let e1 = p[(k + 2) % 3] - p[k];
+                    e0.angle_between(e1)
+                }),
+            });
+        }
+
+        // 按位置（而不是索引）收集共享顶点的角，UV 接缝两侧也能平滑
+        let (_, remap) = weld_positions(&mesh.vertices, 1e-5);
+        let mut corners: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        for (f, t) in tris.iter().enumerate() {
+            for (k, &v) in t.iter().enumerate() {
+                corners.entry(remap[v]).or_default().push((f, k));
+            }
+        }
+
+        let cos_crease = self.crease_angle.clamp(0.0, 180.0).to_radians().cos() - 1e-6;
+        let smooth_with = |f: usize, g: usize| {
+            if f == g {
+                return true;
+            }
+            if let Some(groups) = groups
+                && (groups[f] == 0 || groups[f] != groups[g])
+            {
+                return false;
+            }
+            face_normals[f].dot(face_normals[g]) >= cos_crease
+        };
+
+        // 每个角的法线相同才共享顶点，否则拆分
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut tex_coords = Vec::new();
+        let mut lookup: HashMap<(usize, [i32; 3]), u16> = HashMap::new();
+        let mut indices = Vec::with_capacity(tris.len() * 3);
+        for (f, t) in tris.iter().enumerate() {
+            for &v in t {
+                let mut sum = Vec3::ZERO;
+                for &(g, k) in &corners[&remap[v]] {
+                    if smooth_with(f, g) {
+                        sum += face_normals[g] * corner_weights[g][k];
+                    }
+                }
+                let normal = sum
+                    .try_normalize()
+                    .or(face_normals[f].try_normalize())
+                    .unwrap_or(Vec3::Y);
+                let key = (v, (normal * 1e4).round().as_ivec3().to_array());
+                let index = *lookup.entry(key).or_insert_with(|| {
+                    vertices.push(mesh.vertices[v]);
+                    normals.push(normal.to_array());
+                    tex_coords.push(mesh.tex_coords.get(v).copied().unwrap_or([0.0; 2]));
+                    (vertices.len() - 1) as u16
+                });
+                indices.push(index);
+            }
+        }
+
+        // 拆分后超出 u16 索引范围时退回到按索引平滑
+        if vertices.len() > u16::MAX as usize {
+            return self.apply_without_split(mesh, &tris, &face_normals, &corner_weights);
+        }
+
+        Mesh {
+            vertices,
+            normals,
+            tex_coords,
+            indices,
+            bounding_volume: mesh.bounding_volume.clone(),
+            tangents: Vec::new(),
+            smoothing_groups: if has_groups { mesh.smoothing_groups.clone() } else { Vec::new() },
+        }
+    }
+
+    fn apply_without_split(
+        &self,
+        mesh: &Mesh,
+        tris: &[[usize; 3]],
+        face_normals: &[Vec3],
+        corner_weights: &[[f32; 3]],
+    ) -> Mesh {
+        let mut sums = vec![Vec3::ZERO; mesh.vertices.len()];
+        for (f, t) in tris.iter().enumerate() {
+            for k in 0..3 {
+                sums[t[k]] += face_normals[f] * corner_weights[f][k];
+            }
+        }
+        let mut result = mesh.clone();
+        result.normals = sums
+            .into_iter()
+            .map(|s| s.normalize_or(Vec3::Y).to_array())
+            .collect();
+        result
+    }
+}
+
+/// 读取 OBJ 中的平滑组 (`s` 语句)，按三角化后的顺序给出每个三角形的组号
+///
+/// 只统计第一个对象；若与加载得到的三角形数不一致则返回空
+pub fn parse_obj_smoothing_groups(source: &str, triangle_count: usize) -> Vec<u32> {
+    let mut groups = Vec::with_capacity(triangle_count);
+    let mut current = 0;
+    for line in source.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("s") => {
+                current = match words.next() {
+                    Some("off") | None => 0,
+                    Some(id) => id.parse().unwrap_or(0),
+                };
+            }
+            Some("f") => {
+                let corners = words.count();
+                groups.extend(std::iter::repeat_n(current, corners.saturating_sub(2)));
+            }
+            Some("o") | Some("g") if !groups.is_empty() => break,
+            _ => {}
+        }
+    }
+    if groups.len() == triangle_count {
+        groups
+    } else {
+        Vec::new()
+    }
+}
+
+#[test]
+fn test_crease_angle_splits_cube() {
+    use crate::geometry::shape::cube::Cube;
+    use crate::geometry::shape::mesh::AsMesh;
+
+    // 先焊接成共享顶点的立方体，模拟缺少法线的导入模型
+    let mut mesh = Cube { width: 1.0, height: 1.0, depth: 1.0 }.as_mesh();
+    mesh.tex_coords.iter_mut().for_each(|t| *t = [0.0; 2]);
+    mesh.normals.iter_mut().for_each(|n| *n = [0.0; 3]);
+    mesh.weld(1e-5);
+    assert_eq!(mesh.vertices.len(), 8);
+
+    let hard = NormalModifier::default().apply(&mesh);
+    assert_eq!(hard.vertices.len(), 24);
+    for (t, chunk) in hard.indices.chunks(3).enumerate() {
+        let n = Vec3::from(hard.normals[chunk[0] as usize]);
+        assert!(chunk.iter().all(|&i| Vec3::from(hard.normals[i as usize]).abs_diff_eq(n, 1e-5)), "face {t}");
+        assert!((n.abs().max_element() - 1.0).abs() < 1e-5);
+    }
+
+    let soft = NormalModifier { crease_angle: 180.0, ..Default::default() }.apply(&mesh);
+    assert_eq!(soft.vertices.len(), 8);
+
+    // 平滑组 0 表示平直着色，即使平滑角度为 180° 也不平滑
+    let mut grouped = mesh.clone();
+    grouped.smoothing_groups = vec![0; mesh.indices.len() / 3];
+    let flat = NormalModifier { crease_angle: 180.0, ..Default::default() }.apply(&grouped);
+    assert_eq!(flat.vertices.len(), 24);
+}
@@ -0,0 +1,575 @@
+use std::collections::{HashMap, VecDeque};
+
+use glam::Vec3;
+
+use crate::geometry::halfedge::weld_positions;
+use crate::geometry::normals::NormalModifier;
+use crate::geometry::shape::mesh::{Mesh, compute_smooth_normals};
+use crate::physics::boundingbox::{AABB, BoundingVolume};
+
+/// 拓扑分析时合并顶点位置的容差
+const TOPOLOGY_EPSILON: f32 = 1e-5;
+/// 面积小于该值的三角形视为退化
+const DEGENERATE_AREA: f32 = 1e-12;
+/// 自动填补的孔洞最大边数
+pub const MAX_HOLE_EDGES: usize = 16;
+
+/// 网格检查报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshReport {
+    pub vertex_count: usize,
+    pub triangle_count: usize,
+    /// 索引数量不是 3 的倍数时多出的索引
+    pub dangling_indices: usize,
+    pub out_of_range_indices: usize,
+    pub normal_count: usize,
+    pub tex_coord_count: usize,
+    pub non_finite_positions: usize,
+    /// NaN 或长度为零的法线
+    pub invalid_normals: usize,
+    pub non_finite_tex_coords: usize,
+    pub degenerate_triangles: usize,
+    /// 位置与属性完全相同的重复顶点
+    pub duplicate_vertices: usize,
+    pub unused_vertices: usize,
+    pub non_manifold_edges: usize,
+    /// 每个孔洞（边界环）的边数
+    pub holes: Vec<usize>,
+    /// 相邻两面绕序相同的边
+    pub inconsistent_edges: usize,
+}
+
+impl MeshReport {
+    pub fn attributes_match(&self) -> bool {
+        self.normal_count == self.vertex_count && self.tex_coord_count == self.vertex_count
+    }
+
+    /// 可以安全地提交给渲染器
+    pub fn is_renderable(&self) -> bool {
+        self.attributes_match() && self.out_of_range_indices == 0 && self.dangling_indices == 0
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.is_renderable()
+            && self.non_finite_positions == 0
+            && self.invalid_normals == 0
+            && self.non_finite_tex_coords == 0
+            && self.degenerate_triangles == 0
+            && self.duplicate_vertices == 0
+            && self.unused_vertices == 0
+            && self.non_manifold_edges == 0
+            && self.holes.is_empty()
+            && self.inconsistent_edges == 0
+    }
+
+    /// 逐条列出发现的问题
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.dangling_indices > 0 {
+            issues.push(format!("索引数不是 3 的倍数 (多出 {})", self.dangling_indices));
+        }
+        if self.out_of_range_indices > 0 {
+            issues.push(format!("越界索引: {}", self.out_of_range_indices));
+        }
+        if !self.attributes_match() {
+            issues.push(format!(
+                "属性数量不一致: 顶点 {} / 法线 {} / UV {}",
+                self.vertex_count, self.normal_count, self.tex_coord_count
+            ));
+        }
+        if self.non_finite_positions > 0 {
+            issues.push(format!("非法顶点坐标 (NaN/Inf): {}", self.non_finite_positions));
+        }
+        if self.invalid_normals > 0 {
+            issues.push(format!("非法法线 (NaN/零长度): {}", self.invalid_normals));
+        }
+        if self.non_finite_tex_coords > 0 {
+            issues.push(format!("非法 UV: {}", self.non_finite_tex_coords));
+        }
+        if self.degenerate_triangles > 0 {
+            issues.push(format!("退化三角形: {}", self.degenerate_triangles));
+        }
+        if self.duplicate_vertices > 0 {
+            issues.push(format!("重复顶点: {}", self.duplicate_vertices));
+        }
+        if self.unused_vertices > 0 {
+            issues.push(format!("未使用顶点: {}", self.unused_vertices));
+        }
+        if self.non_manifold_edges > 0 {
+            issues.push(format!("非流形边: {}", self.non_manifold_edges));
+        }
+        if !self.holes.is_empty() {
+            issues.push(format!("孔洞: {} (边数 {:?})", self.holes.len(), self.holes));
+        }
+        if self.inconsistent_edges > 0 {
+            issues.push(format!("绕序不一致的边: {}", self.inconsistent_edges));
+        }
+        issues
+    }
+
+    pub fn ui(&self, ui: &imgui::Ui) {
+        ui.text(format!(
+            "顶点: {}  三角形: {}",
+            self.vertex_count, self.triangle_count
+        ));
+        let issues = self.issues();
+        if issues.is_empty() {
+            ui.text_colored([0.4, 1.0, 0.4, 1.0], "未发现问题");
+        }
+        for issue in issues {
+            ui.text_colored([1.0, 0.7, 0.3, 1.0], issue);
+        }
+    }
+}
+
+/// 一次修复各步骤处理的数量
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RepairStats {
+    pub attributes_fixed: usize,
+    pub degenerates_removed: usize,
+    pub vertices_welded: usize,
+    pub triangles_flipped: usize,
+    pub holes_filled: usize,
+}
+
+impl RepairStats {
+    pub fn ui(&self, ui: &imgui::Ui) {
+        ui.text(format!("修复属性: {}  删除退化面: {}", self.attributes_fixed, self.degenerates_removed));
+        ui.text(format!(
+            "焊接顶点: {}  翻转三角形: {}  填补孔洞: {}",
+            self.vertices_welded, self.triangles_flipped, self.holes_filled
+        ));
+    }
+}
+
+/// 有效的三角形：三个索引都在范围内
+fn triangles(mesh: &Mesh) -> impl Iterator<Item = [usize; 3]> + '_ {
+    let n = mesh.vertices.len();
+    mesh.indices
+        .chunks_exact(3)
+        .map(|c| [c[0] as usize, c[1] as usize, c[2] as usize])
+        .filter(move |t| t.iter().all(|&i| i < n))
+}
+
+fn triangle_area2(mesh: &Mesh, t: [usize; 3]) -> f32 {
+    let p = t.map(|i| Vec3::from(mesh.vertices[i]));
+    (p[1] - p[0]).cross(p[2] - p[0]).length_squared()
+}
+
+fn is_degenerate(mesh: &Mesh, t: [usize; 3]) -> bool {
+    let area2 = triangle_area2(mesh, t);
+    t[0] == t[1] || t[1] == t[2] || t[0] == t[2] || !area2.is_finite() || area2 <= DEGENERATE_AREA
+}
+
+fn finite<const N: usize>(v: &[f32; N]) -> bool {
+    v.iter().all(|x| x.is_finite())
+}
+
+/// 按位置合并后的有向边使用情况
+struct EdgeTopology {
+    /// 顶点到合并后位置编号的映射
+    remap: Vec<usize>,
+    /// 无向边 -> [(三角形, 是否与键方向相同)]
+    edges: HashMap<(usize, usize), Vec<(usize, bool)>>,
+    tris: Vec<[usize; 3]>,
+}
+
+impl EdgeTopology {
+    fn new(mesh: &Mesh) -> Self {
+        let (_, remap) = weld_positions(&mesh.vertices, TOPOLOGY_EPSILON);
+        let tris: Vec<[usize; 3]> = triangles(mesh).filter(|t| !is_degenerate(mesh, *t)).collect();
+        let mut edges: HashMap<(usize, usize), Vec<(usize, bool)>> = HashMap::new();
+        for (f, t) in tris.iter().enumerate() {
+            for k in 0..3 {
+                let a = remap[t[k]];
+                let b = remap[t[(k + 1) % 3]];
+                if a == b {
+                    continue;
+                }
+                edges.entry((a.min(b), a.max(b))).or_default().push((f, a < b));
+            }
+        }
+        Self { remap, edges, tris }
+    }
+
+    /// 边界环，元素为原始顶点索引，方向与填补用的三角形一致
+    fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        // 孔洞边方向与所在三角形的边相反
+        let mut next: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        for t in &self.tris {
+            for k in 0..3 {
+                let (va, vb) = (t[k], t[(k + 1) % 3]);
+                let (a, b) = (self.remap[va], self.remap[vb]);
+                if a == b {
+                    continue;
+                }
+                if self.edges[&(a.min(b), a.max(b))].len() == 1 {
+                    next.entry(b).or_default().push((a, vb));
+                }
+            }
+        }
+        let mut loops = Vec::new();
+        let mut starts: Vec<usize> = next.keys().copied().collect();
+        starts.sort_unstable();
+        for start in starts {
+            while let Some((mut cur, first_vertex)) = next.get_mut(&start).and_then(|v| v.pop()) {
+                let mut ring = vec![first_vertex];
+                let mut closed = false;
+                for _ in 0..self.remap.len() {
+                    if cur == start {
+                        closed = true;
+                        break;
+                    }
+                    let Some((to, vertex)) = next.get_mut(&cur).and_then(|v| v.pop()) else {
+                        break;
+                    };
+                    ring.push(vertex);
+                    cur = to;
+                }
+                if closed && ring.len() >= 3 {
+                    loops.push(ring);
+                }
+            }
+        }
+        loops
+    }
+}
+
+impl Mesh {
+    /// 检查网格的索引、属性与拓扑问题
+    pub fn validate(&self) -> MeshReport {
+        let n = self.vertices.len();
+        let mut report = MeshReport {
+            vertex_count: n,
+            triangle_count: self.indices.len() / 3,
+            dangling_indices: self.indices.len() % 3,
+            out_of_range_indices: self.indices.iter().filter(|&&i| i as usize >= n).count(),
+            normal_count: self.normals.len(),
+            tex_coord_count: self.tex_coords.len(),
+            non_finite_positions: self.vertices.iter().filter(|v| !finite(v)).count(),
+            invalid_normals: self
+                .normals
+                .iter()
+                .filter(|v| !finite(v) || Vec3::from(**v).length_squared() < 1e-12)
+                .count(),
+            non_finite_tex_coords: self.tex_coords.iter().filter(|v| !finite(v)).count(),
+            ..Default::default()
+        };
+
+        let mut used = vec![false; n];
+        for t in triangles(self) {
+            t.iter().for_each(|&i| used[i] = true);
+            if is_degenerate(self, t) {
+                report.degenerate_triangles += 1;
+            }
+        }
+        report.unused_vertices = used.iter().filter(|u| !**u).count();
+        report.duplicate_vertices = n - self.vertex_classes(1e-6).1;
+
+        let topo = EdgeTopology::new(self);
+        for uses in topo.edges.values() {
+            match uses.len() {
+                2 if uses[0].1 == uses[1].1 => report.inconsistent_edges += 1,
+                0..=2 => {}
+                _ => report.non_manifold_edges += 1,
+            }
+        }
+        report.holes = topo.boundary_loops().iter().map(Vec::len).collect();
+        report
+    }
+
+    /// 顶点和索引都有效时才可绘制
+    pub fn is_renderable(&self) -> bool {
+        let n = self.vertices.len();
+        self.normals.len() == n
+            && self.tex_coords.len() == n
+            && self.indices.len().is_multiple_of(3)
+            && self.indices.iter().all(|&i| (i as usize) < n)
+    }
+
+    /// 把位置与属性都相同的顶点归为一类，返回映射与类别数
+    fn vertex_classes(&self, epsilon: f32) -> (Vec<usize>, usize) {
+        let (_, pos_remap) = weld_positions(&self.vertices, epsilon);
+        let mut classes: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut remap = Vec::with_capacity(self.vertices.len());
+        let mut count = 0;
+        for (i, &p) in pos_remap.iter().enumerate() {
+            let candidates = classes.entry(p).or_default();
+            let same = candidates.iter().copied().find(|&j| {
+                let uv_eq = match (self.tex_coords.get(i), self.tex_coords.get(j)) {
+                    (Some(a), Some(b)) => (a[0] - b[0]).abs() <= epsilon && (a[1] - b[1]).abs() <= epsilon,
+                    _ => true,
+                };
+                let n_eq = match (self.normals.get(i), self.normals.get(j)) {
+                    (Some(a), Some(b)) => Vec3::from(*a).distance_squared(Vec3::from(*b)) <= 1e-6,
+                    _ => true,
+                };
+                uv_eq && n_eq
+            });
+            match same {
+                Some(j) => remap.push(remap[j]),
+                None => {
+                    candidates.push(i);
+                    remap.push(count);
+                    count += 1;
+                }
+            }
+        }
+        (remap, count)
+    }
+
+    pub fn update_bounding_volume(&mut self) {
+        let mut aabb = AABB::default();
+        for v in self.vertices.iter().filter(|v| finite(v)) {
+            aabb.union_point_array(*v);
+        }
+        self.bounding_volume = BoundingVolume::AABB(aabb);
+    }
+
+    /// 补齐缺失的法线/UV，清理 NaN，返回修改的属性数
+    pub fn fix_attributes(&mut self) -> usize {
+        let n = self.vertices.len();
+        let mut fixed = self.normals.len().abs_diff(n) + self.tex_coords.len().abs_diff(n);
+        self.normals.resize(n, [0.0; 3]);
+        self.tex_coords.resize(n, [0.0; 2]);
+        for uv in &mut self.tex_coords {
+            if !finite(uv) {
+                *uv = [0.0; 2];
+                fixed += 1;
+            }
+        }
+        let bad_normals = self
+            .normals
+            .iter()
+            .filter(|v| !finite(v) || Vec3::from(**v).length_squared() < 1e-12)
+            .count();
+        if bad_normals > 0 {
+            // 先清理坏三角形再重算，保证法线来自有效的面
+            let smooth = compute_smooth_normals(
+                &self.vertices,
+                &triangles(self).filter(|t| !is_degenerate(self, *t)).flatten().map(|i| i as u32).collect::<Vec<_>>(),
+            );
+            for (n, s) in self.normals.iter_mut().zip(smooth) {
+                if !finite(n) || Vec3::from(*n).length_squared() < 1e-12 {
+                    *n = s;
+                }
+            }
+            fixed += bad_normals;
+        }
+        fixed
+    }
+
+    /// 删除越界、退化或含非法坐标的三角形，返回删除的数量
+    pub fn remove_degenerates(&mut self) -> usize {
+        let n = self.vertices.len();
+        let keep: Vec<bool> = self
+            .indices
+            .chunks_exact(3)
+            .map(|c| {
+                let t = [c[0] as usize, c[1] as usize, c[2] as usize];
+                t.iter().all(|&i| i < n) && !is_degenerate(self, t)
+            })
+            .collect();
+        if self.smoothing_groups.len() == keep.len() {
+            self.smoothing_groups = self.smoothing_groups.iter().zip(&keep).filter(|(_, k)| **k).map(|(g, _)| *g).collect();
+        }
+        self.indices = self
+            .indices
+            .chunks_exact(3)
+            .zip(&keep)
+            .filter(|(_, k)| **k)
+            .flat_map(|(t, _)| t.iter().copied())
+            .collect();
+        keep.iter().filter(|k| !**k).count()
+    }
+
+    /// 合并重复顶点并删除未使用的顶点，返回减少的顶点数
+    pub fn weld(&mut self, epsilon: f32) -> usize {
+        let before = self.vertices.len();
+        let (classes, _) = self.vertex_classes(epsilon);
+        let mut new_index = vec![usize::MAX; classes.len()];
+        let mut order = Vec::new();
+        for &i in &self.indices {
+            let i = i as usize;
+            if i >= before {
+                continue;
+            }
+            let c = classes[i];
+            if new_index[c] == usize::MAX {
+                new_index[c] = order.len();
+                order.push(i);
+            }
+        }
+        self.indices = self
+            .indices
+            .chunks_exact(3)
+            .filter(|t| t.iter().all(|&i| (i as usize) < before))
+            .flatten()
+            .map(|&i| new_index[classes[i as usize]] as u16)
+            .collect();
+        self.vertices = order.iter().map(|&i| self.vertices[i]).collect();
+        self.normals = order.iter().map(|&i| self.normals.get(i).copied().unwrap_or([0.0; 3])).collect();
+        self.tex_coords = order.iter().map(|&i| self.tex_coords.get(i).copied().unwrap_or([0.0; 2])).collect();
+        self.update_bounding_volume();
+        before - self.vertices.len()
+    }
+
+    /// 沿流形边传播统一绕序，封闭连通块朝外，返回翻转的三角形数
+    pub fn unify_winding(&mut self) -> usize {
+        let topo = EdgeTopology::new(self);
+        let face_count = topo.tris.len();
+        let mut flip = vec![false; face_count];
+        let mut visited = vec![false; face_count];
+        let mut adjacency: Vec<Vec<(usize, bool)>> = vec![Vec::new(); face_count];
+        for uses in topo.edges.values() {
+            if let [(f0, d0), (f1, d1)] = uses[..] {
+                // 同向使用同一条边说明两面绕序相反
+                adjacency[f0].push((f1, d0 == d1));
+                adjacency[f1].push((f0, d0 == d1));
+            }
+        }
+
+        for seed in 0..face_count {
+            if visited[seed] {
+                continue;
+            }
+            visited[seed] = true;
+            let mut component = vec![seed];
+            let mut queue = VecDeque::from([seed]);
+            while let Some(f) = queue.pop_front() {
+                for &(g, opposite) in &adjacency[f] {
+                    if !visited[g] {
+                        visited[g] = true;
+                        flip[g] = flip[f] ^ opposite;
+                        component.push(g);
+                        queue.push_back(g);
+                    }
+                }
+            }
+
+            // 封闭连通块按有向体积判断整体朝向
+            let closed = component.iter().all(|&f| adjacency[f].len() == 3);
+            if closed {
+                let volume: f32 = component
+                    .iter()
+                    .map(|&f| {
+                        let p = topo.tris[f].map(|i| Vec3::from(self.vertices[i]));
+                        let v = p[0].dot(p[1].cross(p[2]));
+                        if flip[f] { -v } else { v }
+                    })
+                    .sum();
+                if volume < 0.0 {
+                    component.iter().for_each(|&f| flip[f] = !flip[f]);
+                }
+            }
+        }
+
+        let flipped = flip.iter().filter(|f| **f).count();
+        self.indices = topo
+            .tris
+            .iter()
+            .zip(&flip)
+            .flat_map(|(t, &f)| if f { [t[0], t[2], t[1]] } else { *t })
+            .map(|i| i as u16)
+            .collect();
+        flipped
+    }
+
+    /// 用扇形三角化填补边数不超过 `max_edges` 的孔洞，返回填补的数量
+    pub fn fill_holes(&mut self, max_edges: usize) -> usize {
+        let topo = EdgeTopology::new(self);
+        let track_groups = self.smoothing_groups.len() == self.indices.len() / 3;
+        let mut filled = 0;
+        for ring in topo.boundary_loops() {
+            if ring.len() > max_edges {
+                continue;
+            }
+            if ring.len() == 3 {
+                self.indices.extend(ring.iter().map(|&i| i as u16));
+                filled += 1;
+                continue;
+            }
+            if self.vertices.len() >= u16::MAX as usize {
+                break;
+            }
+            // 在环中心新增顶点，避免凹孔洞产生自交的扇形
+            let count = ring.len() as f32;
+            let center = ring.iter().map(|&i| Vec3::from(self.vertices[i])).sum::<Vec3>() / count;
+            let uv = ring.iter().fold([0.0; 2], |acc, &i| {
+                let t = self.tex_coords.get(i).copied().unwrap_or([0.0; 2]);
+                [acc[0] + t[0] / count, acc[1] + t[1] / count]
+            });
+            let c = self.vertices.len();
+            self.vertices.push(center.to_array());
+            self.tex_coords.push(uv);
+            self.normals.push([0.0; 3]);
+            let mut normal = Vec3::ZERO;
+            for k in 0..ring.len() {
+                let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+                self.indices.extend([a as u16, b as u16, c as u16]);
+                normal += (Vec3::from(self.vertices[b]) - Vec3::from(self.vertices[a]))
+                    .cross(center - Vec3::from(self.vertices[a]));
+            }
+            self.normals[c] = normal.normalize_or(Vec3::Y).to_array();
+            filled += 1;
+        }
+        // 补面不属于任何平滑组
+        if track_groups {
+            self.smoothing_groups.resize(self.indices.len() / 3, 0);
+        }
+        filled
+    }
+
+    /// 按默认平滑角度重新计算法线，硬边处拆分顶点
+    pub fn recompute_normals(&mut self) {
+        *self = NormalModifier::default().apply(self);
+    }
+
+    /// 依次执行全部修复步骤
+    pub fn repair(&mut self, max_hole_edges: usize) -> RepairStats {
+        let mut stats = RepairStats {
+            attributes_fixed: self.fix_attributes(),
+            degenerates_removed: self.remove_degenerates(),
+            ..Default::default()
+        };
+        stats.vertices_welded = self.weld(1e-6);
+        stats.triangles_flipped = self.unify_winding();
+        stats.holes_filled = self.fill_holes(max_hole_edges);
+        if stats.triangles_flipped > 0 || stats.holes_filled > 0 {
+            self.recompute_normals();
+        }
+        self.update_bounding_volume();
+        stats
+    }
+}
+
+#[test]
+fn test_repair_broken_cube() {
+    use crate::geometry::shape::cube::Cube;
+    use crate::geometry::shape::mesh::AsMesh;
+
+    let clean = Cube { width: 1.0, height: 1.0, depth: 1.0 }.as_mesh();
+    assert!(clean.validate().is_clean(), "{:?}", clean.validate().issues());
+
+    let mut mesh = clean.clone();
+    // 翻转一个三角形、删除一个三角形、加入退化三角形和 NaN 法线
+    mesh.indices.swap(1, 2);
+    mesh.indices.truncate(mesh.indices.len() - 3);
+    mesh.indices.extend([0, 0, 1]);
+    mesh.normals[0] = [f32::NAN; 3];
+    mesh.tex_coords.pop();
+
+    let report = mesh.validate();
+    assert!(!report.is_renderable());
+    assert_eq!(report.degenerate_triangles, 1);
+    assert_eq!(report.holes, vec![3]);
+    assert!(report.inconsistent_edges > 0);
+
+    let stats = mesh.repair(8);
+    assert_eq!(stats.degenerates_removed, 1);
+    assert_eq!(stats.holes_filled, 1);
+    let report = mesh.validate();
+    assert!(report.is_renderable());
+    assert!(report.holes.is_empty() && report.inconsistent_edges == 0, "{:?}", report.issues());
+    assert_eq!(report.triangle_count, 12);
+}
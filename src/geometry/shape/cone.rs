@@ -93,6 +93,8 @@ impl AsMesh for Cone {
             tex_coords,
             indices,
             bounding_volume:BoundingVolume::AABB(aabb),
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
         }
     }
 }
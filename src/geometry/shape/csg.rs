@@ -0,0 +1,130 @@
+use imgui::{Drag, TreeNodeFlags};
+
+use crate::core::math::transform::Transform;
+use crate::geometry::csg::{CsgOp, boolean};
+use crate::geometry::shape::mesh::{AsMesh, Mesh};
+use crate::geometry::subdivision::SubdivisionModifier;
+use crate::scene::world::EditableMesh;
+
+/// 布尔运算的一个操作数，保留原物体的形状与修改器
+pub struct CsgOperand {
+    pub name: String,
+    pub shape: Box<dyn EditableMesh>,
+    pub subdivision: Option<SubdivisionModifier>,
+    /// 相对 CSG 节点的变换
+    pub transform: Transform,
+}
+
+impl CsgOperand {
+    /// 形状加上细分修改器后参与运算的网格
+    pub fn shape_mesh(shape: &dyn EditableMesh, subdivision: Option<&SubdivisionModifier>) -> Mesh {
+        let mesh = shape.as_mesh();
+        match subdivision {
+            Some(modifier) => modifier.apply(&mesh),
+            None => mesh,
+        }
+    }
+
+    fn mesh(&self) -> Mesh {
+        Self::shape_mesh(&*self.shape, self.subdivision.as_ref())
+    }
+
+    fn ui(&mut self, ui: &imgui::Ui, id: &str) -> bool {
+        let mut changed = false;
+        let _id = ui.push_id(id);
+        let mut pos = self.transform.position.to_array();
+        if Drag::new("相对位置").speed(0.05).build_array(ui, &mut pos) {
+            self.transform.position = pos.into();
+            changed = true;
+        }
+        let mut scale = self.transform.scale.to_array();
+        if Drag::new("相对缩放").speed(0.01).build_array(ui, &mut scale) {
+            self.transform.scale = scale.into();
+            changed = true;
+        }
+        changed |= self.shape.ui(ui);
+        changed
+    }
+}
+
+/// 非破坏性 CSG 节点：保留两个操作数，参数变化时重新计算
+pub struct CsgNode {
+    pub op: CsgOp,
+    pub a: CsgOperand,
+    pub b: CsgOperand,
+    /// 最近一次成功的运算结果
+    mesh: Mesh,
+    /// 最近一次运算失败的原因，失败时保留上一次的结果
+    error: Option<String>,
+}
+
+impl CsgNode {
+    /// mesh 为调用者已算好的两操作数的运算结果
+    pub fn with_result(op: CsgOp, a: CsgOperand, b: CsgOperand, mesh: Mesh) -> Self {
+        Self { op, a, b, mesh, error: None }
+    }
+
+    /// 重新计算，返回结果是否更新
+    fn update(&mut self) -> bool {
+        match boolean(
+            &self.a.mesh(),
+            self.a.transform.get_matrix(),
+            &self.b.mesh(),
+            self.b.transform.get_matrix(),
+            self.op,
+        ) {
+            Ok(mesh) => {
+                self.mesh = mesh;
+                self.error = None;
+                true
+            }
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+}
+
+impl AsMesh for CsgNode {
+    fn as_mesh(&self) -> Mesh {
+        self.mesh.clone()
+    }
+}
+
+impl EditableMesh for CsgNode {
+    fn ui(&mut self, ui: &imgui::Ui) -> bool {
+        let mut changed = false;
+        ui.text("布尔运算 (CSG)");
+        for (i, (label, op)) in [
+            ("并集", CsgOp::Union),
+            ("差集", CsgOp::Difference),
+            ("交集", CsgOp::Intersection),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if i > 0 {
+                ui.same_line();
+            }
+            if ui.radio_button_bool(label, self.op == op) && self.op != op {
+                self.op = op;
+                changed = true;
+            }
+        }
+        if ui.button("交换操作数") {
+            std::mem::swap(&mut self.a, &mut self.b);
+            changed = true;
+        }
+        if ui.collapsing_header(format!("A: {}", self.a.name), TreeNodeFlags::empty()) {
+            changed |= self.a.ui(ui, "csg_a");
+        }
+        if ui.collapsing_header(format!("B: {}", self.b.name), TreeNodeFlags::empty()) {
+            changed |= self.b.ui(ui, "csg_b");
+        }
+        if let Some(error) = &self.error {
+            ui.text_colored([1.0, 0.4, 0.4, 1.0], error);
+        }
+        changed && self.update()
+    }
+}
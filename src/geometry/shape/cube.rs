@@ -123,6 +123,8 @@ impl AsMesh for Cube {
             tex_coords,
             indices,
             bounding_volume: BoundingVolume::AABB(aabb),
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
         }
     }
 }
@@ -113,6 +113,8 @@ impl AsMesh for Cylinder {
             tex_coords,
             indices,
             bounding_volume: BoundingVolume::AABB(aabb),
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
         }
     }
 }
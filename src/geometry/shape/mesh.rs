@@ -2,6 +2,9 @@ use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
+use crate::geometry::halfedge::HalfEdgeMesh;
+use crate::geometry::normals::{NormalModifier, parse_obj_smoothing_groups};
+use crate::geometry::repair::{MAX_HOLE_EDGES, MeshReport};
 use crate::physics::boundingbox::{AABB, BoundingVolume};
 use crate::scene::world::EditableMesh;
 
@@ -12,6 +15,10 @@ pub struct Mesh {
     pub tex_coords: Vec<[f32; 2]>,
     pub indices: Vec<u16>,
     pub bounding_volume: BoundingVolume,
+    /// 逐顶点切线，由 update_tangents 生成；为空表示尚未计算
+    pub tangents: Vec<[f32; 4]>,
+    /// 每个三角形的 OBJ 平滑组，0 表示不平滑；为空时不使用
+    pub smoothing_groups: Vec<u32>,
 }
 
 pub trait AsMesh {
@@ -24,10 +31,106 @@ impl AsMesh for Mesh {
     }
 }
 
-impl EditableMesh for Mesh {
+/// 拓扑统计，与检查报告一起缓存
+struct TopologyInfo {
+    vertices: usize,
+    edges: usize,
+    faces: usize,
+    euler: i64,
+    boundary_loops: usize,
+    manifold: bool,
+    non_manifold_edges: usize,
+}
+
+impl TopologyInfo {
+    fn new(mesh: &Mesh) -> Self {
+        let he = HalfEdgeMesh::from_mesh(mesh, 1e-5);
+        Self {
+            vertices: he.used_vertex_count(),
+            edges: he.edge_count(),
+            faces: he.faces.len(),
+            euler: he.euler_characteristic(),
+            boundary_loops: he.boundary_loops().len(),
+            manifold: he.is_manifold(),
+            non_manifold_edges: he.non_manifold_edges().len(),
+        }
+    }
+}
+
+/// 以网格本身为形状的物体 (导入或网格化后)，拓扑信息与检查报告在修复后才重新计算
+pub struct MeshShape {
+    pub mesh: Mesh,
+    info: Option<(TopologyInfo, MeshReport)>,
+}
+
+impl MeshShape {
+    pub fn new(mesh: Mesh) -> Self {
+        Self { mesh, info: None }
+    }
+}
+
+impl AsMesh for MeshShape {
+    fn as_mesh(&self) -> Mesh {
+        self.mesh.clone()
+    }
+}
+
+impl EditableMesh for MeshShape {
     fn ui(&mut self, ui: &imgui::Ui) -> bool {
-        ui.text("网格模型编辑能力受限。请在外部 3D 软件中修改后重新导入。");
-        false
+        let mesh = &self.mesh;
+        let (topology, report) = self.info.get_or_insert_with(|| (TopologyInfo::new(mesh), mesh.validate()));
+        if ui.collapsing_header("拓扑信息", imgui::TreeNodeFlags::empty()) {
+            ui.text(format!("V: {}  E: {}  F: {}", topology.vertices, topology.edges, topology.faces));
+            ui.text(format!("欧拉示性数: {}", topology.euler));
+            ui.text(format!("边界环: {}", topology.boundary_loops));
+            ui.text(format!(
+                "流形: {}  非流形边: {}",
+                if topology.manifold { "是" } else { "否" },
+                topology.non_manifold_edges
+            ));
+        }
+        let mut changed = false;
+        if ui.collapsing_header("网格检查与修复", imgui::TreeNodeFlags::empty()) {
+            report.ui(ui);
+            ui.separator();
+            let mesh = &mut self.mesh;
+            if ui.button("修复属性") {
+                mesh.fix_attributes();
+                changed = true;
+            }
+            ui.same_line();
+            if ui.button("删除退化面") {
+                mesh.remove_degenerates();
+                changed = true;
+            }
+            ui.same_line();
+            if ui.button("焊接顶点") {
+                mesh.weld(1e-6);
+                changed = true;
+            }
+            if ui.button("统一绕序") {
+                mesh.unify_winding();
+                changed = true;
+            }
+            ui.same_line();
+            if ui.button("填补小孔") {
+                mesh.fill_holes(MAX_HOLE_EDGES);
+                changed = true;
+            }
+            ui.same_line();
+            if ui.button("重算法线") {
+                mesh.recompute_normals();
+                changed = true;
+            }
+            if ui.button("一键修复") {
+                mesh.repair(MAX_HOLE_EDGES);
+                changed = true;
+            }
+        }
+        if changed {
+            self.info = None;
+        }
+        changed
     }
     fn intermediate_mesh(&self) -> bool {
         false
@@ -132,15 +235,10 @@ impl Mesh {
                 mesh.texcoords.chunks(2).map(|c| [c[0], c[1]]).collect()
             };
 
-            // 智能法线计算
-            let normals: Vec<[f32; 3]> = if mesh.normals.is_empty() {
-                println!("OBJ 模型缺少法线，正在自动计算平滑法线...");
-                compute_smooth_normals(&vertices, &mesh.indices)
-            } else {
-                mesh.normals.chunks(3).map(|c| [c[0], c[1], c[2]]).collect()
-            };
-
             let indices: Vec<u16> = mesh.indices.iter().map(|&i| i as u16).collect();
+            let smoothing_groups = std::fs::read_to_string(path)
+                .map(|source| parse_obj_smoothing_groups(&source, indices.len() / 3))
+                .unwrap_or_default();
 
             println!(
                 "Loaded OBJ: {} vertices, {} indices",
@@ -148,13 +246,22 @@ impl Mesh {
                 indices.len()
             );
 
-            Ok(Mesh {
+            let mut result = Mesh {
+                normals: mesh.normals.chunks(3).map(|c| [c[0], c[1], c[2]]).collect(),
                 vertices,
-                normals,
                 tex_coords,
                 indices,
                 bounding_volume : BoundingVolume::AABB(aabb),
-            })
+                tangents: Vec::new(),
+                smoothing_groups,
+            };
+
+            // 智能法线计算：按平滑角度和平滑组拆分硬边
+            if result.normals.is_empty() {
+                println!("OBJ 模型缺少法线，正在按平滑角度自动计算法线...");
+                result = NormalModifier::default().apply(&result);
+            }
+            Ok(result)
         } else {
             Err("OBJ file contains no models".to_string())
         }
@@ -182,7 +289,7 @@ impl Mesh {
 }
 
 // 辅助函数：计算平滑法线
-fn compute_smooth_normals(vertices: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+pub(crate) fn compute_smooth_normals(vertices: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
     let mut normals = vec![[0.0, 0.0, 0.0]; vertices.len()];
 
     for chunk in indices.chunks(3) {
@@ -343,6 +450,8 @@ fn test_compute_intersecting_face() {
             min: glam::f32::Vec3::from_array([0.0, 0.0, 0.0]),
             max: glam::f32::Vec3::from_array([1.0, 1.0, 1.0]),
         }),
+        tangents: Vec::new(),
+        smoothing_groups: Vec::new(),
     };
     let origin = [0.1, 0.1, -1.0];
     let direction = [0.0, 0.0, 1.0];
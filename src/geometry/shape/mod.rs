@@ -1,5 +1,6 @@
 pub mod cone;
 pub mod cube;
+pub mod csg;
 pub mod cylinder;
 pub mod mesh;
 pub mod nurbs;
@@ -1,5 +1,6 @@
 use crate::core::vertex::Vertex;
 use crate::geometry::shape::mesh::{AsMesh, Mesh};
+use crate::geometry::triangulation::{constrained_triangulate, distance_to_segment, point_in_polygon};
 use crate::physics::boundingbox::{AABB, BoundingVolume};
 use crate::scene::world::EditableMesh;
 use glutin::surface::WindowSurface;
@@ -48,6 +49,188 @@ pub fn generate_clamped_knots(n: usize, k: usize) -> Vec<f32> {
     knots
 }
 
+/// 参数域 (u, v) 中的一段裁剪曲线
+#[derive(Clone, Debug)]
+pub enum TrimCurve {
+    /// 折线
+    Polyline(Vec<[f32; 2]>),
+    /// 二维有理 B 样条曲线，按 samples 均匀采样
+    Nurbs {
+        control_points: Vec<[f32; 2]>,
+        weights: Vec<f32>,
+        degree: usize,
+        knots: Vec<f32>,
+        samples: usize,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrimKind {
+    /// 外边界：只保留环内部
+    Outer,
+    /// 洞：去掉环内部
+    Hole,
+}
+
+/// 由若干曲线首尾相接组成的闭合裁剪环
+#[derive(Clone, Debug)]
+pub struct TrimLoop {
+    pub curves: Vec<TrimCurve>,
+    pub kind: TrimKind,
+}
+
+impl TrimCurve {
+    fn sample(&self) -> Vec<[f32; 2]> {
+        match self {
+            TrimCurve::Polyline(points) => points.clone(),
+            TrimCurve::Nurbs {
+                control_points,
+                weights,
+                degree,
+                knots,
+                samples,
+            } => {
+                let knots = if knots.is_empty() {
+                    generate_clamped_knots(control_points.len(), *degree)
+                } else {
+                    knots.clone()
+                };
+                let samples = (*samples).max(2);
+                let mut points = Vec::with_capacity(samples + 1);
+                for s in 0..=samples {
+                    let t = (s as f32 / samples as f32).clamp(0.0, 0.9999);
+                    let mut p = [0.0, 0.0];
+                    let mut w_sum = 0.0;
+                    for (i, cp) in control_points.iter().enumerate() {
+                        let nw = b_spline_basis(i, *degree, t, &knots) * weights[i];
+                        p[0] += cp[0] * nw;
+                        p[1] += cp[1] * nw;
+                        w_sum += nw;
+                    }
+                    if w_sum.abs() > 1e-6 {
+                        p[0] /= w_sum;
+                        p[1] /= w_sum;
+                    }
+                    points.push(p);
+                }
+                points
+            }
+        }
+    }
+}
+
+impl TrimLoop {
+    pub fn rectangle(center: [f32; 2], half_size: [f32; 2], kind: TrimKind) -> Self {
+        let [cx, cy] = center;
+        let [hx, hy] = half_size;
+        Self {
+            curves: vec![TrimCurve::Polyline(vec![
+                [cx - hx, cy - hy],
+                [cx + hx, cy - hy],
+                [cx + hx, cy + hy],
+                [cx - hx, cy + hy],
+            ])],
+            kind,
+        }
+    }
+
+    /// 精确圆：9 个控制点的二次有理 B 样条
+    pub fn circle(center: [f32; 2], radius: f32, kind: TrimKind) -> Self {
+        let w = std::f32::consts::FRAC_1_SQRT_2;
+        let offsets = [
+            (1.0, 0.0, 1.0),
+            (1.0, 1.0, w),
+            (0.0, 1.0, 1.0),
+            (-1.0, 1.0, w),
+            (-1.0, 0.0, 1.0),
+            (-1.0, -1.0, w),
+            (0.0, -1.0, 1.0),
+            (1.0, -1.0, w),
+            (1.0, 0.0, 1.0),
+        ];
+        Self {
+            curves: vec![TrimCurve::Nurbs {
+                control_points: offsets
+                    .iter()
+                    .map(|o| [center[0] + o.0 * radius, center[1] + o.1 * radius])
+                    .collect(),
+                weights: offsets.iter().map(|o| o.2).collect(),
+                degree: 2,
+                knots: vec![0.0, 0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.75, 0.75, 1.0, 1.0, 1.0],
+                samples: 48,
+            }],
+            kind,
+        }
+    }
+
+    /// 采样成闭合折线（不重复首点），并细分过长的线段
+    pub fn polyline(&self, max_segment: f32) -> Vec<[f32; 2]> {
+        let mut raw: Vec<[f32; 2]> = Vec::new();
+        for curve in &self.curves {
+            for p in curve.sample() {
+                let p = [p[0].clamp(0.0, 1.0), p[1].clamp(0.0, 1.0)];
+                if raw.last().is_none_or(|q| (q[0] - p[0]).abs() + (q[1] - p[1]).abs() > 1e-5) {
+                    raw.push(p);
+                }
+            }
+        }
+        while raw.len() > 1 {
+            let (first, last) = (raw[0], raw[raw.len() - 1]);
+            if (first[0] - last[0]).abs() + (first[1] - last[1]).abs() > 1e-5 {
+                break;
+            }
+            raw.pop();
+        }
+
+        let mut points = Vec::new();
+        for i in 0..raw.len() {
+            let a = raw[i];
+            let b = raw[(i + 1) % raw.len()];
+            let len = ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt();
+            let pieces = (len / max_segment).ceil().max(1.0) as usize;
+            for k in 0..pieces {
+                let t = k as f32 / pieces as f32;
+                points.push([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]);
+            }
+        }
+        points
+    }
+
+    fn translate(&mut self, delta: [f32; 2]) {
+        for curve in &mut self.curves {
+            let points = match curve {
+                TrimCurve::Polyline(points) => points,
+                TrimCurve::Nurbs { control_points, .. } => control_points,
+            };
+            for p in points {
+                p[0] += delta[0];
+                p[1] += delta[1];
+            }
+        }
+    }
+}
+
+/// 判断参数点是否位于裁剪后的有效区域内
+fn inside_trimmed_region(p: [f32; 2], loops: &[(TrimKind, Vec<[f32; 2]>)]) -> bool {
+    let mut has_outer = false;
+    let mut in_outer = false;
+    for (kind, polygon) in loops {
+        let inside = point_in_polygon(p, polygon);
+        match kind {
+            TrimKind::Outer => {
+                has_outer = true;
+                in_outer |= inside;
+            }
+            TrimKind::Hole => {
+                if inside {
+                    return false;
+                }
+            }
+        }
+    }
+    !has_outer || in_outer
+}
+
 pub struct NurbsSurface {
     pub control_points: Vec<[f32; 3]>,
     pub weights: Vec<f32>,
@@ -58,32 +241,190 @@ pub struct NurbsSurface {
     pub selected_point_idx: usize,
     pub u_knots: Vec<f32>,
     pub v_knots: Vec<f32>,
+    pub trims: Vec<TrimLoop>,
+    pub selected_trim_idx: usize,
 }
 
-impl AsMesh for NurbsSurface {
-    fn as_mesh(&self) -> Mesh {
-        let mut aabb = AABB::default();
-        let mut vertices = Vec::new();
-        let mut normals = Vec::new();
-        let mut tex_coords = Vec::new();
-        let mut indices = Vec::new();
-
-        let k = self.degree;
-        let n_u = self.u_count;
-        let n_v = self.v_count;
-
-        // 1. 准备节点向量
+impl NurbsSurface {
+    fn knot_vectors(&self) -> (Vec<f32>, Vec<f32>) {
         let u_knots = if !self.u_knots.is_empty() {
             self.u_knots.clone()
         } else {
-            generate_clamped_knots(n_u, k)
+            generate_clamped_knots(self.u_count, self.degree)
         };
 
         let v_knots = if !self.v_knots.is_empty() {
             self.v_knots.clone()
         } else {
-            generate_clamped_knots(n_v, k)
+            generate_clamped_knots(self.v_count, self.degree)
         };
+        (u_knots, v_knots)
+    }
+
+    /// 计算参数 (u, v) 处的曲面点
+    pub fn evaluate(&self, u: f32, v: f32, u_knots: &[f32], v_knots: &[f32]) -> [f32; 3] {
+        let k = self.degree;
+        let u = u.clamp(0.0, 0.9999);
+        let v = v.clamp(0.0, 0.9999);
+
+        let mut point = [0.0, 0.0, 0.0];
+        let mut rational_weight = 0.0;
+
+        for row in 0..self.v_count {
+            let basis_v = b_spline_basis(row, k, v, v_knots);
+            if basis_v == 0.0 {
+                continue;
+            }
+            for col in 0..self.u_count {
+                let idx = row * self.u_count + col;
+                // 计算基函数值
+                let nip = b_spline_basis(col, k, u, u_knots) * basis_v;
+                let w = self.weights[idx];
+
+                // 有理 B 样条公式
+                point[0] += self.control_points[idx][0] * nip * w;
+                point[1] += self.control_points[idx][1] * nip * w;
+                point[2] += self.control_points[idx][2] * nip * w;
+                rational_weight += nip * w;
+            }
+        }
+
+        // 透视除法
+        if rational_weight.abs() > 1e-6 {
+            point[0] /= rational_weight;
+            point[1] /= rational_weight;
+            point[2] /= rational_weight;
+        }
+        point
+    }
+
+    /// 带裁剪环的细分：网格点 + 裁剪环采样点做约束三角化，再剔除区域外的三角形
+    fn as_trimmed_mesh(&self) -> Mesh {
+        let (u_knots, v_knots) = self.knot_vectors();
+        let step = 1.0 / self.splits.max(1) as f32;
+
+        let loops: Vec<(TrimKind, Vec<[f32; 2]>)> = self
+            .trims
+            .iter()
+            .map(|t| (t.kind, t.polyline(step)))
+            .filter(|(_, p)| p.len() >= 3)
+            .collect();
+
+        // 1. 参数域采样点，去掉离裁剪边太近的网格点以免出现细长三角形
+        let mut uv_points: Vec<[f32; 2]> = Vec::new();
+        for i in 0..=self.splits {
+            for j in 0..=self.splits {
+                let p = [i as f32 * step, j as f32 * step];
+                let near_trim = loops.iter().any(|(_, poly)| {
+                    (0..poly.len())
+                        .any(|k| distance_to_segment(p, poly[k], poly[(k + 1) % poly.len()]) < 0.35 * step)
+                });
+                if !near_trim {
+                    uv_points.push(p);
+                }
+            }
+        }
+
+        let mut constraints = Vec::new();
+        for (_, poly) in &loops {
+            let base = uv_points.len();
+            uv_points.extend_from_slice(poly);
+            for k in 0..poly.len() {
+                constraints.push((base + k, base + (k + 1) % poly.len()));
+            }
+        }
+
+        // 2. 约束三角化并按区域筛选
+        let triangles: Vec<[usize; 3]> = constrained_triangulate(&uv_points, &constraints)
+            .into_iter()
+            .filter(|t| {
+                let c = [
+                    (uv_points[t[0]][0] + uv_points[t[1]][0] + uv_points[t[2]][0]) / 3.0,
+                    (uv_points[t[0]][1] + uv_points[t[1]][1] + uv_points[t[2]][1]) / 3.0,
+                ];
+                inside_trimmed_region(c, &loops)
+            })
+            .collect();
+
+        // 3. 只保留被引用的点，映射到曲面上
+        let mut aabb = AABB::default();
+        let mut remap = vec![usize::MAX; uv_points.len()];
+        let mut vertices = Vec::new();
+        let mut tex_coords = Vec::new();
+        let mut indices = Vec::new();
+        for t in &triangles {
+            for &idx in t {
+                if remap[idx] == usize::MAX {
+                    remap[idx] = vertices.len();
+                    let [u, v] = uv_points[idx];
+                    let point = self.evaluate(u, v, &u_knots, &v_knots);
+                    aabb.union_point_array(point);
+                    vertices.push(point);
+                    tex_coords.push([u.clamp(0.0, 0.9999), 1.0 - v.clamp(0.0, 0.9999)]);
+                }
+                indices.push(remap[idx] as u16);
+            }
+        }
+
+        let mut normals = vec![[0.0, 0.0, 0.0]; vertices.len()];
+        for tri in indices.chunks(3) {
+            let p0 = vertices[tri[0] as usize];
+            let p1 = vertices[tri[1] as usize];
+            let p2 = vertices[tri[2] as usize];
+            let edge_u = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+            let edge_v = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+            let normal = [
+                edge_u[1] * edge_v[2] - edge_u[2] * edge_v[1],
+                edge_u[2] * edge_v[0] - edge_u[0] * edge_v[2],
+                edge_u[0] * edge_v[1] - edge_u[1] * edge_v[0],
+            ];
+            for &idx in tri {
+                normals[idx as usize][0] += normal[0];
+                normals[idx as usize][1] += normal[1];
+                normals[idx as usize][2] += normal[2];
+            }
+        }
+        normalize_normals(&mut normals);
+
+        Mesh {
+            vertices,
+            normals,
+            tex_coords,
+            indices,
+            bounding_volume: BoundingVolume::AABB(aabb),
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
+        }
+    }
+}
+
+fn normalize_normals(normals: &mut [[f32; 3]]) {
+    for n in normals {
+        let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        if len > 1e-6 {
+            n[0] /= len;
+            n[1] /= len;
+            n[2] /= len;
+        } else {
+            *n = [0.0, 1.0, 0.0];
+        }
+    }
+}
+
+impl AsMesh for NurbsSurface {
+    fn as_mesh(&self) -> Mesh {
+        if !self.trims.is_empty() {
+            return self.as_trimmed_mesh();
+        }
+
+        let mut aabb = AABB::default();
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut tex_coords = Vec::new();
+        let mut indices = Vec::new();
+
+        // 1. 准备节点向量
+        let (u_knots, v_knots) = self.knot_vectors();
 
         let step = 1.0 / self.splits as f32;
 
@@ -93,31 +434,7 @@ impl AsMesh for NurbsSurface {
             for j in 0..=self.splits {
                 let v = (j as f32 * step).clamp(0.0, 0.9999);
 
-                let mut point = [0.0, 0.0, 0.0];
-                let mut rational_weight = 0.0;
-
-                for row in 0..n_v {
-                    for col in 0..n_u {
-                        let idx = row * n_u + col;
-                        // 计算基函数值
-                        let nip = b_spline_basis(col, k, u, &u_knots)
-                            * b_spline_basis(row, k, v, &v_knots);
-                        let w = self.weights[idx];
-
-                        // 有理 B 样条公式
-                        point[0] += self.control_points[idx][0] * nip * w;
-                        point[1] += self.control_points[idx][1] * nip * w;
-                        point[2] += self.control_points[idx][2] * nip * w;
-                        rational_weight += nip * w;
-                    }
-                }
-
-                // 透视除法
-                if rational_weight.abs() > 1e-6 {
-                    point[0] /= rational_weight;
-                    point[1] /= rational_weight;
-                    point[2] /= rational_weight;
-                }
+                let point = self.evaluate(u, v, &u_knots, &v_knots);
 
                 vertices.push(point);
                 aabb.union_point_array(point);
@@ -166,16 +483,7 @@ impl AsMesh for NurbsSurface {
         }
 
         // 4. 归一化法线
-        for n in &mut normals {
-            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
-            if len > 1e-6 {
-                n[0] /= len;
-                n[1] /= len;
-                n[2] /= len;
-            } else {
-                *n = [0.0, 1.0, 0.0];
-            }
-        }
+        normalize_normals(&mut normals);
 
         Mesh {
             vertices,
@@ -183,6 +491,8 @@ impl AsMesh for NurbsSurface {
             tex_coords,
             indices,
             bounding_volume: BoundingVolume::AABB(aabb),
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
         }
     }
 }
@@ -215,6 +525,54 @@ impl EditableMesh for NurbsSurface {
             let w = &mut self.weights[self.selected_point_idx];
             changed |= Drag::new("权重").range(0.1, 100.0).speed(0.01).build(ui, w);
         }
+
+        ui.separator();
+        ui.text(format!("裁剪环数: {}", self.trims.len()));
+        if ui.button("添加矩形洞") {
+            self.trims
+                .push(TrimLoop::rectangle([0.5, 0.5], [0.2, 0.15], TrimKind::Hole));
+            self.selected_trim_idx = self.trims.len() - 1;
+            changed = true;
+        }
+        ui.same_line();
+        if ui.button("添加圆形洞") {
+            self.trims
+                .push(TrimLoop::circle([0.5, 0.5], 0.2, TrimKind::Hole));
+            self.selected_trim_idx = self.trims.len() - 1;
+            changed = true;
+        }
+
+        if !self.trims.is_empty() {
+            ui.slider(
+                "裁剪环索引",
+                0,
+                self.trims.len() - 1,
+                &mut self.selected_trim_idx,
+            );
+            let idx = self.selected_trim_idx.min(self.trims.len() - 1);
+            let trim = &mut self.trims[idx];
+
+            let mut is_outer = trim.kind == TrimKind::Outer;
+            if ui.checkbox("作为外边界", &mut is_outer) {
+                trim.kind = if is_outer { TrimKind::Outer } else { TrimKind::Hole };
+                changed = true;
+            }
+
+            let mut delta = [0.0f32; 2];
+            if Drag::new("平移 (U/V)")
+                .speed(0.005)
+                .build_array(ui, &mut delta)
+            {
+                trim.translate(delta);
+                changed = true;
+            }
+
+            if ui.button("删除裁剪环") {
+                self.trims.remove(idx);
+                self.selected_trim_idx = idx.saturating_sub(1);
+                changed = true;
+            }
+        }
         changed
     }
 
@@ -232,6 +590,7 @@ impl EditableMesh for NurbsSurface {
                 position: *v,
                 tex_coord: [0.0; 2],
                 normal: [0.0; 3],
+                tangent: [1.0, 0.0, 0.0, 1.0],
             })
             .collect();
 
@@ -241,3 +600,46 @@ impl EditableMesh for NurbsSurface {
         ))
     }
 }
+
+#[test]
+fn test_trimmed_surface_drops_hole() {
+    let mut control_points = Vec::new();
+    for row in 0..4 {
+        for col in 0..4 {
+            control_points.push([col as f32, 0.0, row as f32]);
+        }
+    }
+    let mut surface = NurbsSurface {
+        control_points,
+        weights: vec![1.0; 16],
+        u_count: 4,
+        v_count: 4,
+        degree: 3,
+        splits: 16,
+        selected_point_idx: 0,
+        u_knots: vec![],
+        v_knots: vec![],
+        trims: vec![],
+        selected_trim_idx: 0,
+    };
+    let full = surface.as_mesh();
+
+    surface
+        .trims
+        .push(TrimLoop::circle([0.5, 0.5], 0.25, TrimKind::Hole));
+    let trimmed = surface.as_mesh();
+    assert!(trimmed.indices.len() < full.indices.len());
+
+    // 洞中心不应再被射线击中，也不应残留未引用的顶点
+    assert!(
+        trimmed
+            .compute_intersecting_face([1.5, 1.0, 1.5], [0.0, -1.0, 0.0])
+            .is_none()
+    );
+    assert!(full.compute_intersecting_face([1.5, 1.0, 1.5], [0.0, -1.0, 0.0]).is_some());
+    let mut used = vec![false; trimmed.vertices.len()];
+    for &i in &trimmed.indices {
+        used[i as usize] = true;
+    }
+    assert!(used.iter().all(|&u| u));
+}
@@ -60,6 +60,8 @@ impl AsMesh for Sphere {
             tex_coords,
             indices,
             bounding_volume,
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
         }
     }
 }
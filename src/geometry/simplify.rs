@@ -0,0 +1,526 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use glam::{DMat4, DVec3, DVec4, Vec3};
+
+use crate::geometry::halfedge::weld_positions;
+use crate::geometry::shape::mesh::Mesh;
+use crate::physics::boundingbox::{AABB, BoundingBox, BoundingVolume};
+
+// 二次误差度量 (QEM) 网格简化
+//
+// 使用半边折叠（顶点折叠到相邻顶点上），不产生新顶点，因此 UV 与法线可以直接沿用。
+// UV 接缝与边界上的边额外加入垂直约束平面，折叠时要求接缝两侧的属性能一一对应，否则拒绝。
+
+const WELD_EPSILON: f32 = 1e-5;
+/// 超过该三角形数的导入模型自动生成 LOD
+pub const AUTO_LOD_TRIANGLES: usize = 2000;
+/// 边界 / 接缝约束平面的权重
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SimplifyTarget {
+    /// 目标三角形数
+    Triangles(usize),
+    /// 允许的几何误差（距离）：折叠后顶点到原表面各平面的加权均方根距离不超过该值，
+    /// 边界与 UV 接缝的约束平面权重更大
+    Error(f32),
+}
+
+/// 二次误差矩阵与累计的平面权重，误差除以权重即为平方距离
+#[derive(Clone, Copy)]
+struct Quadric(DMat4, f64);
+
+impl Quadric {
+    fn zero() -> Self {
+        Quadric(DMat4::ZERO, 0.0)
+    }
+
+    fn from_plane(n: DVec3, p: DVec3, weight: f64) -> Self {
+        let plane = DVec4::new(n.x, n.y, n.z, -n.dot(p));
+        let m = DMat4::from_cols(
+            plane * plane.x,
+            plane * plane.y,
+            plane * plane.z,
+            plane * plane.w,
+        );
+        Quadric(m * weight, weight)
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        self.0 += other.0;
+        self.1 += other.1;
+    }
+
+    fn error(&self, p: DVec3) -> f64 {
+        let v = p.extend(1.0);
+        v.dot(self.0 * v).max(0.0)
+    }
+
+    /// 到各平面的加权平均平方距离
+    fn squared_distance(&self, p: DVec3) -> f64 {
+        if self.1 > 0.0 { self.error(p) / self.1 } else { 0.0 }
+    }
+}
+
+struct Candidate {
+    cost: f64,
+    /// 平方距离，与 SimplifyTarget::Error 比较
+    distance: f64,
+    from: usize,
+    to: usize,
+    stamp: (u32, u32),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Candidate {
+    // 小顶堆；代价相同时按索引排序保证结果确定
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.from.cmp(&self.from))
+            .then_with(|| other.to.cmp(&self.to))
+    }
+}
+
+struct Simplifier<'a> {
+    mesh: &'a Mesh,
+    /// 属性顶点 -> 焊接顶点
+    weld: Vec<usize>,
+    positions: Vec<DVec3>,
+    /// 三角形的角存属性顶点索引
+    triangles: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    vertex_triangles: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    boundary: Vec<bool>,
+    stamps: Vec<u32>,
+    alive_count: usize,
+}
+
+impl<'a> Simplifier<'a> {
+    fn new(mesh: &'a Mesh) -> Self {
+        let (welded, weld) = weld_positions(&mesh.vertices, WELD_EPSILON);
+        let positions: Vec<DVec3> = welded.iter().map(|p| Vec3::from(*p).as_dvec3()).collect();
+        let triangles: Vec<[usize; 3]> = mesh
+            .indices
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .filter(|t| weld[t[0]] != weld[t[1]] && weld[t[1]] != weld[t[2]] && weld[t[2]] != weld[t[0]])
+            .collect();
+
+        let mut vertex_triangles = vec![Vec::new(); positions.len()];
+        let mut quadrics = vec![Quadric::zero(); positions.len()];
+        for (t, tri) in triangles.iter().enumerate() {
+            let [a, b, c] = tri.map(|i| weld[i]);
+            for v in [a, b, c] {
+                vertex_triangles[v].push(t);
+            }
+            let cross = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+            let area = cross.length() * 0.5;
+            if area > 1e-12 {
+                let q = Quadric::from_plane(cross.normalize(), positions[a], area);
+                for v in [a, b, c] {
+                    quadrics[v].add(&q);
+                }
+            }
+        }
+
+        let mut s = Self {
+            mesh,
+            weld,
+            positions,
+            alive: vec![true; triangles.len()],
+            alive_count: triangles.len(),
+            triangles,
+            vertex_triangles,
+            quadrics,
+            boundary: vec![],
+            stamps: vec![],
+        };
+        s.stamps = vec![0; s.positions.len()];
+        s.boundary = vec![false; s.positions.len()];
+        s.add_constraint_planes();
+        s
+    }
+
+    /// 边界边与 UV 接缝边加入垂直于表面的约束平面
+    fn add_constraint_planes(&mut self) {
+        // 无向边 -> [(三角形, 起点属性, 终点属性)]
+        type EdgeUses = Vec<(usize, usize, usize)>;
+        let mut edges: HashMap<(usize, usize), EdgeUses> = HashMap::new();
+        for (t, tri) in self.triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                let (wa, wb) = (self.weld[a], self.weld[b]);
+                // 属性按焊接索引从小到大排列，便于比较两侧
+                let (lo, hi) = if wa < wb { (a, b) } else { (b, a) };
+                edges.entry((wa.min(wb), wa.max(wb))).or_default().push((t, lo, hi));
+            }
+        }
+        let mut keys: Vec<(usize, usize)> = edges.keys().copied().collect();
+        keys.sort_unstable();
+        for key in keys {
+            let list = &edges[&key];
+            let seam = list.len() == 2
+                && (!self.same_attributes(list[0].1, list[1].1)
+                    || !self.same_attributes(list[0].2, list[1].2));
+            if list.len() == 2 && !seam {
+                continue;
+            }
+            let (a, b) = key;
+            self.boundary[a] = true;
+            self.boundary[b] = true;
+            let (pa, pb) = (self.positions[a], self.positions[b]);
+            let edge = pb - pa;
+            for &(t, _, _) in list {
+                let n = self.triangle_normal(t);
+                let side = edge.cross(n);
+                if side.length_squared() > 1e-20 {
+                    let q = Quadric::from_plane(
+                        side.normalize(),
+                        pa,
+                        BOUNDARY_WEIGHT * edge.length_squared(),
+                    );
+                    self.quadrics[a].add(&q);
+                    self.quadrics[b].add(&q);
+                }
+            }
+        }
+    }
+
+    fn same_attributes(&self, a: usize, b: usize) -> bool {
+        a == b
+            || (self.mesh.tex_coords.get(a) == self.mesh.tex_coords.get(b)
+                && self.mesh.normals.get(a) == self.mesh.normals.get(b))
+    }
+
+    fn triangle_normal(&self, t: usize) -> DVec3 {
+        let [a, b, c] = self.triangles[t].map(|i| self.positions[self.weld[i]]);
+        (b - a).cross(c - a).normalize_or_zero()
+    }
+
+    fn neighbors(&self, v: usize) -> Vec<usize> {
+        let mut result = Vec::new();
+        for &t in &self.vertex_triangles[v] {
+            if !self.alive[t] {
+                continue;
+            }
+            for &c in &self.triangles[t] {
+                let w = self.weld[c];
+                if w != v && !result.contains(&w) {
+                    result.push(w);
+                }
+            }
+        }
+        result
+    }
+
+    fn candidate(&self, from: usize, to: usize) -> Candidate {
+        let mut q = self.quadrics[from];
+        q.add(&self.quadrics[to]);
+        Candidate {
+            cost: q.error(self.positions[to]),
+            distance: q.squared_distance(self.positions[to]),
+            from,
+            to,
+            stamp: (self.stamps[from], self.stamps[to]),
+        }
+    }
+
+    /// 检查折叠 from -> to 是否合法，合法时返回属性映射 (from 的属性顶点 -> to 的属性顶点)
+    fn check_collapse(&self, from: usize, to: usize) -> Option<HashMap<usize, usize>> {
+        let live: Vec<usize> = self.vertex_triangles[from]
+            .iter()
+            .copied()
+            .filter(|&t| self.alive[t])
+            .collect();
+
+        let mut mapping: HashMap<usize, usize> = HashMap::new();
+        let mut shared = 0;
+        for &t in &live {
+            let tri = self.triangles[t];
+            let Some(ct) = tri.iter().find(|&&c| self.weld[c] == to) else {
+                continue;
+            };
+            shared += 1;
+            let cf = *tri.iter().find(|&&c| self.weld[c] == from).unwrap();
+            match mapping.get(&cf) {
+                Some(&existing) if !self.same_attributes(existing, *ct) => return None,
+                _ => {
+                    mapping.insert(cf, *ct);
+                }
+            }
+        }
+        if shared == 0 {
+            return None;
+        }
+
+        // 边界顶点只能沿边界折叠
+        if self.boundary[from] && !self.boundary[to] {
+            return None;
+        }
+
+        // 连接条件：共同邻居数必须等于共享三角形数，否则会产生非流形
+        let nf = self.neighbors(from);
+        let nt = self.neighbors(to);
+        let common = nf.iter().filter(|v| nt.contains(v)).count();
+        if common != shared {
+            return None;
+        }
+
+        for &t in &live {
+            let tri = self.triangles[t];
+            if tri.iter().any(|&c| self.weld[c] == to) {
+                continue;
+            }
+            // 其余属性必须都能映射，否则会撕开接缝
+            let cf = *tri.iter().find(|&&c| self.weld[c] == from).unwrap();
+            if !mapping.contains_key(&cf) {
+                return None;
+            }
+            // 不允许翻转或退化
+            let before = self.triangle_normal(t);
+            let moved = tri.map(|c| {
+                let w = self.weld[c];
+                if w == from { self.positions[to] } else { self.positions[w] }
+            });
+            let after = (moved[1] - moved[0]).cross(moved[2] - moved[0]);
+            if after.length_squared() < 1e-24 || after.normalize().dot(before) < 0.2 {
+                return None;
+            }
+        }
+        Some(mapping)
+    }
+
+    fn collapse(&mut self, from: usize, to: usize, mapping: &HashMap<usize, usize>) {
+        let live: Vec<usize> = self.vertex_triangles[from]
+            .iter()
+            .copied()
+            .filter(|&t| self.alive[t])
+            .collect();
+        for t in live {
+            let tri = &mut self.triangles[t];
+            if tri.iter().any(|&c| self.weld[c] == to) {
+                self.alive[t] = false;
+                self.alive_count -= 1;
+                continue;
+            }
+            for c in tri.iter_mut() {
+                if self.weld[*c] == from {
+                    *c = mapping[c];
+                }
+            }
+            self.vertex_triangles[to].push(t);
+        }
+        self.vertex_triangles[from].clear();
+        let q = self.quadrics[from];
+        self.quadrics[to].add(&q);
+        self.stamps[from] += 1;
+        self.stamps[to] += 1;
+    }
+
+    fn run(&mut self, target: SimplifyTarget) {
+        let mut heap = BinaryHeap::new();
+        for v in 0..self.positions.len() {
+            for w in self.neighbors(v) {
+                heap.push(self.candidate(v, w));
+            }
+        }
+
+        let max_error = match target {
+            SimplifyTarget::Error(e) => (e as f64) * (e as f64),
+            SimplifyTarget::Triangles(_) => f64::INFINITY,
+        };
+
+        while let Some(c) = heap.pop() {
+            if let SimplifyTarget::Triangles(n) = target
+                && self.alive_count <= n
+            {
+                break;
+            }
+            if c.stamp != (self.stamps[c.from], self.stamps[c.to]) {
+                continue;
+            }
+            // 堆按面积加权的代价排序，距离不单调，超出误差的折叠逐个跳过
+            if c.distance > max_error {
+                continue;
+            }
+            let Some(mapping) = self.check_collapse(c.from, c.to) else {
+                continue;
+            };
+            self.collapse(c.from, c.to, &mapping);
+
+            // 更新受影响的边
+            let mut touched = self.neighbors(c.to);
+            touched.push(c.to);
+            for &v in &touched {
+                self.stamps[v] += 1;
+            }
+            for &v in &touched {
+                for w in self.neighbors(v) {
+                    heap.push(self.candidate(v, w));
+                }
+            }
+        }
+    }
+
+    fn output(&self) -> Mesh {
+        let mut remap: HashMap<usize, u16> = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut tex_coords = Vec::new();
+        let mut indices = Vec::with_capacity(self.alive_count * 3);
+        let mut aabb = AABB::default();
+        for (t, tri) in self.triangles.iter().enumerate() {
+            if !self.alive[t] {
+                continue;
+            }
+            for &c in tri {
+                let idx = *remap.entry(c).or_insert_with(|| {
+                    let p = self.mesh.vertices[c];
+                    aabb.union_point_array(p);
+                    vertices.push(p);
+                    normals.push(self.mesh.normals.get(c).copied().unwrap_or([0.0, 1.0, 0.0]));
+                    tex_coords.push(self.mesh.tex_coords.get(c).copied().unwrap_or([0.0, 0.0]));
+                    (vertices.len() - 1) as u16
+                });
+                indices.push(idx);
+            }
+        }
+        Mesh {
+            vertices,
+            normals,
+            tex_coords,
+            indices,
+            bounding_volume: BoundingVolume::AABB(aabb),
+            tangents: Vec::new(),
+            smoothing_groups: Vec::new(),
+        }
+    }
+}
+
+/// 用 QEM 简化网格
+pub fn simplify(mesh: &Mesh, target: SimplifyTarget) -> Mesh {
+    let mut s = Simplifier::new(mesh);
+    s.run(target);
+    s.output()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LodMetric {
+    /// 按到相机的距离切换
+    #[default]
+    Distance,
+    /// 按包围球在屏幕上所占高度比例切换
+    ScreenSize,
+}
+
+/// 观察者信息，用于选择 LOD 层级
+#[derive(Clone, Copy, Debug)]
+pub struct LodView {
+    pub position: Vec3,
+    pub fovy: f32,
+}
+
+/// 自动生成的 LOD 链，`levels[0]` 为最精细的一级
+#[derive(Clone, Debug)]
+pub struct LodChain {
+    pub levels: Vec<Mesh>,
+    pub metric: LodMetric,
+    /// 切换到第 i + 1 级的阈值：距离模式下为距离，屏幕模式下为屏幕高度比例
+    pub thresholds: Vec<f32>,
+    pub ratios: Vec<f32>,
+}
+
+impl LodChain {
+    pub fn new(metric: LodMetric) -> Self {
+        let thresholds = match metric {
+            LodMetric::Distance => vec![10.0, 20.0, 40.0],
+            LodMetric::ScreenSize => vec![0.3, 0.15, 0.05],
+        };
+        Self {
+            levels: vec![],
+            metric,
+            thresholds,
+            ratios: vec![0.5, 0.25, 0.1],
+        }
+    }
+
+    /// 按 `ratios` 逐级简化生成整条链
+    pub fn generate(&mut self, mesh: &Mesh) {
+        let triangles = mesh.indices.len() / 3;
+        self.levels = vec![mesh.clone()];
+        for &ratio in &self.ratios {
+            let target = ((triangles as f32 * ratio) as usize).max(4);
+            let prev = self.levels.last().unwrap();
+            let mut level = simplify(prev, SimplifyTarget::Triangles(target));
+            level.update_tangents();
+            self.levels.push(level);
+        }
+    }
+
+    /// 根据观察者位置选择层级
+    pub fn select(&self, bounding_volume: &BoundingVolume, model: glam::Mat4, view: &LodView) -> usize {
+        if self.levels.is_empty() {
+            return 0;
+        }
+        let aabb = bounding_volume.get_global_aabb(model);
+        let center = aabb.center();
+        let radius = aabb.get_half_extents().length();
+        let distance = center.distance(view.position).max(1e-3);
+        let level = match self.metric {
+            LodMetric::Distance => self.thresholds.iter().filter(|&&t| distance >= t).count(),
+            LodMetric::ScreenSize => {
+                let size = radius / (distance * (view.fovy * 0.5).tan());
+                self.thresholds.iter().filter(|&&t| size <= t).count()
+            }
+        };
+        level.min(self.levels.len() - 1)
+    }
+}
+
+#[test]
+fn test_simplify_sphere_keeps_shape() {
+    use crate::geometry::shape::mesh::AsMesh;
+    use crate::geometry::shape::sphere::Sphere;
+
+    let mesh = Sphere {
+        radius: 1.0,
+        col_divisions: 32,
+        row_divisions: 32,
+    }
+    .as_mesh();
+    let before = mesh.indices.len() / 3;
+    let simplified = simplify(&mesh, SimplifyTarget::Triangles(before / 4));
+    let after = simplified.indices.len() / 3;
+    assert!(after <= before / 4 + 2 && after > 0, "{before} -> {after}");
+    for v in &simplified.vertices {
+        let r = Vec3::from(*v).length();
+        assert!((r - 1.0).abs() < 1e-4, "vertex moved off the sphere: {r}");
+    }
+
+    // 球面上任何折叠都有误差，误差目标为 0 时只去掉两极的退化三角形，与不折叠的结果相同
+    let lossless = simplify(&mesh, SimplifyTarget::Error(0.0));
+    let untouched = simplify(&mesh, SimplifyTarget::Triangles(before));
+    assert_eq!(lossless.indices.len(), untouched.indices.len());
+    // 误差目标以距离计：误差越大折叠越多，小误差下三角形仍贴近球面
+    let fine = simplify(&mesh, SimplifyTarget::Error(0.005));
+    let coarse = simplify(&mesh, SimplifyTarget::Error(0.02));
+    assert!(coarse.indices.len() < fine.indices.len() && fine.indices.len() < untouched.indices.len());
+    for tri in fine.indices.chunks_exact(3) {
+        let center = tri.iter().map(|&i| Vec3::from(fine.vertices[i as usize])).sum::<Vec3>() / 3.0;
+        assert!(1.0 - center.length() < 0.1, "triangle strays from the sphere: {}", center.length());
+    }
+}
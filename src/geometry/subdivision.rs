@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+use crate::geometry::edit::editable_topology;
+use crate::geometry::halfedge::{Corner, HalfEdgeMesh};
+use crate::geometry::shape::mesh::Mesh;
+
+// 细分曲面：Loop（三角形）与 Catmull-Clark（任意多边形），支持半锐折痕
+//
+// 折痕权重 w >= 1 的边按锐边规则细分，子边权重为 w - 1；0 < w < 1 时在光滑与锐利规则间插值。
+// 边界边视为无穷锐利。UV 按角线性插值，因此接缝不会被拉扯。
+
+/// 输出顶点数受 `u16` 索引限制
+const MAX_CORNERS: usize = u16::MAX as usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SubdivisionScheme {
+    /// 四边形占多数时用 Catmull-Clark，否则用 Loop
+    #[default]
+    Auto,
+    Loop,
+    CatmullClark,
+}
+
+/// 非破坏性细分修改器，折痕按边两端的位置记录，拓扑编辑后未移动的边仍保留折痕
+#[derive(Clone, Debug, Default)]
+pub struct SubdivisionModifier {
+    pub scheme: SubdivisionScheme,
+    pub levels: u32,
+    pub creases: Vec<([f32; 3], [f32; 3], f32)>,
+}
+
+type CreaseMap = HashMap<(usize, usize), f32>;
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+impl SubdivisionModifier {
+    /// 设置一条边的折痕权重，权重为 0 时移除
+    pub fn set_crease(&mut self, a: [f32; 3], b: [f32; 3], weight: f32) {
+        self.creases
+            .retain(|&(p, q, _)| !((p == a && q == b) || (p == b && q == a)));
+        if weight > 0.0 {
+            self.creases.push((a, b, weight));
+        }
+    }
+
+    pub fn apply(&self, mesh: &Mesh) -> Mesh {
+        if self.levels == 0 {
+            return mesh.clone();
+        }
+        let (mut topo, _) = editable_topology(mesh);
+
+        let lookup: HashMap<[u32; 3], usize> = topo
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.position.map(f32::to_bits), i))
+            .collect();
+        let mut creases: CreaseMap = HashMap::new();
+        for (a, b, w) in &self.creases {
+            if let (Some(&a), Some(&b)) = (
+                lookup.get(&a.map(f32::to_bits)),
+                lookup.get(&b.map(f32::to_bits)),
+            ) {
+                creases.insert(edge_key(a, b), *w);
+            }
+        }
+
+        let scheme = match self.scheme {
+            SubdivisionScheme::Auto => {
+                let quads = (0..topo.faces.len())
+                    .filter(|&f| topo.face_half_edges(f).len() == 4)
+                    .count();
+                if quads * 2 >= topo.faces.len() {
+                    SubdivisionScheme::CatmullClark
+                } else {
+                    SubdivisionScheme::Loop
+                }
+            }
+            s => s,
+        };
+
+        for _ in 0..self.levels {
+            if topo.half_edges.len() * 4 > MAX_CORNERS {
+                break;
+            }
+            (topo, creases) = match scheme {
+                SubdivisionScheme::Loop => loop_subdivide(&triangulate(&topo), &creases),
+                _ => catmull_clark(&topo, &creases),
+            };
+        }
+
+        smooth_normals(&mut topo, &creases);
+        // 角数已限制在 u16 范围内，转换不会失败
+        topo.to_mesh().unwrap_or_else(|| mesh.clone())
+    }
+}
+
+/// 边表：每条无向边的半边列表，按首次出现的半边顺序排列以保证确定性
+struct EdgeTable {
+    keys: Vec<(usize, usize)>,
+    half_edges: HashMap<(usize, usize), Vec<usize>>,
+}
+
+impl EdgeTable {
+    fn new(topo: &HalfEdgeMesh) -> Self {
+        let mut keys = Vec::new();
+        let mut half_edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for h in 0..topo.half_edges.len() {
+            let key = edge_key(topo.half_edges[h].origin, topo.dest(h));
+            let list = half_edges.entry(key).or_default();
+            if list.is_empty() {
+                keys.push(key);
+            }
+            list.push(h);
+        }
+        Self { keys, half_edges }
+    }
+
+    /// 边的锐度：边界与非流形边视为无穷大
+    fn sharpness(&self, key: (usize, usize), creases: &CreaseMap) -> f32 {
+        if self.half_edges[&key].len() != 2 {
+            f32::INFINITY
+        } else {
+            creases.get(&key).copied().unwrap_or(0.0)
+        }
+    }
+}
+
+fn pos(topo: &HalfEdgeMesh, v: usize) -> Vec3 {
+    Vec3::from(topo.vertices[v].position)
+}
+
+/// 顶点规则：根据相邻锐边数选择光滑 / 折痕 / 角点规则
+fn vertex_rule(
+    topo: &HalfEdgeMesh,
+    edges: &EdgeTable,
+    creases: &CreaseMap,
+    v: usize,
+    smooth: Vec3,
+    crease_rule: impl Fn(usize, usize) -> Vec3,
+) -> Vec3 {
+    let sharp: Vec<(usize, f32)> = topo
+        .vertex_neighbors(v)
+        .into_iter()
+        .map(|w| (w, edges.sharpness(edge_key(v, w), creases)))
+        .filter(|&(_, s)| s > 0.0)
+        .collect();
+    let blend = || {
+        (sharp.iter().map(|&(_, s)| s.min(1.0)).sum::<f32>() / sharp.len() as f32).clamp(0.0, 1.0)
+    };
+    match sharp.len() {
+        0 | 1 => smooth,
+        2 => smooth.lerp(crease_rule(sharp[0].0, sharp[1].0), blend()),
+        _ => smooth.lerp(pos(topo, v), blend()),
+    }
+}
+
+fn child_creases(creases: &CreaseMap, parent: (usize, usize), children: [(usize, usize); 2], out: &mut CreaseMap) {
+    if let Some(&w) = creases.get(&parent)
+        && w > 1.0
+    {
+        for c in children {
+            out.insert(edge_key(c.0, c.1), w - 1.0);
+        }
+    }
+}
+
+fn avg_uv(corners: &[Corner]) -> [f32; 2] {
+    let s = corners.iter().fold([0.0, 0.0], |acc, c| {
+        [acc[0] + c.tex_coord[0], acc[1] + c.tex_coord[1]]
+    });
+    [s[0] / corners.len() as f32, s[1] / corners.len() as f32]
+}
+
+fn corner(vertex: usize, tex_coord: [f32; 2]) -> Corner {
+    Corner {
+        vertex,
+        tex_coord,
+        normal: [0.0; 3],
+    }
+}
+
+/// Catmull-Clark 细分一次，结果全为四边形
+pub fn catmull_clark(topo: &HalfEdgeMesh, creases: &CreaseMap) -> (HalfEdgeMesh, CreaseMap) {
+    let edges = EdgeTable::new(topo);
+    let nv = topo.vertices.len();
+    let face_points: Vec<Vec3> = (0..topo.faces.len()).map(|f| topo.face_centroid(f)).collect();
+
+    let mut positions = vec![[0.0; 3]; nv];
+    let mut edge_index: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut edge_points = Vec::with_capacity(edges.keys.len());
+    for &key in &edges.keys {
+        let (a, b) = key;
+        let mid = (pos(topo, a) + pos(topo, b)) * 0.5;
+        let hs = &edges.half_edges[&key];
+        let s = edges.sharpness(key, creases);
+        let p = if s.is_infinite() {
+            mid
+        } else {
+            let f0 = face_points[topo.half_edges[hs[0]].face];
+            let f1 = face_points[topo.half_edges[hs[1]].face];
+            let smooth = (pos(topo, a) + pos(topo, b) + f0 + f1) * 0.25;
+            smooth.lerp(mid, s.clamp(0.0, 1.0))
+        };
+        edge_index.insert(key, nv + edge_points.len());
+        edge_points.push(p.to_array());
+    }
+
+    for (v, out) in positions.iter_mut().enumerate() {
+        let p = pos(topo, v);
+        let faces = topo.vertex_faces(v);
+        let neighbors = topo.vertex_neighbors(v);
+        if faces.is_empty() || neighbors.is_empty() {
+            *out = p.to_array();
+            continue;
+        }
+        let n = neighbors.len() as f32;
+        let q = faces.iter().map(|&f| face_points[f]).sum::<Vec3>() / faces.len() as f32;
+        let r = neighbors
+            .iter()
+            .map(|&w| (p + pos(topo, w)) * 0.5)
+            .sum::<Vec3>()
+            / n;
+        let smooth = (q + 2.0 * r + (n - 3.0) * p) / n;
+        let rule = vertex_rule(topo, &edges, creases, v, smooth, |a, b| {
+            (p * 6.0 + pos(topo, a) + pos(topo, b)) / 8.0
+        });
+        *out = rule.to_array();
+    }
+    positions.extend(edge_points);
+
+    let mut new_creases = HashMap::new();
+    let mut faces = Vec::with_capacity(topo.half_edges.len());
+    for (f, face_point) in face_points.iter().enumerate() {
+        positions.push(face_point.to_array());
+        let center = positions.len() - 1;
+        let hs = topo.face_half_edges(f);
+        let cs: Vec<Corner> = hs
+            .iter()
+            .map(|&h| corner(topo.half_edges[h].origin, topo.half_edges[h].tex_coord))
+            .collect();
+        let center_uv = avg_uv(&cs);
+        let k = cs.len();
+        let mid_uv = |i: usize, j: usize| {
+            let (a, b) = (cs[i].tex_coord, cs[j].tex_coord);
+            [(a[0] + b[0]) * 0.5, (a[1] + b[1]) * 0.5]
+        };
+        for i in 0..k {
+            let prev = (i + k - 1) % k;
+            let next = (i + 1) % k;
+            let e_next = edge_index[&edge_key(cs[i].vertex, cs[next].vertex)];
+            let e_prev = edge_index[&edge_key(cs[prev].vertex, cs[i].vertex)];
+            faces.push(vec![
+                cs[i],
+                corner(e_next, mid_uv(i, next)),
+                corner(center, center_uv),
+                corner(e_prev, mid_uv(prev, i)),
+            ]);
+        }
+    }
+    for &key in &edges.keys {
+        let e = edge_index[&key];
+        child_creases(creases, key, [(key.0, e), (e, key.1)], &mut new_creases);
+    }
+
+    (HalfEdgeMesh::from_faces(positions, &faces), new_creases)
+}
+
+/// 多边形扇形三角化，供 Loop 细分使用
+fn triangulate(topo: &HalfEdgeMesh) -> HalfEdgeMesh {
+    let mut faces = Vec::new();
+    for face in topo.to_faces() {
+        for k in 1..face.len() - 1 {
+            faces.push(vec![face[0], face[k], face[k + 1]]);
+        }
+    }
+    HalfEdgeMesh::from_faces(topo.positions(), &faces)
+}
+
+/// Loop 细分一次，输入必须是三角网格
+pub fn loop_subdivide(topo: &HalfEdgeMesh, creases: &CreaseMap) -> (HalfEdgeMesh, CreaseMap) {
+    let edges = EdgeTable::new(topo);
+    let nv = topo.vertices.len();
+
+    let mut positions: Vec<[f32; 3]> = (0..nv)
+        .map(|v| {
+            let p = pos(topo, v);
+            let neighbors = topo.vertex_neighbors(v);
+            if neighbors.is_empty() {
+                return p.to_array();
+            }
+            let n = neighbors.len() as f32;
+            let beta = if neighbors.len() == 3 { 3.0 / 16.0 } else { 3.0 / (8.0 * n) };
+            let sum: Vec3 = neighbors.iter().map(|&w| pos(topo, w)).sum();
+            let smooth = p * (1.0 - n * beta) + sum * beta;
+            vertex_rule(topo, &edges, creases, v, smooth, |a, b| {
+                p * 0.75 + (pos(topo, a) + pos(topo, b)) * 0.125
+            })
+            .to_array()
+        })
+        .collect();
+
+    let mut edge_index: HashMap<(usize, usize), usize> = HashMap::new();
+    for &key in &edges.keys {
+        let (a, b) = key;
+        let mid = (pos(topo, a) + pos(topo, b)) * 0.5;
+        let hs = &edges.half_edges[&key];
+        let s = edges.sharpness(key, creases);
+        let p = if s.is_infinite() {
+            mid
+        } else {
+            let c = pos(topo, topo.half_edges[topo.half_edges[hs[0]].prev].origin);
+            let d = pos(topo, topo.half_edges[topo.half_edges[hs[1]].prev].origin);
+            let smooth = (pos(topo, a) + pos(topo, b)) * 0.375 + (c + d) * 0.125;
+            smooth.lerp(mid, s.clamp(0.0, 1.0))
+        };
+        positions.push(p.to_array());
+        edge_index.insert(key, positions.len() - 1);
+    }
+
+    let mut faces = Vec::with_capacity(topo.faces.len() * 4);
+    for f in 0..topo.faces.len() {
+        let hs = topo.face_half_edges(f);
+        let cs: Vec<Corner> = hs
+            .iter()
+            .map(|&h| corner(topo.half_edges[h].origin, topo.half_edges[h].tex_coord))
+            .collect();
+        let m: Vec<Corner> = (0..3)
+            .map(|i| {
+                let (a, b) = (cs[i], cs[(i + 1) % 3]);
+                corner(
+                    edge_index[&edge_key(a.vertex, b.vertex)],
+                    [
+                        (a.tex_coord[0] + b.tex_coord[0]) * 0.5,
+                        (a.tex_coord[1] + b.tex_coord[1]) * 0.5,
+                    ],
+                )
+            })
+            .collect();
+        faces.push(vec![cs[0], m[0], m[2]]);
+        faces.push(vec![m[0], cs[1], m[1]]);
+        faces.push(vec![m[2], m[1], cs[2]]);
+        faces.push(vec![m[0], m[1], m[2]]);
+    }
+
+    let mut new_creases = HashMap::new();
+    for &key in &edges.keys {
+        let e = edge_index[&key];
+        child_creases(creases, key, [(key.0, e), (e, key.1)], &mut new_creases);
+    }
+
+    (HalfEdgeMesh::from_faces(positions, &faces), new_creases)
+}
+
+/// 按角计算法线：相邻面跨过非锐边时平滑，锐边（剩余折痕或边界）处保持硬边
+fn smooth_normals(topo: &mut HalfEdgeMesh, creases: &CreaseMap) {
+    let n = topo.half_edges.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+
+    for h in 0..n {
+        let Some(t) = topo.half_edges[h].twin else {
+            continue;
+        };
+        let key = edge_key(topo.half_edges[h].origin, topo.dest(h));
+        if creases.get(&key).is_some_and(|&w| w > 0.0) {
+            continue;
+        }
+        // h 的起点在对面对应的角是 next(t)
+        let other = topo.half_edges[t].next;
+        let (a, b) = (find(&mut parent, h), find(&mut parent, other));
+        parent[a] = b;
+    }
+
+    let face_normals: Vec<Vec3> = (0..topo.faces.len()).map(|f| topo.face_normal(f)).collect();
+    let mut sums = vec![Vec3::ZERO; n];
+    for h in 0..n {
+        let root = find(&mut parent, h);
+        sums[root] += face_normals[topo.half_edges[h].face];
+    }
+    for h in 0..n {
+        let root = find(&mut parent, h);
+        let normal = sums[root].normalize_or_zero();
+        topo.half_edges[h].normal = if normal == Vec3::ZERO {
+            face_normals[topo.half_edges[h].face].to_array()
+        } else {
+            normal.to_array()
+        };
+    }
+}
+
+#[test]
+fn test_subdivide_cube() {
+    use crate::geometry::shape::cube::Cube;
+    use crate::geometry::shape::mesh::AsMesh;
+
+    let cube = Cube {
+        width: 2.0,
+        height: 2.0,
+        depth: 2.0,
+    }
+    .as_mesh();
+
+    let mut modifier = SubdivisionModifier {
+        scheme: SubdivisionScheme::CatmullClark,
+        levels: 2,
+        creases: vec![],
+    };
+    let smooth = modifier.apply(&cube);
+    let (topo, _) = editable_topology(&smooth);
+    assert!(topo.is_closed() && topo.is_manifold());
+    // 光滑细分后角点向内收缩
+    let max = smooth.vertices.iter().map(|v| v[0].abs()).fold(0.0, f32::max);
+    assert!(max < 1.0);
+
+    // 全部边设为锐利折痕时形状不变
+    let (base, _) = editable_topology(&cube);
+    for h in 0..base.half_edges.len() {
+        let a = base.vertices[base.half_edges[h].origin].position;
+        let b = base.vertices[base.dest(h)].position;
+        modifier.set_crease(a, b, 10.0);
+    }
+    let sharp = modifier.apply(&cube);
+    for v in &sharp.vertices {
+        assert!(v.iter().all(|c| c.abs() <= 1.0 + 1e-5));
+        assert!(v.iter().any(|c| (c.abs() - 1.0).abs() < 1e-5));
+    }
+
+    modifier.scheme = SubdivisionScheme::Loop;
+    modifier.creases.clear();
+    let looped = modifier.apply(&cube);
+    let (topo, _) = editable_topology(&looped);
+    assert_eq!(topo.euler_characteristic(), 2);
+}
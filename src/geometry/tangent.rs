@@ -0,0 +1,114 @@
+use glam::{Vec2, Vec3};
+
+use crate::geometry::shape::mesh::Mesh;
+
+/// 同一手系的切线加权和
+#[derive(Clone, Copy, Default)]
+struct TangentSum {
+    tangent: Vec3,
+    weight: f32,
+}
+
+/// 与法线正交、任意方向的切线，用于没有有效 UV 的顶点
+fn fallback_tangent(normal: Vec3) -> Vec3 {
+    normal.any_orthonormal_vector()
+}
+
+impl Mesh {
+    /// 重新计算并保存切线，网格生成或编辑后调用一次，渲染时直接使用
+    pub fn update_tangents(&mut self) {
+        self.tangents = self.compute_tangents();
+    }
+
+    /// 生成逐顶点切线
+    ///
+    /// 每个角的切线先投影到顶点法线的切平面再按内角加权，左右手系分开累加；
+    /// 镜像 UV 在同一顶点相遇时取权重较大的一侧。着色器中副切线按
+    /// `tangent.w * cross(normal, tangent.xyz)` 重建，不再单独存储。
+    pub fn compute_tangents(&self) -> Vec<[f32; 4]> {
+        let n = self.vertices.len();
+        let mut sums = vec![[TangentSum::default(); 2]; n];
+        let normal_of = |i: usize| {
+            self.normals
+                .get(i)
+                .map(|v| Vec3::from(*v).normalize_or_zero())
+                .unwrap_or(Vec3::ZERO)
+        };
+
+        for tri in self.indices.chunks_exact(3) {
+            let t = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            if t.iter().any(|&i| i >= n || i >= self.tex_coords.len()) {
+                continue;
+            }
+            let p = t.map(|i| Vec3::from(self.vertices[i]));
+            let uv = t.map(|i| Vec2::from(self.tex_coords[i]));
+            let (e1, e2) = (p[1] - p[0], p[2] - p[0]);
+            let (d1, d2) = (uv[1] - uv[0], uv[2] - uv[0]);
+            let det = d1.x * d2.y - d2.x * d1.y;
+            if det.abs() < 1e-12 {
+                continue;
+            }
+            let tangent = (e1 * d2.y - e2 * d1.y) / det;
+            let bitangent = (e2 * d1.x - e1 * d2.x) / det;
+            let face_normal = e1.cross(e2).normalize_or_zero();
+
+            for k in 0..3 {
+                let v = t[k];
+                let mut normal = normal_of(v);
+                if normal == Vec3::ZERO {
+                    normal = face_normal;
+                }
+                // 投影到切平面
+                let projected = (tangent - normal * normal.dot(tangent)).normalize_or_zero();
+                if projected == Vec3::ZERO {
+                    continue;
+                }
+                let handed = normal.cross(projected).dot(bitangent) < 0.0;
+                let angle = (p[(k + 1) % 3] - p[k]).angle_between(p[(k + 2) % 3] - p[k]);
+                if !angle.is_finite() {
+                    continue;
+                }
+                let sum = &mut sums[v][usize::from(handed)];
+                sum.tangent += projected * angle;
+                sum.weight += angle;
+            }
+        }
+
+        sums.iter()
+            .enumerate()
+            .map(|(i, [right, left])| {
+                let (sum, sign) = if left.weight > right.weight { (left, -1.0) } else { (right, 1.0) };
+                let normal = normal_of(i);
+                let tangent = (sum.tangent - normal * normal.dot(sum.tangent))
+                    .try_normalize()
+                    .unwrap_or_else(|| fallback_tangent(if normal == Vec3::ZERO { Vec3::Y } else { normal }));
+                [tangent.x, tangent.y, tangent.z, sign]
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_cube_tangents_follow_uv() {
+    use crate::geometry::shape::cube::Cube;
+    use crate::geometry::shape::mesh::AsMesh;
+
+    let mesh = Cube { width: 1.0, height: 1.0, depth: 1.0 }.as_mesh();
+    let tangents = mesh.compute_tangents();
+    assert_eq!(tangents.len(), mesh.vertices.len());
+    for tri in mesh.indices.chunks(3) {
+        let t = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        let p = t.map(|i| Vec3::from(mesh.vertices[i]));
+        let uv = t.map(|i| Vec2::from(mesh.tex_coords[i]));
+        let (d1, d2) = (uv[1] - uv[0], uv[2] - uv[0]);
+        let det = d1.x * d2.y - d2.x * d1.y;
+        let expected = (((p[1] - p[0]) * d2.y - (p[2] - p[0]) * d1.y) / det).normalize();
+        for &i in &t {
+            let tangent = Vec3::from_slice(&tangents[i][..3]);
+            let normal = Vec3::from(mesh.normals[i]);
+            assert!((tangent.length() - 1.0).abs() < 1e-4);
+            assert!(tangent.dot(normal).abs() < 1e-4);
+            assert!(tangent.dot(expected) > 0.99);
+        }
+    }
+}
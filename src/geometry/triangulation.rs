@@ -0,0 +1,288 @@
+use std::collections::{HashMap, VecDeque};
+
+// 二维约束 Delaunay 三角化 (Bowyer-Watson + Sloan 翻边恢复约束边)
+
+fn orient(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn in_circumcircle(a: [f64; 2], b: [f64; 2], c: [f64; 2], p: [f64; 2]) -> bool {
+    // 要求 a, b, c 为逆时针
+    let ax = a[0] - p[0];
+    let ay = a[1] - p[1];
+    let bx = b[0] - p[0];
+    let by = b[1] - p[1];
+    let cx = c[0] - p[0];
+    let cy = c[1] - p[1];
+    let det = (ax * ax + ay * ay) * (bx * cy - cx * by) - (bx * bx + by * by) * (ax * cy - cx * ay)
+        + (cx * cx + cy * cy) * (ax * by - bx * ay);
+    det > 1e-14
+}
+
+/// 判断线段 p1p2 与 q1q2 是否严格相交（端点重合不算）
+fn segments_cross(p1: [f64; 2], p2: [f64; 2], q1: [f64; 2], q2: [f64; 2]) -> bool {
+    let d1 = orient(q1, q2, p1);
+    let d2 = orient(q1, q2, p2);
+    let d3 = orient(p1, p2, q1);
+    let d4 = orient(p1, p2, q2);
+    let eps = 1e-12;
+    ((d1 > eps && d2 < -eps) || (d1 < -eps && d2 > eps))
+        && ((d3 > eps && d4 < -eps) || (d3 < -eps && d4 > eps))
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// 对平面点集做约束 Delaunay 三角化
+///
+/// `constraints` 中的边会出现在结果中（只要它们互不交叉）。
+/// 返回的三角形为逆时针顺序，索引指向 `points`；重合点会被合并到第一次出现的索引。
+pub fn constrained_triangulate(
+    points: &[[f32; 2]],
+    constraints: &[(usize, usize)],
+) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut pts: Vec<[f64; 2]> = points.iter().map(|p| [p[0] as f64, p[1] as f64]).collect();
+
+    // 合并重合点
+    let mut remap: Vec<usize> = (0..pts.len()).collect();
+    {
+        let mut seen: HashMap<(i64, i64), usize> = HashMap::new();
+        for (i, p) in pts.iter().enumerate() {
+            let key = ((p[0] * 1e6).round() as i64, (p[1] * 1e6).round() as i64);
+            match seen.get(&key) {
+                Some(&j) => remap[i] = j,
+                None => {
+                    seen.insert(key, i);
+                }
+            }
+        }
+    }
+
+    // 超级三角形
+    let mut min = [f64::INFINITY; 2];
+    let mut max = [f64::NEG_INFINITY; 2];
+    for p in &pts {
+        min[0] = min[0].min(p[0]);
+        min[1] = min[1].min(p[1]);
+        max[0] = max[0].max(p[0]);
+        max[1] = max[1].max(p[1]);
+    }
+    let d = (max[0] - min[0]).max(max[1] - min[1]).max(1e-6);
+    let mid = [(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5];
+    let n = pts.len();
+    pts.push([mid[0] - 20.0 * d, mid[1] - d]);
+    pts.push([mid[0] + 20.0 * d, mid[1] - d]);
+    pts.push([mid[0], mid[1] + 20.0 * d]);
+
+    let mut triangles: Vec<[usize; 3]> = vec![[n, n + 1, n + 2]];
+
+    for i in 0..n {
+        if remap[i] != i {
+            continue;
+        }
+        let p = pts[i];
+        let mut bad = Vec::new();
+        for (t_idx, t) in triangles.iter().enumerate() {
+            if in_circumcircle(pts[t[0]], pts[t[1]], pts[t[2]], p) {
+                bad.push(t_idx);
+            }
+        }
+
+        // 空洞边界：只属于一个坏三角形的边
+        let mut edge_count: HashMap<(usize, usize), (usize, usize, u32)> = HashMap::new();
+        for &t_idx in &bad {
+            let t = triangles[t_idx];
+            for k in 0..3 {
+                let a = t[k];
+                let b = t[(k + 1) % 3];
+                let entry = edge_count.entry(edge_key(a, b)).or_insert((a, b, 0));
+                entry.2 += 1;
+            }
+        }
+
+        for &t_idx in bad.iter().rev() {
+            triangles.swap_remove(t_idx);
+        }
+
+        let mut boundary: Vec<(usize, usize)> = edge_count
+            .values()
+            .filter(|e| e.2 == 1)
+            .map(|e| (e.0, e.1))
+            .collect();
+        boundary.sort_unstable();
+        for (a, b) in boundary {
+            if orient(pts[a], pts[b], p) > 0.0 {
+                triangles.push([a, b, i]);
+            } else {
+                triangles.push([b, a, i]);
+            }
+        }
+    }
+
+    // 恢复约束边
+    for &(a, b) in constraints {
+        let a = remap[a];
+        let b = remap[b];
+        if a == b {
+            continue;
+        }
+        recover_edge(&pts, &mut triangles, a, b);
+    }
+
+    triangles.retain(|t| t.iter().all(|&v| v < n));
+    triangles
+}
+
+fn has_edge(triangles: &[[usize; 3]], a: usize, b: usize) -> bool {
+    triangles
+        .iter()
+        .any(|t| (0..3).any(|k| edge_key(t[k], t[(k + 1) % 3]) == edge_key(a, b)))
+}
+
+fn recover_edge(pts: &[[f64; 2]], triangles: &mut [[usize; 3]], a: usize, b: usize) {
+    if has_edge(triangles, a, b) {
+        return;
+    }
+
+    let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+    let mut seen = std::collections::HashSet::new();
+    for t in triangles.iter() {
+        for k in 0..3 {
+            let u = t[k];
+            let v = t[(k + 1) % 3];
+            let key = edge_key(u, v);
+            if segments_cross(pts[u], pts[v], pts[a], pts[b]) && seen.insert(key) {
+                queue.push_back(key);
+            }
+        }
+    }
+
+    // 每条边最多被推迟若干次，防止退化输入导致死循环
+    let mut budget = queue.len() * 16 + 64;
+    while let Some((u, v)) = queue.pop_front() {
+        if budget == 0 {
+            break;
+        }
+        budget -= 1;
+
+        let mut pair = [usize::MAX; 2];
+        let mut found = 0;
+        for (t_idx, t) in triangles.iter().enumerate() {
+            if t.contains(&u) && t.contains(&v) {
+                pair[found] = t_idx;
+                found += 1;
+                if found == 2 {
+                    break;
+                }
+            }
+        }
+        if found < 2 {
+            continue;
+        }
+
+        let opposite = |t: [usize; 3]| *t.iter().find(|&&x| x != u && x != v).unwrap();
+        let p = opposite(triangles[pair[0]]);
+        let q = opposite(triangles[pair[1]]);
+
+        // 只有凸四边形才能翻边
+        if !segments_cross(pts[p], pts[q], pts[u], pts[v]) {
+            queue.push_back((u, v));
+            continue;
+        }
+
+        let mut t1 = [p, q, u];
+        if orient(pts[t1[0]], pts[t1[1]], pts[t1[2]]) < 0.0 {
+            t1.swap(0, 1);
+        }
+        let mut t2 = [p, q, v];
+        if orient(pts[t2[0]], pts[t2[1]], pts[t2[2]]) < 0.0 {
+            t2.swap(0, 1);
+        }
+        triangles[pair[0]] = t1;
+        triangles[pair[1]] = t2;
+
+        if edge_key(p, q) != edge_key(a, b) && segments_cross(pts[p], pts[q], pts[a], pts[b]) {
+            queue.push_back(edge_key(p, q));
+        }
+    }
+}
+
+/// 偶奇规则判断点是否在闭合多边形内
+pub fn point_in_polygon(p: [f32; 2], polygon: &[[f32; 2]]) -> bool {
+    let mut inside = false;
+    let n = polygon.len();
+    if n < 3 {
+        return false;
+    }
+    let mut j = n - 1;
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[j];
+        if (a[1] > p[1]) != (b[1] > p[1]) {
+            let x = a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+            if p[0] < x {
+                inside = !inside;
+            }
+        }
+        j = i;
+    }
+    inside
+}
+
+/// 点到线段的距离
+pub fn distance_to_segment(p: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let ab = [b[0] - a[0], b[1] - a[1]];
+    let ap = [p[0] - a[0], p[1] - a[1]];
+    let len2 = ab[0] * ab[0] + ab[1] * ab[1];
+    let t = if len2 > 1e-12 {
+        ((ap[0] * ab[0] + ap[1] * ab[1]) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let dx = ap[0] - ab[0] * t;
+    let dy = ap[1] - ab[1] * t;
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[test]
+fn test_constrained_triangulate_square_hole() {
+    let mut points = Vec::new();
+    for i in 0..=6 {
+        for j in 0..=6 {
+            points.push([i as f32 / 6.0, j as f32 / 6.0]);
+        }
+    }
+    // 斜放的方形洞，边不与网格对齐
+    let hole = [[0.5, 0.2], [0.8, 0.5], [0.5, 0.8], [0.2, 0.5]];
+    let base = points.len();
+    points.extend_from_slice(&hole);
+    let constraints: Vec<(usize, usize)> = (0..4).map(|k| (base + k, base + (k + 1) % 4)).collect();
+
+    let mut triangles = constrained_triangulate(&points, &constraints);
+    for &(a, b) in &constraints {
+        assert!(has_edge(&triangles, a, b), "missing constraint edge {a}-{b}");
+    }
+
+    triangles.retain(|t| {
+        let c = [
+            (points[t[0]][0] + points[t[1]][0] + points[t[2]][0]) / 3.0,
+            (points[t[0]][1] + points[t[1]][1] + points[t[2]][1]) / 3.0,
+        ];
+        !point_in_polygon(c, &hole)
+    });
+
+    // 剩余面积 = 1 - 洞面积 (0.18)
+    let area: f32 = triangles
+        .iter()
+        .map(|t| {
+            let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+            0.5 * ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]))
+        })
+        .sum();
+    assert!((area - 0.82).abs() < 1e-4, "area = {area}");
+}
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+
+use glam::{Vec2, Vec3};
+
+use crate::geometry::halfedge::weld_positions;
+use crate::geometry::shape::mesh::Mesh;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum UvAxis {
+    X,
+    #[default]
+    Y,
+    Z,
+}
+
+impl UvAxis {
+    /// 从该轴正方向看过去的平面坐标
+    fn planar(self, p: Vec3) -> Vec2 {
+        match self {
+            UvAxis::X => Vec2::new(-p.z, p.y),
+            UvAxis::Y => Vec2::new(p.x, -p.z),
+            UvAxis::Z => Vec2::new(p.x, p.y),
+        }
+    }
+
+    /// 旋转坐标使该轴成为 y 轴
+    fn frame(self, p: Vec3) -> Vec3 {
+        match self {
+            UvAxis::X => Vec3::new(p.z, p.x, p.y),
+            UvAxis::Y => p,
+            UvAxis::Z => Vec3::new(p.y, p.z, p.x),
+        }
+    }
+}
+
+/// UV 生成方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UvProjection {
+    /// 沿轴向平面投影
+    Planar(UvAxis),
+    /// 每个面按主法线方向选择投影平面（三平面投影）
+    Box,
+    Cylindrical(UvAxis),
+    Spherical,
+    /// 自动分片并用 LSCM 展开
+    Unwrap,
+}
+
+/// UV 修改器
+#[derive(Clone, Debug, PartialEq)]
+pub struct UvModifier {
+    pub projection: UvProjection,
+    /// 按世界尺寸计算 UV，不同大小的物体纹理密度一致
+    pub world_space: bool,
+    /// 世界空间下为每单位长度的重复次数，否则为整体重复次数
+    pub scale: f32,
+    pub offset: [f32; 2],
+}
+
+impl Default for UvModifier {
+    fn default() -> Self {
+        Self {
+            projection: UvProjection::Box,
+            world_space: true,
+            scale: 1.0,
+            offset: [0.0; 2],
+        }
+    }
+}
+
+/// 按角（三角形的顶点）生成 UV 后，UV 不同的角拆分成不同顶点
+fn rebuild(mesh: &Mesh, tris: &[[usize; 3]], corner_uvs: &[[Vec2; 3]]) -> Mesh {
+    let mut result = Mesh {
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        tex_coords: Vec::new(),
+        indices: Vec::with_capacity(tris.len() * 3),
+        bounding_volume: mesh.bounding_volume.clone(),
+        tangents: Vec::new(),
+        smoothing_groups: if mesh.smoothing_groups.len() == tris.len() {
+            mesh.smoothing_groups.clone()
+        } else {
+            Vec::new()
+        },
+    };
+    let mut lookup: HashMap<(usize, [i32; 2]), u16> = HashMap::new();
+    for (t, uvs) in tris.iter().zip(corner_uvs) {
+        for (&v, uv) in t.iter().zip(uvs) {
+            let key = (v, (*uv * 1e4).round().as_ivec2().to_array());
+            let index = *lookup.entry(key).or_insert_with(|| {
+                result.vertices.push(mesh.vertices[v]);
+                result.normals.push(mesh.normals.get(v).copied().unwrap_or([0.0; 3]));
+                result.tex_coords.push(uv.to_array());
+                (result.vertices.len() - 1) as u16
+            });
+            result.indices.push(index);
+        }
+    }
+    result
+}
+
+fn valid_triangles(mesh: &Mesh) -> Vec<[usize; 3]> {
+    let n = mesh.vertices.len();
+    mesh.indices
+        .chunks_exact(3)
+        .map(|c| [c[0] as usize, c[1] as usize, c[2] as usize])
+        .filter(|t| t.iter().all(|&i| i < n))
+        .collect()
+}
+
+/// 角度类投影中，跨越接缝的三角形把 u 展开到同一周期
+fn unwrap_period(uvs: &mut [Vec2; 3], period: f32) {
+    let max = uvs.iter().map(|uv| uv.x).fold(f32::MIN, f32::max);
+    for uv in uvs.iter_mut() {
+        if max - uv.x > period * 0.5 {
+            uv.x += period;
+        }
+    }
+}
+
+/// 平面投影，法线朝负方向的面镜像以保持纹理方向
+fn box_face_uv(p: Vec3, normal: Vec3) -> Vec2 {
+    let a = normal.abs();
+    if a.x >= a.y && a.x >= a.z {
+        Vec2::new(-p.z * normal.x.signum(), p.y)
+    } else if a.y >= a.z {
+        Vec2::new(p.x, -p.z * normal.y.signum())
+    } else {
+        Vec2::new(p.x * normal.z.signum(), p.y)
+    }
+}
+
+impl UvModifier {
+    /// `object_scale` 为物体的缩放，世界空间模式下用于统一纹理密度
+    pub fn apply(&self, mesh: &Mesh, object_scale: Vec3) -> Mesh {
+        let tris = valid_triangles(mesh);
+        if tris.is_empty() {
+            return mesh.clone();
+        }
+        let world = if self.world_space { object_scale } else { Vec3::ONE };
+        let positions: Vec<Vec3> = mesh.vertices.iter().map(|v| Vec3::from(*v) * world).collect();
+        let (min, max) = positions
+            .iter()
+            .fold((Vec3::MAX, Vec3::MIN), |(lo, hi), p| (lo.min(*p), hi.max(*p)));
+        let center = (min + max) * 0.5;
+        let extent = (max - min).max(Vec3::splat(1e-6));
+
+        let mut corner_uvs: Vec<[Vec2; 3]> = match self.projection {
+            UvProjection::Planar(axis) => {
+                let local: Vec<Vec2> = positions.iter().map(|p| axis.planar(*p)).collect();
+                let (lo, hi) = local
+                    .iter()
+                    .fold((Vec2::MAX, Vec2::MIN), |(lo, hi), uv| (lo.min(*uv), hi.max(*uv)));
+                let size = (hi - lo).max(Vec2::splat(1e-6));
+                tris.iter()
+                    .map(|t| t.map(|i| if self.world_space { local[i] } else { (local[i] - lo) / size }))
+                    .collect()
+            }
+            UvProjection::Box => tris
+                .iter()
+                .map(|t| {
+                    let p = t.map(|i| positions[i]);
+                    let normal = (p[1] - p[0]).cross(p[2] - p[0]);
+                    p.map(|q| {
+                        if self.world_space {
+                            box_face_uv(q, normal)
+                        } else {
+                            box_face_uv((q - min) / extent.max_element(), normal)
+                        }
+                    })
+                })
+                .collect(),
+            UvProjection::Cylindrical(axis) => {
+                let local: Vec<Vec3> = positions.iter().map(|p| axis.frame(*p - center)).collect();
+                let radius = local.iter().map(|q| Vec2::new(q.x, q.z).length()).sum::<f32>() / local.len() as f32;
+                let height = local.iter().map(|q| q.y).fold(f32::MIN, f32::max)
+                    - local.iter().map(|q| q.y).fold(f32::MAX, f32::min);
+                tris.iter()
+                    .map(|t| {
+                        let mut uvs = t.map(|i| {
+                            let q = local[i];
+                            let u = (q.x.atan2(q.z) + PI) / TAU;
+                            if self.world_space {
+                                Vec2::new(u * TAU * radius, q.y)
+                            } else {
+                                Vec2::new(u, q.y / height.max(1e-6) + 0.5)
+                            }
+                        });
+                        let period = if self.world_space { TAU * radius } else { 1.0 };
+                        unwrap_period(&mut uvs, period);
+                        uvs
+                    })
+                    .collect()
+            }
+            UvProjection::Spherical => {
+                let local: Vec<Vec3> = positions.iter().map(|p| *p - center).collect();
+                let radius = local.iter().map(|q| q.length()).sum::<f32>() / local.len() as f32;
+                let period = if self.world_space { TAU * radius } else { 1.0 };
+                tris.iter()
+                    .map(|t| {
+                        let mut uvs = t.map(|i| {
+                            let q = local[i].normalize_or(Vec3::Y);
+                            let uv = Vec2::new((q.x.atan2(q.z) + PI) / TAU, q.y.clamp(-1.0, 1.0).asin() / PI + 0.5);
+                            if self.world_space { uv * TAU * radius * Vec2::new(1.0, 0.5) } else { uv }
+                        });
+                        unwrap_period(&mut uvs, period);
+                        // 极点处经度无意义，取另外两个角的平均
+                        for k in 0..3 {
+                            let q = local[t[k]].normalize_or(Vec3::Y);
+                            if q.y.abs() > 0.9999 {
+                                uvs[k].x = (uvs[(k + 1) % 3].x + uvs[(k + 2) % 3].x) * 0.5;
+                            }
+                        }
+                        uvs
+                    })
+                    .collect()
+            }
+            UvProjection::Unwrap => lscm_unwrap(&positions, &tris),
+        };
+
+        let offset = Vec2::from(self.offset);
+        for uvs in &mut corner_uvs {
+            for uv in uvs.iter_mut() {
+                *uv = *uv * self.scale + offset;
+            }
+        }
+        let result = rebuild(mesh, &tris, &corner_uvs);
+        if result.vertices.len() > u16::MAX as usize {
+            return mesh.clone();
+        }
+        result
+    }
+}
+
+/// 按法线锥生长分片：与种子面法线夹角小于该值的相邻面归入同一片
+const CHART_ANGLE: f32 = 66.0;
+/// 分片之间的间隔（占图集边长的比例）
+const CHART_MARGIN: f32 = 0.01;
+
+/// 分片 + LSCM 展开 + 简单的行式打包，结果位于 [0, 1]²
+fn lscm_unwrap(positions: &[Vec3], tris: &[[usize; 3]]) -> Vec<[Vec2; 3]> {
+    let raw: Vec<[f32; 3]> = positions.iter().map(|p| p.to_array()).collect();
+    let (_, remap) = weld_positions(&raw, 1e-5);
+    let face_normals: Vec<Vec3> = tris
+        .iter()
+        .map(|t| (positions[t[1]] - positions[t[0]]).cross(positions[t[2]] - positions[t[0]]).normalize_or_zero())
+        .collect();
+
+    // 面邻接（按合并后的位置）
+    let mut edge_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (f, t) in tris.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (remap[t[k]], remap[t[(k + 1) % 3]]);
+            edge_faces.entry((a.min(b), a.max(b))).or_default().push(f);
+        }
+    }
+
+    let cos_limit = CHART_ANGLE.to_radians().cos();
+    let mut chart_of = vec![usize::MAX; tris.len()];
+    let mut charts: Vec<Vec<usize>> = Vec::new();
+    for seed in 0..tris.len() {
+        if chart_of[seed] != usize::MAX {
+            continue;
+        }
+        let id = charts.len();
+        let axis = face_normals[seed];
+        let mut faces = vec![seed];
+        chart_of[seed] = id;
+        let mut i = 0;
+        while i < faces.len() {
+            let f = faces[i];
+            i += 1;
+            for k in 0..3 {
+                let (a, b) = (remap[tris[f][k]], remap[tris[f][(k + 1) % 3]]);
+                for &g in &edge_faces[&(a.min(b), a.max(b))] {
+                    if chart_of[g] == usize::MAX && face_normals[g].dot(axis) >= cos_limit {
+                        chart_of[g] = id;
+                        faces.push(g);
+                    }
+                }
+            }
+        }
+        charts.push(faces);
+    }
+
+    // 逐片求解，UV 以合并后的位置为键
+    let mut chart_uvs: Vec<HashMap<usize, Vec2>> = Vec::with_capacity(charts.len());
+    for faces in &charts {
+        chart_uvs.push(lscm_chart(positions, tris, &remap, faces));
+    }
+
+    // 行式打包：按高度从大到小逐行排放
+    let bounds: Vec<(Vec2, Vec2)> = chart_uvs
+        .iter()
+        .map(|uvs| uvs.values().fold((Vec2::MAX, Vec2::MIN), |(lo, hi), uv| (lo.min(*uv), hi.max(*uv))))
+        .collect();
+    let total_area: f32 = bounds.iter().map(|(lo, hi)| (*hi - *lo).x * (*hi - *lo).y).sum();
+    let margin = total_area.sqrt().max(1e-6) * CHART_MARGIN;
+    let row_width = total_area.sqrt() * 1.2 + margin;
+    let mut order: Vec<usize> = (0..charts.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (ha, hb) = ((bounds[a].1 - bounds[a].0).y, (bounds[b].1 - bounds[b].0).y);
+        hb.total_cmp(&ha)
+    });
+    let mut placement = vec![Vec2::ZERO; charts.len()];
+    let (mut cursor, mut row_height, mut used) = (Vec2::splat(margin), 0.0f32, Vec2::ZERO);
+    for &c in &order {
+        let size = bounds[c].1 - bounds[c].0;
+        if cursor.x + size.x > row_width && cursor.x > margin {
+            cursor = Vec2::new(margin, cursor.y + row_height + margin);
+            row_height = 0.0;
+        }
+        placement[c] = cursor - bounds[c].0;
+        row_height = row_height.max(size.y);
+        used = used.max(cursor + size);
+        cursor.x += size.x + margin;
+    }
+    let atlas = (used.max_element() + margin).max(1e-6);
+
+    tris.iter()
+        .enumerate()
+        .map(|(f, t)| {
+            let c = chart_of[f];
+            t.map(|i| (chart_uvs[c][&remap[i]] + placement[c]) / atlas)
+        })
+        .collect()
+}
+
+/// 最小二乘保角映射：固定两个顶点，用共轭梯度解法方程
+fn lscm_chart(positions: &[Vec3], tris: &[[usize; 3]], remap: &[usize], faces: &[usize]) -> HashMap<usize, Vec2> {
+    // 片内顶点编号，并取片的平均法线作为初始平面投影
+    let mut local: HashMap<usize, usize> = HashMap::new();
+    let mut points: Vec<Vec3> = Vec::new();
+    let mut normal = Vec3::ZERO;
+    for &f in faces {
+        let t = tris[f];
+        normal += (positions[t[1]] - positions[t[0]]).cross(positions[t[2]] - positions[t[0]]);
+        for &v in &t {
+            local.entry(remap[v]).or_insert_with(|| {
+                points.push(positions[v]);
+                points.len() - 1
+            });
+        }
+    }
+    let normal = normal.normalize_or(Vec3::Y);
+    let tangent = normal.any_orthonormal_vector();
+    let bitangent = normal.cross(tangent);
+    let initial: Vec<Vec2> = points.iter().map(|p| Vec2::new(p.dot(tangent), p.dot(bitangent))).collect();
+
+    // 沿主方向距离最远的两点作为固定点
+    let (mut pin0, mut pin1) = (0, 0);
+    for (i, uv) in initial.iter().enumerate() {
+        if uv.x < initial[pin0].x {
+            pin0 = i;
+        }
+        if uv.x > initial[pin1].x {
+            pin1 = i;
+        }
+    }
+    let n = points.len();
+    let mut solution = initial.clone();
+    if pin0 != pin1 && faces.len() > 1 {
+        // 每个三角形贡献两行：Σ W_j U_j = 0 的实部与虚部，W_j 为对边的复数表示
+        let mut rows: Vec<Vec<(usize, f32)>> = Vec::with_capacity(faces.len() * 2);
+        for &f in faces {
+            let t = tris[f];
+            let p = t.map(|v| positions[v]);
+            let ids = t.map(|v| local[&remap[v]]);
+            // 三角形自身的正交坐标系
+            let e0 = p[1] - p[0];
+            let x_axis = e0.normalize_or_zero();
+            let face_n = e0.cross(p[2] - p[0]);
+            let area2 = face_n.length();
+            if area2 < 1e-12 || x_axis == Vec3::ZERO {
+                continue;
+            }
+            let y_axis = face_n.normalize().cross(x_axis);
+            let z = p.map(|q| Vec2::new((q - p[0]).dot(x_axis), (q - p[0]).dot(y_axis)));
+            let scale = 1.0 / area2.sqrt();
+            let mut real = Vec::with_capacity(6);
+            let mut imag = Vec::with_capacity(6);
+            for j in 0..3 {
+                let w = z[(j + 2) % 3] - z[(j + 1) % 3];
+                // 未知量排列为 [u0, v0, u1, v1, ...]
+                real.push((ids[j] * 2, w.x * scale));
+                real.push((ids[j] * 2 + 1, -w.y * scale));
+                imag.push((ids[j] * 2, w.y * scale));
+                imag.push((ids[j] * 2 + 1, w.x * scale));
+            }
+            rows.push(real);
+            rows.push(imag);
+        }
+
+        let pinned = |k: usize| k / 2 == pin0 || k / 2 == pin1;
+        let mut x: Vec<f32> = solution.iter().flat_map(|uv| [uv.x, uv.y]).collect();
+        let apply = |x: &[f32], free_only: bool| -> Vec<f32> {
+            let mut out = vec![0.0; 2 * n];
+            for row in &rows {
+                let r: f32 = row.iter().filter(|(k, _)| !free_only || !pinned(*k)).map(|(k, a)| a * x[*k]).sum();
+                for &(k, a) in row {
+                    if !pinned(k) {
+                        out[k] += a * r;
+                    }
+                }
+            }
+            out
+        };
+        // 右端项：固定点的贡献移到右边
+        let pinned_x: Vec<f32> = (0..2 * n).map(|k| if pinned(k) { x[k] } else { 0.0 }).collect();
+        let b: Vec<f32> = apply(&pinned_x, false).iter().map(|v| -v).collect();
+        let free_x: Vec<f32> = (0..2 * n).map(|k| if pinned(k) { 0.0 } else { x[k] }).collect();
+        let ax = apply(&free_x, true);
+        let mut r: Vec<f32> = b.iter().zip(&ax).map(|(b, a)| b - a).collect();
+        let mut d = r.clone();
+        let mut rr: f32 = r.iter().map(|v| v * v).sum();
+        let tolerance = rr.max(1e-20) * 1e-10;
+        for _ in 0..(4 * n).max(200) {
+            if rr <= tolerance {
+                break;
+            }
+            let ad = apply(&d, true);
+            let dad: f32 = d.iter().zip(&ad).map(|(a, b)| a * b).sum();
+            if dad.abs() < 1e-30 {
+                break;
+            }
+            let alpha = rr / dad;
+            for k in 0..2 * n {
+                if !pinned(k) {
+                    x[k] += alpha * d[k];
+                    r[k] -= alpha * ad[k];
+                }
+            }
+            let rr_new: f32 = r.iter().map(|v| v * v).sum();
+            let beta = rr_new / rr;
+            rr = rr_new;
+            for k in 0..2 * n {
+                d[k] = r[k] + beta * d[k];
+            }
+        }
+        if x.iter().all(|v| v.is_finite()) {
+            solution = x.chunks(2).map(|c| Vec2::new(c[0], c[1])).collect();
+        }
+    }
+
+    local.into_iter().map(|(key, i)| (key, solution[i])).collect()
+}
+
+#[test]
+fn test_projections_and_unwrap() {
+    use crate::geometry::shape::mesh::AsMesh;
+    use crate::geometry::shape::sphere::Sphere;
+
+    let mesh = crate::geometry::shape::cube::Cube { width: 2.0, height: 1.0, depth: 1.0 }.as_mesh();
+    let boxed = UvModifier { projection: UvProjection::Box, ..Default::default() }.apply(&mesh, Vec3::ONE);
+    // 世界空间下每个面的 UV 尺寸等于面的实际尺寸
+    for tri in boxed.indices.chunks(3) {
+        let uv: [Vec2; 3] = std::array::from_fn(|k| Vec2::from(boxed.tex_coords[tri[k] as usize]));
+        let p: [Vec3; 3] = std::array::from_fn(|k| Vec3::from(boxed.vertices[tri[k] as usize]));
+        let uv_area = (uv[1] - uv[0]).perp_dot(uv[2] - uv[0]).abs();
+        let area = (p[1] - p[0]).cross(p[2] - p[0]).length();
+        assert!((uv_area - area).abs() < 1e-4);
+    }
+
+    let sphere = Sphere { radius: 1.0, col_divisions: 16, row_divisions: 8 }.as_mesh();
+    let unwrapped = UvModifier { projection: UvProjection::Unwrap, world_space: false, ..Default::default() }
+        .apply(&sphere, Vec3::ONE);
+    assert!(unwrapped.tex_coords.iter().all(|uv| (0.0..=1.0).contains(&uv[0]) && (0.0..=1.0).contains(&uv[1])));
+    // 展开后的三角形不翻转
+    let flipped = unwrapped
+        .indices
+        .chunks(3)
+        .filter(|tri| {
+            let uv: [Vec2; 3] = std::array::from_fn(|k| Vec2::from(unwrapped.tex_coords[tri[k] as usize]));
+            (uv[1] - uv[0]).perp_dot(uv[2] - uv[0]) < -1e-7
+        })
+        .count();
+    assert_eq!(flipped, 0);
+}
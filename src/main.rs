@@ -87,9 +87,8 @@ fn main() {
 
                     _cn_font.pop();
                     let now = Instant::now();
-                    let mut dt = (now - last_frame).as_secs_f32();
-                    if dt > 1.0 { dt = 0.0; }
-                    
+                    let dt = (now - last_frame).as_secs_f32();
+
                     last_frame = now;
                     scene.handle_ui_input(ui, &display);
                     scene.step(dt);
@@ -1,5 +1,5 @@
 use glam::{f32::{ Vec3, Mat4 }};
-use std::{fmt::Debug, ops::Index};
+use std::{fmt::Debug, ops::Index, sync::Arc};
 
 use crate::core::math::{ray::Ray, transform::Transform};
 
@@ -7,10 +7,14 @@ pub trait BoundingBox {
     fn get_global_aabb(&self, transform: Mat4) -> AABB;
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum BoundingVolume {
     AABB(AABB),
     Sphere(SphereBox),
+    OBB(OBB),
+    Capsule(Capsule),
+    ConvexHull(ConvexHull),
+    TriMesh(TriMesh),
 }
 
 impl BoundingBox for BoundingVolume { 
@@ -18,11 +22,40 @@ impl BoundingBox for BoundingVolume {
         match self {
             BoundingVolume::AABB(aabb) => aabb.get_global_aabb(transform),
             BoundingVolume::Sphere(sphere) => sphere.get_global_aabb(transform),
+            other => other.bounds().get_global_aabb(transform),
         }
 
     }
 }
 
+impl BoundingVolume {
+    /// 形状在自身坐标系下的轴对齐包围盒
+    pub fn bounds(&self) -> AABB {
+        match self {
+            BoundingVolume::AABB(aabb) => *aabb,
+            BoundingVolume::Sphere(sphere) => AABB::from_sphere(sphere.center, sphere.radius),
+            BoundingVolume::OBB(obb) => {
+                let extent = obb.asxes[0].abs() * obb.half_extents.x
+                    + obb.asxes[1].abs() * obb.half_extents.y
+                    + obb.asxes[2].abs() * obb.half_extents.z;
+                AABB::from_vec(obb.center - extent, obb.center + extent)
+            }
+            BoundingVolume::Capsule(capsule) => AABB::from_vec(
+                capsule.a.min(capsule.b) - capsule.radius,
+                capsule.a.max(capsule.b) + capsule.radius,
+            ),
+            BoundingVolume::ConvexHull(hull) => {
+                let mut aabb = AABB::default();
+                for p in &hull.points {
+                    aabb.union_point_array(p.to_array());
+                }
+                aabb
+            }
+            BoundingVolume::TriMesh(mesh) => mesh.bounds,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct OBB {
     pub center: Vec3,
@@ -31,6 +64,28 @@ pub struct OBB {
 }
 
 
+/// 胶囊体：线段 a-b 膨胀 radius
+#[derive(Debug, Clone, Copy)]
+pub struct Capsule {
+    pub a: Vec3,
+    pub b: Vec3,
+    pub radius: f32,
+}
+
+/// 凸包，points 为世界空间下的凸包顶点
+#[derive(Debug, Clone)]
+pub struct ConvexHull {
+    pub points: Vec<Vec3>,
+}
+
+/// 静态三角网格，三角形保存在局部空间，由 matrix 变换到世界空间
+#[derive(Debug, Clone)]
+pub struct TriMesh {
+    pub triangles: Arc<Vec<[Vec3; 3]>>,
+    pub matrix: Mat4,
+    pub bounds: AABB,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SphereBox {
     pub center: Vec3,
@@ -73,14 +128,18 @@ impl Default for OBB {
 
 impl OBB {
     pub fn from_transform(aabb: &AABB, transform: &Transform) -> Self {
-        let center = transform.position +  (aabb.min + aabb.max) * 0.5;
-        let half_extents = (glam::f32::Mat4::from_scale(transform.scale) * aabb.get_half_extents().extend(1.0)).truncate();
+        let center = transform.get_matrix().transform_point3(aabb.center());
+        let half_extents = aabb.get_half_extents() * transform.scale.abs();
         let mut asxes = [Vec3::X, Vec3::Y, Vec3::Z];
         for i in 0..3 { 
             asxes[i] = transform.rotation * asxes[i];
         }
         Self { center, half_extents, asxes }
     }
+
+    pub fn from_aabb(aabb: &AABB) -> Self {
+        Self { center: aabb.center(), half_extents: aabb.get_half_extents(), ..Default::default() }
+    }
 }
 
 
@@ -202,10 +261,11 @@ impl AABB {
 
 
     pub fn union_bounding_volume(&mut self, b: &BoundingVolume) {
-        match b {
-            BoundingVolume::AABB(aabb) => self.union_aabb(aabb),
-            BoundingVolume::Sphere(sphere) => self.union_aabb(&AABB::from_sphere(sphere.center, sphere.radius)),
-        }
+        self.union_aabb(&b.bounds());
+    }
+
+    pub fn overlaps(&self, b: &AABB) -> bool {
+        self.min.cmple(b.max).all() && b.min.cmple(self.max).all()
     }
 
     pub fn union_aabb(&mut self, b: &AABB) {
@@ -0,0 +1,285 @@
+use crate::{
+    physics::{boundingbox::{BoundingVolume, Capsule}, collision::board::collide},
+    scene::world::BodyType,
+};
+
+use glam::f32::Vec3;
+
+/// 单次移动不超过半径的这个比例，避免一步穿过薄墙
+const MAX_MOVE_FRACTION: f32 = 0.5;
+/// 解除穿透的迭代次数
+const DEPENETRATION_ITERATIONS: usize = 4;
+/// 小于该深度的穿透忽略，避免在地面上抖动
+const CONTACT_EPSILON: f32 = 1e-4;
+
+/// 角色控制器周围的碰撞体
+pub struct Obstacle {
+    pub volume: BoundingVolume,
+    /// 物体的速度，站在运动平台上时随之移动
+    pub velocity: Vec3,
+    pub body_type: BodyType,
+}
+
+/// 一次碰撞滑动的结果
+struct Slide {
+    eye: Vec3,
+    velocity: Vec3,
+    /// 脚下可行走的地面：(法线, 地面物体的速度)
+    ground: Option<(Vec3, Vec3)>,
+}
+
+/// 运动学角色控制器：胶囊体，碰撞后沿表面滑动，支持台阶、坡度限制与土狼时间
+/// 位置以眼睛 (胶囊顶端) 为准，脚底在眼睛下方 2 * half_height 处
+#[derive(Clone, Debug)]
+pub struct CharacterController {
+    pub radius: f32,
+    /// 能直接迈上的台阶高度
+    pub step_height: f32,
+    /// 可行走的最大坡度 (弧度)
+    pub max_slope: f32,
+    /// 离开地面后仍允许起跳的时间
+    pub coyote_time: f32,
+    /// 着地时的水平阻力系数，松开按键后角色会停下
+    pub ground_drag: f32,
+    /// 角色自身的速度，不含台阶和平台带来的位移
+    pub velocity: Vec3,
+    pub grounded: bool,
+    pub ground_normal: Vec3,
+    ground_velocity: Vec3,
+    air_time: f32,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            radius: 0.25,
+            step_height: 0.35,
+            max_slope: 45f32.to_radians(),
+            coyote_time: 0.12,
+            ground_drag: 5.5,
+            velocity: Vec3::ZERO,
+            grounded: false,
+            ground_normal: Vec3::Y,
+            ground_velocity: Vec3::ZERO,
+            air_time: f32::INFINITY,
+        }
+    }
+}
+
+impl CharacterController {
+    /// 眼睛位于 eye、半高为 half_height 时的胶囊体
+    pub fn capsule(&self, eye: Vec3, half_height: f32) -> Capsule {
+        let radius = self.radius.min(half_height);
+        Capsule {
+            a: eye - Vec3::Y * (2.0 * half_height - radius),
+            b: eye - Vec3::Y * radius,
+            radius,
+        }
+    }
+
+    pub fn can_jump(&self) -> bool {
+        self.air_time <= self.coyote_time
+    }
+
+    /// 起跳，土狼时间随之失效
+    pub fn jump(&mut self, speed: f32) {
+        self.velocity.y = speed;
+        self.grounded = false;
+        self.air_time = f32::INFINITY;
+    }
+
+    /// 该尺寸的胶囊能否放在 eye 处而不与静止物体重叠，用于判断能否站起
+    pub fn fits(&self, eye: Vec3, half_height: f32, obstacles: &[Obstacle]) -> bool {
+        let capsule = BoundingVolume::Capsule(self.capsule(eye, half_height));
+        obstacles
+            .iter()
+            .filter(|o| o.body_type != BodyType::Dynamic)
+            .all(|o| collide(&capsule, &o.volume).is_none_or(|c| c.penetration <= CONTACT_EPSILON))
+    }
+
+    /// 推进一个物理步：积分加速度 (输入与重力之和) 与地面阻力，碰撞滑动后返回新的眼睛位置
+    pub fn update(
+        &mut self,
+        eye: Vec3,
+        half_height: f32,
+        acceleration: Vec3,
+        obstacles: &[Obstacle],
+        dt: f32,
+    ) -> Vec3 {
+        self.velocity += acceleration * dt;
+        if self.grounded {
+            let horizontal = Vec3::new(self.velocity.x, 0.0, self.velocity.z);
+            self.velocity -= horizontal * (self.ground_drag * dt).min(1.0);
+        }
+        let motion = (self.velocity + self.ground_velocity) * dt;
+
+        let mut result = self.slide(eye, half_height, motion, self.velocity, obstacles);
+        let horizontal = Vec3::new(motion.x, 0.0, motion.z);
+        let progress = |e: Vec3| Vec3::new(e.x - eye.x, 0.0, e.z - eye.z).length();
+        if self.grounded && progress(result.eye) < horizontal.length() * 0.9 {
+            // 台阶：抬高、平移、落下。胶囊底部是半球，至少要前进到能稳稳站在台阶边缘上
+            let reach = self.radius * (1.0 - self.max_slope.sin()) + 0.01;
+            let forward = horizontal.normalize() * horizontal.length().max(reach);
+            let up = self.slide(eye, half_height, Vec3::Y * self.step_height, Vec3::ZERO, obstacles);
+            let moved = self.slide(up.eye, half_height, forward, Vec3::ZERO, obstacles);
+            let drop = up.eye.y - eye.y + self.step_height;
+            let down = self.slide(moved.eye, half_height, -Vec3::Y * drop, Vec3::ZERO, obstacles);
+            if down.ground.is_some() && down.eye.y > result.eye.y + 0.01 && progress(down.eye) > progress(result.eye) {
+                result = Slide {
+                    eye: down.eye,
+                    velocity: Vec3::new(self.velocity.x, 0.0, self.velocity.z),
+                    ground: down.ground,
+                };
+            }
+        }
+        if self.grounded && result.ground.is_none() && self.velocity.y <= 0.0 {
+            // 贴地：下坡或走下台阶时不离开地面
+            let down = self.slide(result.eye, half_height, -Vec3::Y * self.step_height, result.velocity, obstacles);
+            if down.ground.is_some() {
+                result = down;
+            }
+        }
+
+        self.velocity = result.velocity;
+        match result.ground {
+            Some((normal, velocity)) => {
+                self.grounded = true;
+                self.ground_normal = normal;
+                self.ground_velocity = velocity;
+                self.air_time = 0.0;
+            }
+            None => {
+                // 离开平台时保留它的速度
+                if self.grounded {
+                    self.velocity += self.ground_velocity;
+                }
+                self.grounded = false;
+                self.ground_normal = Vec3::Y;
+                self.ground_velocity = Vec3::ZERO;
+                self.air_time += dt;
+            }
+        }
+        result.eye
+    }
+
+    /// 分段移动并解除穿透，速度和剩余位移在碰到的表面上被裁剪
+    fn slide(&self, mut eye: Vec3, half_height: f32, motion: Vec3, mut velocity: Vec3, obstacles: &[Obstacle]) -> Slide {
+        let max_move = self.radius * MAX_MOVE_FRACTION;
+        let segments = (motion.length() / max_move).ceil().max(1.0) as usize;
+        let walkable = self.max_slope.cos();
+        let mut ground: Option<(Vec3, Vec3)> = None;
+        // 剩余的每段位移与速度一样在碰到的表面上裁剪
+        let mut step = motion / segments as f32;
+        for _ in 0..segments {
+            eye += step;
+            for _ in 0..DEPENETRATION_ITERATIONS {
+                let mut moved = false;
+                for obstacle in obstacles {
+                    let capsule = BoundingVolume::Capsule(self.capsule(eye, half_height));
+                    let Some(contact) = collide(&capsule, &obstacle.volume) else { continue };
+                    if contact.penetration <= CONTACT_EPSILON {
+                        continue;
+                    }
+                    // 法线从角色指向障碍物，取反得到把角色推出去的方向
+                    let normal = -contact.normal;
+                    if normal.y >= walkable {
+                        // 可行走的地面只在竖直方向推开，站在坡上不会下滑
+                        eye.y += contact.penetration / normal.y;
+                        velocity.y = velocity.y.max(0.0);
+                        step.y = step.y.max(0.0);
+                        if ground.is_none_or(|(n, _)| normal.y > n.y) {
+                            ground = Some((normal, obstacle.velocity));
+                        }
+                    } else if obstacle.body_type == BodyType::Dynamic {
+                        // 侧面碰到的动态物体交给求解器推开
+                        continue;
+                    } else if normal.y > 0.0 {
+                        // 陡坡按水平方向处理，角色无法走上去
+                        let side = Vec3::new(normal.x, 0.0, normal.z);
+                        let length = side.length();
+                        if length < f32::EPSILON {
+                            continue;
+                        }
+                        let side = side / length;
+                        eye += side * (contact.penetration / length);
+                        velocity -= side * velocity.dot(side).min(0.0);
+                        step -= side * step.dot(side).min(0.0);
+                    } else {
+                        eye += normal * contact.penetration;
+                        velocity -= normal * velocity.dot(normal).min(0.0);
+                        step -= normal * step.dot(normal).min(0.0);
+                    }
+                    moved = true;
+                }
+                if !moved {
+                    break;
+                }
+            }
+        }
+        Slide { eye, velocity, ground }
+    }
+}
+
+#[test]
+fn test_character_steps_and_slopes() {
+    use crate::{core::math::transform::Transform, physics::boundingbox::{AABB, OBB}};
+
+    let block = |center: Vec3, half: Vec3, rotation: glam::Quat| {
+        let mut transform = Transform::default();
+        transform.position = center;
+        transform.rotation = rotation;
+        Obstacle {
+            volume: BoundingVolume::OBB(OBB::from_transform(&AABB::from_vec(-half, half), &transform)),
+            velocity: Vec3::ZERO,
+            body_type: BodyType::Static,
+        }
+    };
+    let floor = || block(Vec3::new(0.0, -0.5, 0.0), Vec3::new(20.0, 0.5, 20.0), glam::Quat::IDENTITY);
+    let ledge = |height: f32| block(Vec3::new(5.5, height * 0.5, 0.0), Vec3::new(4.5, height * 0.5, 5.0), glam::Quat::IDENTITY);
+    // 沿 +x 方向行走 2 秒，返回最终眼睛位置与控制器
+    let walk = |obstacles: &[Obstacle]| {
+        let mut controller = CharacterController::default();
+        let mut eye = Vec3::new(0.0, 1.7, 0.0);
+        for _ in 0..120 {
+            eye = controller.update(eye, 0.85, Vec3::new(20.0, -9.8, 0.0), obstacles, 1.0 / 60.0);
+        }
+        (eye, controller)
+    };
+
+    // 平地上着地，可以起跳
+    let (eye, controller) = walk(&[floor()]);
+    assert!(controller.grounded && controller.can_jump());
+    assert!((eye.y - 1.7).abs() < 0.01 && eye.x > 3.0, "eye = {eye}");
+
+    // 0.3 m 的台阶能迈上去，0.6 m 的墙挡住
+    let (eye, _) = walk(&[floor(), ledge(0.3)]);
+    assert!(eye.x > 3.0 && (eye.y - 2.0).abs() < 0.02, "eye = {eye}");
+    let (eye, _) = walk(&[floor(), ledge(0.6)]);
+    assert!(eye.x < 1.0, "eye = {eye}");
+
+    // 20° 的坡能走上去，60° 的坡走不上去
+    // 坡面从 x = 1 处的地面开始升高
+    let ramp = |degrees: f32| {
+        let rotation = glam::Quat::from_rotation_z(degrees.to_radians());
+        let half = Vec3::new(5.0, 0.5, 5.0);
+        block(Vec3::new(1.0, 0.0, 0.0) - rotation * Vec3::new(-half.x, half.y, 0.0), half, rotation)
+    };
+    let (eye, controller) = walk(&[floor(), ramp(20.0)]);
+    assert!(controller.grounded && eye.y > 2.0, "eye = {eye}");
+    assert!((controller.ground_normal.y - 20f32.to_radians().cos()).abs() < 0.01);
+    let (eye, _) = walk(&[floor(), ramp(60.0)]);
+    assert!(eye.y < 1.8, "eye = {eye}");
+
+    // 走出平台边缘后短时间内仍能起跳
+    let platform = [block(Vec3::new(-10.0, -0.5, 0.0), Vec3::new(10.0, 0.5, 5.0), glam::Quat::IDENTITY)];
+    let mut controller = CharacterController::default();
+    let mut eye = Vec3::new(-0.5, 1.7, 0.0);
+    let mut airborne = Vec::new();
+    for _ in 0..60 {
+        eye = controller.update(eye, 0.85, Vec3::new(20.0, -9.8, 0.0), &platform, 1.0 / 60.0);
+        if !controller.grounded {
+            airborne.push(controller.can_jump());
+        }
+    }
+    assert!(airborne[0] && !airborne[airborne.len() - 1]);
+}
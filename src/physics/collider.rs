@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use glam::f32::Vec3;
+
+use crate::core::math::transform::Transform;
+use crate::geometry::shape::mesh::Mesh;
+use crate::physics::boundingbox::{
+    AABB, BoundingVolume, Capsule, ConvexHull, OBB, SphereBox, TriMesh,
+};
+
+/// 物体使用的碰撞形状
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum ColliderShape {
+    /// 按网格的包围体：球体用球，其余用有向包围盒
+    #[default]
+    Auto,
+    Box,
+    Sphere,
+    Capsule,
+    ConvexHull,
+    /// 精确三角网格，只用于静态物体
+    TriMesh,
+}
+
+impl ColliderShape {
+    pub const ALL: [ColliderShape; 6] = [
+        ColliderShape::Auto,
+        ColliderShape::Box,
+        ColliderShape::Sphere,
+        ColliderShape::Capsule,
+        ColliderShape::ConvexHull,
+        ColliderShape::TriMesh,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColliderShape::Auto => "自动",
+            ColliderShape::Box => "有向盒",
+            ColliderShape::Sphere => "球体",
+            ColliderShape::Capsule => "胶囊体",
+            ColliderShape::ConvexHull => "凸包",
+            ColliderShape::TriMesh => "三角网格",
+        }
+    }
+}
+
+/// 由网格计算的局部空间碰撞数据，网格或形状改变时才重建
+#[derive(Clone, Default)]
+pub struct ColliderCache {
+    /// 已构建的形状，None 表示网格改变后尚未重建
+    built: Option<ColliderShape>,
+    pub hull: Arc<Vec<Vec3>>,
+    pub triangles: Arc<Vec<[Vec3; 3]>>,
+}
+
+impl ColliderCache {
+    /// 网格改变后调用，下一次 update 时重建
+    pub fn invalidate(&mut self) {
+        self.built = None;
+    }
+
+    pub fn update(&mut self, mesh: &Mesh, shape: ColliderShape) {
+        if !matches!(shape, ColliderShape::ConvexHull | ColliderShape::TriMesh) || self.built == Some(shape) {
+            return;
+        }
+        self.built = Some(shape);
+
+        let points: Vec<Vec3> = mesh.vertices.iter().map(|v| Vec3::from(*v)).collect();
+        if shape == ColliderShape::ConvexHull {
+            self.hull = Arc::new(convex_hull(&points));
+            self.triangles = Arc::default();
+        } else {
+            self.triangles = Arc::new(
+                mesh.indices
+                    .chunks_exact(3)
+                    .filter(|t| t.iter().all(|&i| (i as usize) < points.len()))
+                    .map(|t| [0, 1, 2].map(|k| points[t[k] as usize]))
+                    .collect(),
+            );
+            self.hull = Arc::default();
+        }
+    }
+
+    /// 生成世界空间的碰撞体，local 为网格自身的包围体
+    pub fn world_volume(
+        &self,
+        shape: ColliderShape,
+        local: &BoundingVolume,
+        transform: &Transform,
+    ) -> BoundingVolume {
+        let aabb = local.bounds();
+        let scaled = aabb.get_half_extents() * transform.scale.abs();
+        let center = transform.get_matrix().transform_point3(aabb.center());
+        match shape {
+            ColliderShape::Auto => match local {
+                BoundingVolume::Sphere(sphere) => BoundingVolume::Sphere(
+                    sphere.get_global_sphere(transform.scale, transform.position),
+                ),
+                _ => BoundingVolume::OBB(OBB::from_transform(&aabb, transform)),
+            },
+            ColliderShape::Box => BoundingVolume::OBB(OBB::from_transform(&aabb, transform)),
+            ColliderShape::Sphere => {
+                BoundingVolume::Sphere(SphereBox::new(center, scaled.max_element()))
+            }
+            ColliderShape::Capsule => {
+                // 沿最长轴放置，半径取另外两轴的较大者
+                let axis = scaled.max_position();
+                let radius = (0..3)
+                    .filter(|&i| i != axis)
+                    .map(|i| scaled[i])
+                    .fold(0.0, f32::max);
+                let half = (scaled[axis] - radius).max(0.0);
+                let dir = transform.rotation * Vec3::AXES[axis];
+                BoundingVolume::Capsule(Capsule {
+                    a: center - dir * half,
+                    b: center + dir * half,
+                    radius,
+                })
+            }
+            ColliderShape::ConvexHull if !self.hull.is_empty() => {
+                let matrix = transform.get_matrix();
+                BoundingVolume::ConvexHull(ConvexHull {
+                    points: self.hull.iter().map(|p| matrix.transform_point3(*p)).collect(),
+                })
+            }
+            ColliderShape::TriMesh if !self.triangles.is_empty() => {
+                let matrix = transform.get_matrix();
+                BoundingVolume::TriMesh(TriMesh {
+                    triangles: self.triangles.clone(),
+                    matrix,
+                    bounds: aabb.get_global_aabb(matrix),
+                })
+            }
+            // 缓存尚未生成时退化为有向包围盒
+            _ => BoundingVolume::OBB(OBB::from_transform(&aabb, transform)),
+        }
+    }
+}
+
+impl ColliderCache {
+    /// 缩放后的碰撞形状半尺寸，凸包按其包围盒近似
+    fn half_extents(&self, shape: ColliderShape, local: &BoundingVolume, scale: Vec3) -> Vec3 {
+        let aabb = match shape {
+            ColliderShape::ConvexHull if !self.hull.is_empty() => {
+                BoundingVolume::ConvexHull(ConvexHull { points: self.hull.to_vec() }).bounds()
+            }
+            _ => local.bounds(),
+        };
+        aabb.get_half_extents() * scale.abs()
+    }
+
+    /// 碰撞形状的体积，用于由密度计算质量
+    pub fn volume(&self, shape: ColliderShape, local: &BoundingVolume, scale: Vec3) -> f32 {
+        let half = self.half_extents(shape, local, scale);
+        let sphere = |r: f32| 4.0 / 3.0 * std::f32::consts::PI * r * r * r;
+        match (shape, local) {
+            (ColliderShape::Auto, BoundingVolume::Sphere(s)) => sphere(s.radius * scale.x.abs()),
+            (ColliderShape::Sphere, _) => sphere(half.max_element()),
+            (ColliderShape::Capsule, _) => {
+                let axis = half.max_position();
+                let r = (0..3).filter(|&i| i != axis).map(|i| half[i]).fold(0.0, f32::max);
+                let h = (half[axis] - r).max(0.0);
+                std::f32::consts::PI * r * r * 2.0 * h + sphere(r)
+            }
+            _ => 8.0 * half.x * half.y * half.z,
+        }
+    }
+
+    /// 碰撞形状在物体坐标系下的主转动惯量，凸包按其包围盒近似
+    pub fn inertia(&self, shape: ColliderShape, local: &BoundingVolume, scale: Vec3, mass: f32) -> Vec3 {
+        let half = self.half_extents(shape, local, scale);
+        let sphere = |r: f32| Vec3::splat(0.4 * mass * r * r);
+        match (shape, local) {
+            (ColliderShape::Auto, BoundingVolume::Sphere(s)) => sphere(s.radius * scale.x.abs()),
+            (ColliderShape::Sphere, _) => sphere(half.max_element()),
+            (ColliderShape::Capsule, _) => {
+                // 圆柱与两个半球按体积分配质量
+                let axis = half.max_position();
+                let r = (0..3).filter(|&i| i != axis).map(|i| half[i]).fold(0.0, f32::max);
+                let h = (half[axis] - r).max(0.0);
+                let cylinder = 2.0 * h;
+                let sphere_volume = 4.0 / 3.0 * r;
+                let mc = mass * cylinder / (cylinder + sphere_volume).max(f32::EPSILON);
+                let ms = mass - mc;
+                let along = mc * r * r * 0.5 + ms * 0.4 * r * r;
+                let across = mc * (r * r * 0.25 + cylinder * cylinder / 12.0)
+                    + ms * (0.4 * r * r + h * h + 0.75 * h * r);
+                let mut inertia = Vec3::splat(across);
+                inertia[axis] = along;
+                inertia
+            }
+            _ => {
+                let s = half * half * 4.0;
+                Vec3::new(s.y + s.z, s.x + s.z, s.x + s.y) * (mass / 12.0)
+            }
+        }
+    }
+}
+
+/// 增量法求三维凸包，返回凸包顶点；点集共面时原样返回去重后的点
+pub fn convex_hull(points: &[Vec3]) -> Vec<Vec3> {
+    let mut seen = HashMap::new();
+    let points: Vec<Vec3> = points
+        .iter()
+        .filter(|p| p.is_finite())
+        .filter(|p| seen.insert((**p * 1e5).round().as_ivec3(), ()).is_none())
+        .copied()
+        .collect();
+    if points.len() < 4 {
+        return points;
+    }
+    let mut bounds = AABB::default();
+    for p in &points {
+        bounds.union_point_array(p.to_array());
+    }
+    let eps = (bounds.max - bounds.min).max_element() * 1e-5;
+
+    // 初始四面体：x 最小的点、离它最远的点、离直线最远的点、离平面最远的点
+    let farthest = |f: &dyn Fn(Vec3) -> f32| {
+        (0..points.len())
+            .max_by(|&a, &b| f(points[a]).total_cmp(&f(points[b])))
+            .unwrap()
+    };
+    let i0 = farthest(&|p| -p.x);
+    let i1 = farthest(&|p| p.distance_squared(points[i0]));
+    let line = (points[i1] - points[i0]).normalize_or_zero();
+    let i2 = farthest(&|p| (p - points[i0]).cross(line).length_squared());
+    let plane = (points[i1] - points[i0]).cross(points[i2] - points[i0]).normalize_or_zero();
+    let i3 = farthest(&|p| (p - points[i0]).dot(plane).abs());
+    if (points[i3] - points[i0]).dot(plane).abs() <= eps || plane == Vec3::ZERO {
+        return points;
+    }
+
+    let centroid = (points[i0] + points[i1] + points[i2] + points[i3]) * 0.25;
+    let outward = |f: [usize; 3]| {
+        let [a, b, c] = f.map(|i| points[i]);
+        if (b - a).cross(c - a).dot(a - centroid) < 0.0 { [f[0], f[2], f[1]] } else { f }
+    };
+    let mut faces: Vec<[usize; 3]> = [[i0, i1, i2], [i0, i1, i3], [i0, i2, i3], [i1, i2, i3]]
+        .into_iter()
+        .map(outward)
+        .collect();
+
+    for (i, &p) in points.iter().enumerate() {
+        let visible = |f: &[usize; 3]| {
+            let [a, b, c] = f.map(|k| points[k]);
+            let n = (b - a).cross(c - a);
+            n.dot(p - a) > eps * n.length()
+        };
+        if !faces.iter().any(visible) {
+            continue;
+        }
+        // 可见面的有向边中，反向边不在可见面里的构成地平线
+        let mut edges = Vec::new();
+        faces.retain(|f| {
+            if !visible(f) {
+                return true;
+            }
+            edges.extend([(f[0], f[1]), (f[1], f[2]), (f[2], f[0])]);
+            false
+        });
+        for &(u, v) in &edges {
+            if !edges.contains(&(v, u)) {
+                faces.push([u, v, i]);
+            }
+        }
+    }
+
+    let mut used: Vec<usize> = faces.iter().flatten().copied().collect();
+    used.sort_unstable();
+    used.dedup();
+    used.into_iter().map(|i| points[i]).collect()
+}
+
+#[test]
+fn test_convex_hull_of_cube() {
+    use crate::geometry::shape::{cube::Cube, mesh::AsMesh};
+    let mut mesh = Cube { width: 1.0, height: 1.0, depth: 1.0 }.as_mesh();
+    // 内部的点不应进入凸包
+    mesh.vertices.extend([[0.1, 0.2, -0.3], [0.0, 0.0, 0.0]]);
+    let hull = convex_hull(&mesh.vertices.iter().map(|v| Vec3::from(*v)).collect::<Vec<_>>());
+    assert_eq!(hull.len(), 8);
+
+    let mut cache = ColliderCache::default();
+    cache.update(&mesh, ColliderShape::ConvexHull);
+    let mut transform = Transform::default();
+    transform.position = Vec3::new(0.0, 2.0, 0.0);
+    let BoundingVolume::ConvexHull(world) =
+        cache.world_volume(ColliderShape::ConvexHull, &mesh.bounding_volume, &transform)
+    else {
+        panic!("expected convex hull");
+    };
+    assert!(world.points.iter().all(|p| p.y >= 1.0 - 1e-5));
+
+    // 网格改变后要显式失效才会重建
+    mesh.vertices.push([2.0, 0.0, 0.0]);
+    cache.update(&mesh, ColliderShape::ConvexHull);
+    assert_eq!(cache.hull.len(), 8);
+    cache.invalidate();
+    cache.update(&mesh, ColliderShape::ConvexHull);
+    assert_eq!(cache.hull.len(), 9);
+}
@@ -1,16 +1,34 @@
-use crate::physics::{boundingbox::{BoundingVolume}, collision::narrow::{aabb_vs_aabb, aabb_vs_sphere, sphere_vs_sphere}, rigid::{Contact}};
+use crate::physics::{boundingbox::{BoundingVolume, OBB}, collision::narrow::{aabb_vs_aabb, aabb_vs_sphere, capsule_vs_capsule, capsule_vs_sphere, flipped, gjk_epa, obb_vs_obb, obb_vs_sphere, sphere_vs_sphere, trimesh_vs_convex, with_manifold}, rigid::{Contact}};
 
+/// 按形状组合分派窄相检测，返回带接触点的流形，法线从 a 指向 b
 pub fn collide(a: &BoundingVolume, b: &BoundingVolume) -> Option<Contact> {
+    match (a, b) {
+        // 静态网格之间不做检测
+        (BoundingVolume::TriMesh(_), BoundingVolume::TriMesh(_)) => None,
+        (BoundingVolume::TriMesh(mesh), other) => trimesh_vs_convex(mesh, other),
+        (other, BoundingVolume::TriMesh(mesh)) => trimesh_vs_convex(mesh, other).map(flipped),
+        _ => convex_contact(a, b).map(|contact| with_manifold(a, b, contact)),
+    }
+}
+
+fn convex_contact(a: &BoundingVolume, b: &BoundingVolume) -> Option<Contact> {
     match (a, b) {
         (BoundingVolume::AABB(a), BoundingVolume::AABB(b)) => aabb_vs_aabb(a, b),
         (BoundingVolume::AABB(a), BoundingVolume::Sphere(b)) => aabb_vs_sphere(a, b),
-        (BoundingVolume::Sphere(a), BoundingVolume::AABB(b)) => {
-            aabb_vs_sphere(b, a).map(|mut c| {
-                c.normal = -c.normal;
-                c
-            })
-        },
+        (BoundingVolume::Sphere(a), BoundingVolume::AABB(b)) => aabb_vs_sphere(b, a).map(flipped),
         (BoundingVolume::Sphere(a), BoundingVolume::Sphere(b)) => sphere_vs_sphere(a, b),
-        _ => None,
+
+        (BoundingVolume::OBB(a), BoundingVolume::OBB(b)) => obb_vs_obb(a, b),
+        (BoundingVolume::AABB(a), BoundingVolume::OBB(b)) => obb_vs_obb(&OBB::from_aabb(a), b),
+        (BoundingVolume::OBB(a), BoundingVolume::AABB(b)) => obb_vs_obb(a, &OBB::from_aabb(b)),
+        (BoundingVolume::OBB(a), BoundingVolume::Sphere(b)) => obb_vs_sphere(a, b),
+        (BoundingVolume::Sphere(a), BoundingVolume::OBB(b)) => obb_vs_sphere(b, a).map(flipped),
+
+        (BoundingVolume::Capsule(a), BoundingVolume::Capsule(b)) => capsule_vs_capsule(a, b),
+        (BoundingVolume::Capsule(a), BoundingVolume::Sphere(b)) => capsule_vs_sphere(a, b),
+        (BoundingVolume::Sphere(a), BoundingVolume::Capsule(b)) => capsule_vs_sphere(b, a).map(flipped),
+
+        // 其余凸体组合 (胶囊、凸包与盒子等) 统一用 GJK/EPA
+        (a, b) => gjk_epa(a, b),
     }
 }
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use crate::{physics::boundingbox::{AABB, maximum_dim}, scene::world::BodyHandle};
+
+/// 静态树叶节点最多容纳的物体数
+const LEAF_SIZE: usize = 4;
+
+/// 一个参与宽相检测的物体：句柄、世界空间包围盒，以及是否为静态 (不会移动)
+#[derive(Clone, Copy, Debug)]
+pub struct BroadProxy {
+    pub handle: BodyHandle,
+    pub aabb: AABB,
+    pub is_static: bool,
+}
+
+enum Node {
+    Leaf { aabb: AABB, start: usize, end: usize },
+    Branch { aabb: AABB, left: usize, right: usize },
+}
+
+impl Node {
+    fn aabb(&self) -> &AABB {
+        match self {
+            Node::Leaf { aabb, .. } | Node::Branch { aabb, .. } => aabb,
+        }
+    }
+}
+
+/// 静态物体的包围盒树，只有静态物体增删或移动时才重建
+#[derive(Default)]
+struct StaticTree {
+    /// 建树时的静态物体，用于判断是否需要重建
+    snapshot: Vec<(BodyHandle, AABB)>,
+    /// 按树的叶节点顺序排列的静态物体
+    proxies: Vec<(BodyHandle, AABB)>,
+    nodes: Vec<Node>,
+}
+
+impl StaticTree {
+    fn is_current(&self, proxies: &[(BodyHandle, AABB)]) -> bool {
+        self.snapshot.len() == proxies.len()
+            && self.snapshot.iter().zip(proxies).all(|((ha, a), (hb, b))| {
+                ha == hb && a.min == b.min && a.max == b.max
+            })
+    }
+
+    fn rebuild(&mut self, proxies: Vec<(BodyHandle, AABB)>) {
+        self.proxies = proxies.clone();
+        self.snapshot = proxies;
+        self.nodes.clear();
+        if !self.proxies.is_empty() {
+            let len = self.proxies.len();
+            self.build(0, len);
+        }
+    }
+
+    /// 自顶向下按最长轴的中位数划分
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let mut aabb = AABB::default();
+        for (_, b) in &self.proxies[start..end] {
+            aabb.union_aabb(b);
+        }
+        let index = self.nodes.len();
+        if end - start <= LEAF_SIZE {
+            self.nodes.push(Node::Leaf { aabb, start, end });
+            return index;
+        }
+        let mut centers = AABB::default();
+        for (_, b) in &self.proxies[start..end] {
+            centers.union_point_array(b.center().to_array());
+        }
+        let axis = maximum_dim(&centers);
+        let mid = (start + end) / 2;
+        self.proxies[start..end].select_nth_unstable_by(mid - start, |(_, a), (_, b)| {
+            a.center()[axis].total_cmp(&b.center()[axis])
+        });
+        self.nodes.push(Node::Leaf { aabb, start, end });
+        let left = self.build(start, mid);
+        let right = self.build(mid, end);
+        self.nodes[index] = Node::Branch { aabb, left, right };
+        index
+    }
+
+    /// 收集包围盒通过 test 的静态物体，test 对节点包围盒同样适用
+    fn query(&self, test: impl Fn(&AABB) -> bool, out: &mut Vec<BodyHandle>) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !test(node.aabb()) {
+                continue;
+            }
+            match node {
+                Node::Leaf { start, end, .. } => {
+                    out.extend(
+                        self.proxies[*start..*end]
+                            .iter()
+                            .filter(|(_, b)| test(b))
+                            .map(|(h, _)| *h),
+                    );
+                }
+                Node::Branch { left, right, .. } => stack.extend([*left, *right]),
+            }
+        }
+    }
+}
+
+/// 宽相：会移动的物体之间用扫描裁剪，它们与静态物体之间查询静态树
+#[derive(Default)]
+pub struct BroadPhase {
+    statics: StaticTree,
+    /// 静态树重建的次数，便于观察
+    pub rebuilds: usize,
+}
+
+impl BroadPhase {
+    /// 返回包围盒重叠的候选对 (i, j)，i < j 为 proxies 的下标；静态物体之间不配对
+    pub fn find_pairs(&mut self, proxies: &[BroadProxy]) -> Vec<(usize, usize)> {
+        let statics: Vec<(BodyHandle, AABB)> = proxies
+            .iter()
+            .filter(|p| p.is_static)
+            .map(|p| (p.handle, p.aabb))
+            .collect();
+        if !self.statics.is_current(&statics) {
+            self.statics.rebuild(statics);
+            self.rebuilds += 1;
+        }
+
+        let dynamics: Vec<usize> = (0..proxies.len()).filter(|&i| !proxies[i].is_static).collect();
+        let mut pairs = Vec::new();
+
+        // 动态-动态：沿中心分布最广的轴排序后扫描
+        let mut centers = AABB::default();
+        for &i in &dynamics {
+            centers.union_point_array(proxies[i].aabb.center().to_array());
+        }
+        let axis = maximum_dim(&centers);
+        let mut sorted = dynamics.clone();
+        sorted.sort_by(|&a, &b| proxies[a].aabb.min[axis].total_cmp(&proxies[b].aabb.min[axis]));
+        let mut active: Vec<usize> = Vec::new();
+        for &i in &sorted {
+            let aabb = &proxies[i].aabb;
+            active.retain(|&j| proxies[j].aabb.max[axis] >= aabb.min[axis]);
+            for &j in &active {
+                if proxies[j].aabb.overlaps(aabb) {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+            active.push(i);
+        }
+
+        // 动态-静态：查询静态树，再把句柄映射回下标
+        let static_index: HashMap<BodyHandle, usize> = (0..proxies.len())
+            .filter(|&i| proxies[i].is_static)
+            .map(|i| (proxies[i].handle, i))
+            .collect();
+        let mut hits = Vec::new();
+        for &i in &dynamics {
+            hits.clear();
+            self.statics.query(|b| b.overlaps(&proxies[i].aabb), &mut hits);
+            for handle in &hits {
+                if let Some(&j) = static_index.get(handle) {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// 场景查询：返回包围盒通过 test 的 proxies 下标 (升序)
+    /// 静态物体没有变化时走静态树，否则逐个检测；test 须对包含关系单调，例如重叠或射线相交
+    pub fn query(&self, proxies: &[BroadProxy], test: impl Fn(&AABB) -> bool) -> Vec<usize> {
+        let statics: Vec<(BodyHandle, AABB)> = proxies
+            .iter()
+            .filter(|p| p.is_static)
+            .map(|p| (p.handle, p.aabb))
+            .collect();
+        if !self.statics.is_current(&statics) {
+            return (0..proxies.len()).filter(|&i| test(&proxies[i].aabb)).collect();
+        }
+        let static_index: HashMap<BodyHandle, usize> = (0..proxies.len())
+            .filter(|&i| proxies[i].is_static)
+            .map(|i| (proxies[i].handle, i))
+            .collect();
+        let mut hits = Vec::new();
+        self.statics.query(&test, &mut hits);
+        let mut result: Vec<usize> = hits.iter().filter_map(|h| static_index.get(h).copied()).collect();
+        result.extend((0..proxies.len()).filter(|&i| !proxies[i].is_static && test(&proxies[i].aabb)));
+        result.sort_unstable();
+        result
+    }
+}
+
+#[test]
+fn test_broad_phase_matches_brute_force() {
+    use glam::Vec3;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut proxies: Vec<BroadProxy> = (0..200)
+        .map(|i| {
+            let center = Vec3::new(rng.gen_range(-10.0..10.0), rng.gen_range(-2.0..2.0), rng.gen_range(-10.0..10.0));
+            let half = Vec3::new(rng.gen_range(0.1..1.0), rng.gen_range(0.1..1.0), rng.gen_range(0.1..1.0));
+            BroadProxy {
+                handle: BodyHandle::Object(i),
+                aabb: AABB::from_vec(center - half, center + half),
+                is_static: i % 3 == 0,
+            }
+        })
+        .collect();
+
+    let brute = |proxies: &[BroadProxy]| {
+        let mut pairs = Vec::new();
+        for i in 0..proxies.len() {
+            for j in i + 1..proxies.len() {
+                if !(proxies[i].is_static && proxies[j].is_static) && proxies[i].aabb.overlaps(&proxies[j].aabb) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    };
+
+    let mut broad = BroadPhase::default();
+    assert_eq!(broad.find_pairs(&proxies), brute(&proxies));
+    // 只移动动态物体时静态树保持不变
+    for p in proxies.iter_mut().filter(|p| !p.is_static) {
+        p.aabb.min.x += 0.5;
+        p.aabb.max.x += 0.5;
+    }
+    assert_eq!(broad.find_pairs(&proxies), brute(&proxies));
+    assert_eq!(broad.rebuilds, 1);
+    proxies[0].aabb.max.y += 1.0;
+    assert_eq!(broad.find_pairs(&proxies), brute(&proxies));
+    assert_eq!(broad.rebuilds, 2);
+}
@@ -0,0 +1,251 @@
+use crate::physics::{boundingbox::{AABB, BoundingVolume, SphereBox}, collision::narrow::ConvexShape};
+
+use glam::f32::{Mat3, Vec3};
+
+/// 保守推进的最大迭代次数
+const CCD_MAX_ITERATIONS: usize = 32;
+/// 距离小于该值视为接触
+const CCD_TOLERANCE: f32 = 1e-3;
+/// 截断到碰撞时刻后再前进的深度，保证下一步窄相能检测到接触
+const CCD_CONTACT_DEPTH: f32 = 0.01;
+
+/// 射线 origin + motion·t 穿过盒子 [min, max] 的时间区间，限制在 [0,1] 内
+pub fn slab(origin: Vec3, motion: Vec3, min: Vec3, max: Vec3) -> Option<(f32, f32)> {
+    let (mut t_enter, mut t_exit) = (0.0f32, 1.0f32);
+    for axis in 0..3 {
+        let (o, d) = (origin[axis], motion[axis]);
+        if d.abs() < f32::EPSILON {
+            if o < min[axis] || o > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let (t1, t2) = ((min[axis] - o) / d, (max[axis] - o) / d);
+        t_enter = t_enter.max(t1.min(t2));
+        t_exit = t_exit.min(t1.max(t2));
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+    Some((t_enter, t_exit))
+}
+
+/// 移动的 AABB 沿 motion 扫过静止的 target，返回首次接触的时刻 t∈[0,1]；开始时已重叠返回 None
+pub fn sweep_aabb(moving: &AABB, motion: Vec3, target: &AABB) -> Option<f32> {
+    // 把 target 按 moving 的半径扩张，退化为射线与盒子求交
+    let half = moving.get_half_extents();
+    let (min, max) = (target.min - half, target.max + half);
+    let origin = moving.center();
+    if origin.cmpgt(min).all() && origin.cmplt(max).all() {
+        return None;
+    }
+    slab(origin, motion, min, max).map(|(t, _)| t)
+}
+
+/// 移动的球沿 motion 扫过静止的 target，返回首次接触时刻；开始时已重叠返回 None
+pub fn sweep_sphere(sphere: &SphereBox, motion: Vec3, target: &AABB) -> Option<f32> {
+    let distance = |center: Vec3| center.distance(center.clamp(target.min, target.max)) - sphere.radius;
+    let length = motion.length();
+    if length < f32::EPSILON || distance(sphere.center) <= 0.0 {
+        return None;
+    }
+    // 先用按半径扩张的盒子求出可能接触的时间窗，再在窗内保守推进
+    let radius = Vec3::splat(sphere.radius);
+    let (mut t, t_exit) = slab(sphere.center, motion, target.min - radius, target.max + radius)?;
+    for _ in 0..CCD_MAX_ITERATIONS {
+        let d = distance(sphere.center + motion * t);
+        if d < CCD_TOLERANCE {
+            return Some(t);
+        }
+        // 沿运动方向前进 d 不会穿过凸体
+        t += d / length;
+        if t > t_exit {
+            return None;
+        }
+    }
+    Some(t)
+}
+
+/// 快速物体 index 本步能走完的比例：对其余物体做扫掠检测并截断到最早的碰撞
+/// motions 为各物体本步的位移，按相对位移计算
+pub fn clamp_motion(volumes: &[BoundingVolume], motions: &[Vec3], index: usize, collides: impl Fn(usize) -> bool) -> f32 {
+    let mut earliest = 1.0f32;
+    for (j, volume) in volumes.iter().enumerate() {
+        if j == index || !collides(j) {
+            continue;
+        }
+        let motion = motions[index] - motions[j];
+        let target = volume.bounds();
+        let hit = match &volumes[index] {
+            BoundingVolume::Sphere(s) => sweep_sphere(s, motion, &target),
+            v => sweep_aabb(&v.bounds(), motion, &target),
+        };
+        if let Some(t) = hit {
+            let length = motion.length();
+            earliest = earliest.min((t + CCD_CONTACT_DEPTH / length.max(f32::EPSILON)).min(1.0));
+        }
+    }
+    earliest
+}
+
+/// 点集凸包上离原点最近的点，以及各点的重心坐标
+/// 点数不超过 4，逐个检查子集在仿射包上的投影，取重心坐标全为正且最近的一个
+fn closest_on_simplex(points: &[Vec3]) -> (Vec3, Vec<f32>) {
+    let n = points.len();
+    let mut best: Option<(Vec3, Vec<f32>)> = None;
+    for mask in 1..1u32 << n {
+        let subset: Vec<usize> = (0..n).filter(|i| mask >> i & 1 == 1).collect();
+        let base = points[subset[0]];
+        let edges: Vec<Vec3> = subset[1..].iter().map(|&i| points[i] - base).collect();
+        // 正规方程 G·μ = -Eᵀ·base，未用到的维度填单位阵
+        let mut gram = Mat3::IDENTITY;
+        let mut rhs = Vec3::ZERO;
+        for (i, ei) in edges.iter().enumerate() {
+            for (j, ej) in edges.iter().enumerate() {
+                gram.col_mut(j)[i] = ei.dot(*ej);
+            }
+            rhs[i] = -ei.dot(base);
+        }
+        let scale: f32 = (0..edges.len()).map(|i| gram.col(i)[i]).product();
+        if gram.determinant() <= scale * 1e-6 {
+            continue;
+        }
+        let mu = gram.inverse() * rhs;
+        let mut weights = vec![0.0; n];
+        weights[subset[0]] = 1.0 - (0..edges.len()).map(|i| mu[i]).sum::<f32>();
+        for (k, &i) in subset[1..].iter().enumerate() {
+            weights[i] = mu[k];
+        }
+        if subset.iter().any(|&i| weights[i] <= 0.0) {
+            continue;
+        }
+        let point = edges.iter().enumerate().fold(base, |p, (i, e)| p + *e * mu[i]);
+        if best.as_ref().is_none_or(|(q, _)| point.length_squared() < q.length_squared()) {
+            best = Some((point, weights));
+        }
+    }
+    best.unwrap_or_else(|| {
+        // 数值退化时退回最近的顶点
+        let i = (0..n).min_by(|&a, &b| points[a].length_squared().total_cmp(&points[b].length_squared())).unwrap_or(0);
+        let mut weights = vec![0.0; n];
+        weights[i] = 1.0;
+        (points[i], weights)
+    })
+}
+
+/// 凸体 a 沿 motion 平移扫过凸体 b (GJK 射线投射)，返回首次接触的 (时刻 t∈[0,1], b 上的接触点, 法线)
+/// 法线是 b 表面朝向 a 的方向；开始时已重叠返回 t = 0，法线为零
+pub fn shape_cast(a: &impl ConvexShape, motion: Vec3, b: &impl ConvexShape) -> Option<(f32, Vec3, Vec3)> {
+    // 射线 x = t·motion 与闵可夫斯基差 b - a 求交，同时记录 b 上的支撑点
+    let support = |d: Vec3| {
+        let pb = b.support(d);
+        (pb - a.support(-d), pb)
+    };
+    let (mut t, mut x, mut normal) = (0.0, Vec3::ZERO, Vec3::ZERO);
+    let mut v = x - (b.center() - a.center());
+    let mut simplex: Vec<(Vec3, Vec3)> = Vec::new();
+    let mut weights = Vec::new();
+    for _ in 0..CCD_MAX_ITERATIONS {
+        if v.length_squared() < CCD_TOLERANCE * CCD_TOLERANCE {
+            break;
+        }
+        let (p, pb) = support(v);
+        let w = x - p;
+        if v.dot(w) > 0.0 {
+            // 分离平面把 x 与差集分开，沿射线推进到平面上
+            if v.dot(motion) >= 0.0 {
+                return None;
+            }
+            t -= v.dot(w) / v.dot(motion);
+            if t > 1.0 {
+                return None;
+            }
+            x = motion * t;
+            normal = v;
+        }
+        if simplex.iter().any(|(q, _)| q.distance_squared(p) < CCD_TOLERANCE * CCD_TOLERANCE) {
+            break;
+        }
+        simplex.push((p, pb));
+        let points: Vec<Vec3> = simplex.iter().map(|(q, _)| x - *q).collect();
+        let (closest, w) = closest_on_simplex(&points);
+        v = closest;
+        // 只保留重心坐标非零的点
+        let kept: Vec<usize> = (0..simplex.len()).filter(|&i| w[i] > 0.0).collect();
+        simplex = kept.iter().map(|&i| simplex[i]).collect();
+        weights = kept.iter().map(|&i| w[i]).collect();
+    }
+    let point = if weights.is_empty() {
+        b.support(-motion)
+    } else {
+        simplex.iter().zip(&weights).map(|((_, pb), w)| *pb * *w).sum()
+    };
+    Some((t, point, normal.normalize_or_zero()))
+}
+
+/// 凸体扫过任意碰撞体，三角网格逐个检测与扫掠范围重叠的三角形
+pub fn cast_volume(a: &impl ConvexShape, motion: Vec3, target: &BoundingVolume) -> Option<(f32, Vec3, Vec3)> {
+    let BoundingVolume::TriMesh(mesh) = target else {
+        return shape_cast(a, motion, target);
+    };
+    let mut swept = AABB::default();
+    for d in [Vec3::X, Vec3::Y, Vec3::Z, -Vec3::X, -Vec3::Y, -Vec3::Z] {
+        let p = a.support(d);
+        swept.union_point_array(p.to_array());
+        swept.union_point_array((p + motion).to_array());
+    }
+    if !swept.overlaps(&mesh.bounds) {
+        return None;
+    }
+    let local = swept.get_global_aabb(mesh.matrix.inverse());
+    mesh.triangles
+        .iter()
+        .filter(|tri| {
+            let mut bounds = AABB::default();
+            for p in *tri {
+                bounds.union_point_array(p.to_array());
+            }
+            bounds.overlaps(&local)
+        })
+        .filter_map(|tri| shape_cast(a, motion, &tri.map(|p| mesh.matrix.transform_point3(p))))
+        .min_by(|x, y| x.0.total_cmp(&y.0))
+}
+
+#[test]
+fn test_swept_shapes_hit_thin_wall() {
+    // 0.4 m 厚的墙，碎片一步移动 5 m
+    let wall = AABB::from_vec(Vec3::new(-0.2, -2.0, -2.0), Vec3::new(0.2, 2.0, 2.0));
+    let motion = Vec3::new(5.0, 0.0, 0.0);
+
+    let cube = AABB::from_vec(Vec3::new(-2.1, -0.1, -0.1), Vec3::new(-1.9, 0.1, 0.1));
+    let t = sweep_aabb(&cube, motion, &wall).unwrap();
+    assert!((t - 1.7 / 5.0).abs() < 1e-5, "t = {t}");
+    // 平行移动或背向移动都不会命中
+    assert!(sweep_aabb(&cube, Vec3::new(0.0, 5.0, 0.0), &wall).is_none());
+    assert!(sweep_aabb(&cube, -motion, &wall).is_none());
+
+    let sphere = SphereBox::new(Vec3::new(-2.0, 0.0, 0.0), 0.1);
+    let t = sweep_sphere(&sphere, motion, &wall).unwrap();
+    assert!((t - 1.7 / 5.0).abs() < 1e-3, "t = {t}");
+    // 从墙角外侧掠过
+    let corner = SphereBox::new(Vec3::new(-2.0, 2.15, 0.0), 0.1);
+    assert!(sweep_sphere(&corner, motion, &wall).is_none());
+    assert!(sweep_sphere(&sphere, Vec3::new(1.0, 0.0, 0.0), &wall).is_none());
+}
+
+#[test]
+fn test_fast_body_stops_at_wall() {
+    use crate::physics::collision::solve::{PhysicsState, crate_object, step_objects};
+
+    let mut wall = crate_object("wall", 0.4, 4.0, false);
+    let mut shard = crate_object("shard", 0.1, 0.1, true);
+    shard.transform.position.x = -1.0;
+    shard.physics.velocity = [60.0, 0.0, 0.0];
+    shard.physics.ccd = true;
+
+    let mut state = PhysicsState::default();
+    for _ in 0..10 {
+        step_objects(&mut [&mut wall, &mut shard], &mut state, 1.0 / 60.0);
+    }
+    assert!(shard.transform.position.x < 0.0, "shard at {}", shard.transform.position);
+}
@@ -1,3 +1,5 @@
 pub mod solve;
 pub mod board;
+pub mod broad;
+pub mod ccd;
 pub mod narrow;
@@ -1,5 +1,12 @@
 use glam::f32::{Vec3};
-use crate::{physics::{boundingbox::{AABB, SphereBox}, rigid::Contact}};
+use crate::{physics::{boundingbox::{AABB, BoundingVolume, Capsule, ConvexHull, OBB, SphereBox, TriMesh}, rigid::{Contact, ContactPoint}}};
+
+const GJK_MAX_ITERATIONS: usize = 64;
+const EPA_MAX_ITERATIONS: usize = 64;
+const EPA_TOLERANCE: f32 = 1e-4;
+/// 到最远点的距离在此范围内的顶点视为同一接触特征
+const FEATURE_TOLERANCE: f32 = 0.01;
+const MAX_MANIFOLD_POINTS: usize = 4;
 
 pub fn aabb_vs_aabb(a: &AABB, b: &AABB) -> Option<Contact>{
     let a_center = a.center();
@@ -25,10 +32,7 @@ pub fn aabb_vs_aabb(a: &AABB, b: &AABB) -> Option<Contact>{
         (overlap.z, Vec3::new(0.0, 0.0, delta.z.signum()))
     };
 
-    Some(Contact {
-        normal,
-        penetration,
-    })
+    Some(Contact::new(normal, penetration))
 }
 
 pub fn sphere_vs_sphere(a: &SphereBox, b: &SphereBox)-> Option<Contact>{ 
@@ -49,10 +53,7 @@ pub fn sphere_vs_sphere(a: &SphereBox, b: &SphereBox)-> Option<Contact>{
         glam::f32::Vec3::X
     };
 
-    Some(Contact {
-        normal,
-        penetration,
-    })
+    Some(Contact::new(normal, penetration))
 }
 
 pub fn aabb_vs_sphere(aabb: &AABB, sphere: &SphereBox) -> Option<Contact>{
@@ -73,8 +74,718 @@ pub fn aabb_vs_sphere(aabb: &AABB, sphere: &SphereBox) -> Option<Contact>{
         (sphere.center - aabb.center()).normalize_or_zero()
     };
 
-    Some(Contact {
-        normal,
-        penetration,
-    })
-}
\ No newline at end of file
+    Some(Contact::new(normal, penetration))
+}
+/// 法线方向取反，用于交换碰撞对的两个物体
+pub fn flipped(mut contact: Contact) -> Contact {
+    contact.normal = -contact.normal;
+    contact
+}
+
+/// 支撑函数描述的凸体，供 GJK/EPA 使用
+pub trait ConvexShape {
+    /// 沿方向 d 最远的点
+    fn support(&self, d: Vec3) -> Vec3;
+    fn center(&self) -> Vec3;
+    /// 沿方向 d 最远的特征 (顶点、边或面) 的顶点
+    fn feature(&self, d: Vec3) -> Vec<Vec3> {
+        vec![self.support(d)]
+    }
+}
+
+impl ConvexShape for AABB {
+    fn support(&self, d: Vec3) -> Vec3 {
+        Vec3::select(d.cmpge(Vec3::ZERO), self.max, self.min)
+    }
+    fn center(&self) -> Vec3 {
+        AABB::center(self)
+    }
+    fn feature(&self, d: Vec3) -> Vec<Vec3> {
+        OBB::from_aabb(self).feature(d)
+    }
+}
+
+impl ConvexShape for SphereBox {
+    fn support(&self, d: Vec3) -> Vec3 {
+        self.center + d.normalize_or_zero() * self.radius
+    }
+    fn center(&self) -> Vec3 {
+        self.center
+    }
+}
+
+impl ConvexShape for OBB {
+    fn support(&self, d: Vec3) -> Vec3 {
+        let mut p = self.center;
+        for i in 0..3 {
+            let sign = if d.dot(self.asxes[i]) >= 0.0 { 1.0 } else { -1.0 };
+            p += self.asxes[i] * self.half_extents[i] * sign;
+        }
+        p
+    }
+    fn center(&self) -> Vec3 {
+        self.center
+    }
+    fn feature(&self, d: Vec3) -> Vec<Vec3> {
+        let corners = (0..8).map(|k| {
+            (0..3).fold(self.center, |p, i| {
+                let sign = if k >> i & 1 == 1 { 1.0 } else { -1.0 };
+                p + self.asxes[i] * self.half_extents[i] * sign
+            })
+        });
+        feature_of(corners, d)
+    }
+}
+
+impl ConvexShape for Capsule {
+    fn support(&self, d: Vec3) -> Vec3 {
+        let end = if d.dot(self.a) >= d.dot(self.b) { self.a } else { self.b };
+        end + d.normalize_or_zero() * self.radius
+    }
+    fn center(&self) -> Vec3 {
+        (self.a + self.b) * 0.5
+    }
+    fn feature(&self, d: Vec3) -> Vec<Vec3> {
+        let offset = d.normalize_or_zero() * self.radius;
+        feature_of([self.a, self.b].into_iter(), d)
+            .into_iter()
+            .map(|p| p + offset)
+            .collect()
+    }
+}
+
+impl ConvexShape for ConvexHull {
+    fn support(&self, d: Vec3) -> Vec3 {
+        max_along(&self.points, d)
+    }
+    fn center(&self) -> Vec3 {
+        self.points.iter().copied().sum::<Vec3>() / self.points.len().max(1) as f32
+    }
+    fn feature(&self, d: Vec3) -> Vec<Vec3> {
+        feature_of(self.points.iter().copied(), d)
+    }
+}
+
+impl ConvexShape for [Vec3; 3] {
+    fn support(&self, d: Vec3) -> Vec3 {
+        max_along(self, d)
+    }
+    fn center(&self) -> Vec3 {
+        (self[0] + self[1] + self[2]) / 3.0
+    }
+    fn feature(&self, d: Vec3) -> Vec<Vec3> {
+        feature_of(self.iter().copied(), d)
+    }
+}
+
+impl ConvexShape for BoundingVolume {
+    fn support(&self, d: Vec3) -> Vec3 {
+        match self {
+            BoundingVolume::AABB(s) => s.support(d),
+            BoundingVolume::Sphere(s) => s.support(d),
+            BoundingVolume::OBB(s) => s.support(d),
+            BoundingVolume::Capsule(s) => s.support(d),
+            BoundingVolume::ConvexHull(s) => s.support(d),
+            // 三角网格是凹的，只能给出包围盒的支撑点
+            BoundingVolume::TriMesh(s) => s.bounds.support(d),
+        }
+    }
+    fn center(&self) -> Vec3 {
+        self.bounds().center()
+    }
+    fn feature(&self, d: Vec3) -> Vec<Vec3> {
+        match self {
+            BoundingVolume::AABB(s) => s.feature(d),
+            BoundingVolume::Sphere(s) => s.feature(d),
+            BoundingVolume::OBB(s) => s.feature(d),
+            BoundingVolume::Capsule(s) => s.feature(d),
+            BoundingVolume::ConvexHull(s) => s.feature(d),
+            BoundingVolume::TriMesh(s) => s.bounds.feature(d),
+        }
+    }
+}
+
+fn max_along(points: &[Vec3], d: Vec3) -> Vec3 {
+    points
+        .iter()
+        .copied()
+        .max_by(|a, b| a.dot(d).total_cmp(&b.dot(d)))
+        .unwrap_or(Vec3::ZERO)
+}
+
+fn feature_of(points: impl Iterator<Item = Vec3>, d: Vec3) -> Vec<Vec3> {
+    let d = d.normalize_or_zero();
+    let points: Vec<Vec3> = points.collect();
+    let max = points.iter().map(|p| p.dot(d)).fold(f32::NEG_INFINITY, f32::max);
+    let mut feature: Vec<Vec3> = points
+        .into_iter()
+        .filter(|p| p.dot(d) >= max - FEATURE_TOLERANCE)
+        .collect();
+    if feature.len() > 2 {
+        // 按绕法线的角度排序，得到凸多边形的顶点顺序
+        let center = feature.iter().copied().sum::<Vec3>() / feature.len() as f32;
+        let (u, v) = d.any_orthonormal_pair();
+        let angle = |p: &Vec3| (*p - center).dot(v).atan2((*p - center).dot(u));
+        feature.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+    }
+    feature
+}
+
+fn minkowski_support(a: &impl ConvexShape, b: &impl ConvexShape, d: Vec3) -> Vec3 {
+    a.support(d) - b.support(-d)
+}
+
+/// GJK 判断相交，相交时用 EPA 求穿透深度与法线 (从 a 指向 b)
+pub fn gjk_epa(a: &impl ConvexShape, b: &impl ConvexShape) -> Option<Contact> {
+    let mut d = b.center() - a.center();
+    if d.length_squared() < 1e-12 {
+        d = Vec3::X;
+    }
+    // 单纯形顶点，最新加入的在最前
+    let mut simplex = vec![minkowski_support(a, b, d)];
+    d = -simplex[0];
+    for _ in 0..GJK_MAX_ITERATIONS {
+        if d.length_squared() < 1e-12 {
+            // 原点恰好落在单纯形上，视为接触但没有穿透
+            return None;
+        }
+        let p = minkowski_support(a, b, d);
+        if p.dot(d) <= 0.0 {
+            return None;
+        }
+        simplex.insert(0, p);
+        if next_simplex(&mut simplex, &mut d) {
+            return epa(a, b, &simplex);
+        }
+    }
+    None
+}
+
+fn next_simplex(simplex: &mut Vec<Vec3>, d: &mut Vec3) -> bool {
+    match simplex.len() {
+        2 => simplex_line(simplex, d),
+        3 => simplex_triangle(simplex, d),
+        _ => simplex_tetrahedron(simplex, d),
+    }
+}
+
+fn simplex_line(simplex: &mut Vec<Vec3>, d: &mut Vec3) -> bool {
+    let (a, b) = (simplex[0], simplex[1]);
+    let (ab, ao) = (b - a, -a);
+    if ab.dot(ao) > 0.0 {
+        *d = ab.cross(ao).cross(ab);
+        if d.length_squared() < 1e-12 {
+            // 原点在线段上，任取一个垂直方向继续扩展
+            *d = ab.any_orthonormal_vector();
+        }
+    } else {
+        simplex.truncate(1);
+        *d = ao;
+    }
+    false
+}
+
+fn simplex_triangle(simplex: &mut Vec<Vec3>, d: &mut Vec3) -> bool {
+    let (a, b, c) = (simplex[0], simplex[1], simplex[2]);
+    let (ab, ac, ao) = (b - a, c - a, -a);
+    let abc = ab.cross(ac);
+    if abc.cross(ac).dot(ao) > 0.0 {
+        if ac.dot(ao) > 0.0 {
+            *simplex = vec![a, c];
+            *d = ac.cross(ao).cross(ac);
+            false
+        } else {
+            *simplex = vec![a, b];
+            simplex_line(simplex, d)
+        }
+    } else if ab.cross(abc).dot(ao) > 0.0 {
+        *simplex = vec![a, b];
+        simplex_line(simplex, d)
+    } else if abc.dot(ao) > 0.0 {
+        *d = abc;
+        false
+    } else {
+        *simplex = vec![a, c, b];
+        *d = -abc;
+        false
+    }
+}
+
+fn simplex_tetrahedron(simplex: &mut Vec<Vec3>, d: &mut Vec3) -> bool {
+    let (a, b, c, dd) = (simplex[0], simplex[1], simplex[2], simplex[3]);
+    let (ab, ac, ad, ao) = (b - a, c - a, dd - a, -a);
+    let abc = ab.cross(ac);
+    let acd = ac.cross(ad);
+    let adb = ad.cross(ab);
+    if abc.dot(ao) > 0.0 {
+        *simplex = vec![a, b, c];
+        return simplex_triangle(simplex, d);
+    }
+    if acd.dot(ao) > 0.0 {
+        *simplex = vec![a, c, dd];
+        return simplex_triangle(simplex, d);
+    }
+    if adb.dot(ao) > 0.0 {
+        *simplex = vec![a, dd, b];
+        return simplex_triangle(simplex, d);
+    }
+    true
+}
+
+/// 多面体的面：顶点下标、朝外法线与到原点的距离
+struct EpaFace {
+    indices: [usize; 3],
+    normal: Vec3,
+    distance: f32,
+}
+
+fn epa_face(polytope: &[Vec3], indices: [usize; 3]) -> EpaFace {
+    let [a, b, c] = indices.map(|i| polytope[i]);
+    let mut normal = (b - a).cross(c - a).normalize_or_zero();
+    let mut distance = normal.dot(a);
+    if distance < 0.0 {
+        normal = -normal;
+        distance = -distance;
+    }
+    if normal == Vec3::ZERO {
+        // 退化的面不参与最近面的选取
+        distance = f32::INFINITY;
+    }
+    EpaFace { indices, normal, distance }
+}
+
+fn epa(a: &impl ConvexShape, b: &impl ConvexShape, simplex: &[Vec3]) -> Option<Contact> {
+    let mut polytope = simplex.to_vec();
+    let mut faces: Vec<EpaFace> = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]
+        .into_iter()
+        .map(|f| epa_face(&polytope, f))
+        .collect();
+
+    for _ in 0..EPA_MAX_ITERATIONS {
+        let closest = faces
+            .iter()
+            .min_by(|x, y| x.distance.total_cmp(&y.distance))?;
+        if !closest.distance.is_finite() {
+            return None;
+        }
+        let (normal, distance) = (closest.normal, closest.distance);
+        let p = minkowski_support(a, b, normal);
+        if p.dot(normal) - distance < EPA_TOLERANCE {
+            return Some(Contact::new(normal, distance));
+        }
+
+        // 删除所有能看到新点的面，保留地平线上的边
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|f| {
+            if f.normal.dot(p - polytope[f.indices[0]]) <= 0.0 {
+                return true;
+            }
+            for k in 0..3 {
+                let edge = (f.indices[k], f.indices[(k + 1) % 3]);
+                match horizon
+                    .iter()
+                    .position(|&(u, v)| (u, v) == edge || (v, u) == edge)
+                {
+                    Some(pos) => {
+                        horizon.swap_remove(pos);
+                    }
+                    None => horizon.push(edge),
+                }
+            }
+            false
+        });
+        if horizon.is_empty() {
+            return Some(Contact::new(normal, distance));
+        }
+        polytope.push(p);
+        let new_index = polytope.len() - 1;
+        for (u, v) in horizon {
+            faces.push(epa_face(&polytope, [u, v, new_index]));
+        }
+    }
+
+    let closest = faces.iter().min_by(|x, y| x.distance.total_cmp(&y.distance))?;
+    closest.distance.is_finite().then_some(Contact::new(closest.normal, closest.distance))
+}
+
+/// 分离轴检测两个有向包围盒，法线从 a 指向 b
+pub fn obb_vs_obb(a: &OBB, b: &OBB) -> Option<Contact> {
+    let delta = b.center - a.center;
+    let mut best: Option<Contact> = None;
+    let mut test_axis = |axis: Vec3, bias: f32| -> bool {
+        let len = axis.length();
+        if len < 1e-6 {
+            return true;
+        }
+        let axis = axis / len;
+        let ra: f32 = (0..3).map(|i| a.half_extents[i] * a.asxes[i].dot(axis).abs()).sum();
+        let rb: f32 = (0..3).map(|i| b.half_extents[i] * b.asxes[i].dot(axis).abs()).sum();
+        let dist = delta.dot(axis);
+        let overlap = ra + rb - dist.abs();
+        if overlap <= 0.0 {
+            return false;
+        }
+        // 边-边轴稍作惩罚，数值接近时优先选用面法线
+        if best.as_ref().is_none_or(|c| overlap * bias < c.penetration) {
+            let sign = if dist >= 0.0 { 1.0 } else { -1.0 };
+            best = Some(Contact::new(axis * sign, overlap));
+        }
+        true
+    };
+    for axis in a.asxes.iter().chain(b.asxes.iter()) {
+        if !test_axis(*axis, 1.0) {
+            return None;
+        }
+    }
+    for u in &a.asxes {
+        for v in &b.asxes {
+            if !test_axis(u.cross(*v), 1.05) {
+                return None;
+            }
+        }
+    }
+    best
+}
+
+pub fn obb_vs_sphere(obb: &OBB, sphere: &SphereBox) -> Option<Contact> {
+    let delta = sphere.center - obb.center;
+    let local = Vec3::from_array(std::array::from_fn(|i| delta.dot(obb.asxes[i])));
+    let clamped = local.clamp(-obb.half_extents, obb.half_extents);
+    if local != clamped {
+        let closest = obb.center + (0..3).map(|i| obb.asxes[i] * clamped[i]).sum::<Vec3>();
+        let offset = sphere.center - closest;
+        let dist2 = offset.length_squared();
+        if dist2 >= sphere.radius * sphere.radius {
+            return None;
+        }
+        let dist = dist2.sqrt();
+        return Some(Contact::new(offset / dist, sphere.radius - dist));
+    }
+    // 球心在盒内：沿最近的面推出
+    let depth = obb.half_extents - local.abs();
+    let axis = depth.min_position();
+    let sign = if local[axis] >= 0.0 { 1.0 } else { -1.0 };
+    Some(Contact::new(obb.asxes[axis] * sign, depth[axis] + sphere.radius))
+}
+
+pub fn closest_point_on_segment(a: Vec3, b: Vec3, p: Vec3) -> Vec3 {
+    let ab = b - a;
+    let len2 = ab.length_squared();
+    if len2 < 1e-12 {
+        return a;
+    }
+    a + ab * ((p - a).dot(ab) / len2).clamp(0.0, 1.0)
+}
+
+/// 两线段间的最近点对
+pub fn closest_points_segments(p1: Vec3, q1: Vec3, p2: Vec3, q2: Vec3) -> (Vec3, Vec3) {
+    let (d1, d2, r) = (q1 - p1, q2 - p2, p1 - p2);
+    let (a, e, f) = (d1.length_squared(), d2.length_squared(), d2.dot(r));
+    if a < 1e-12 && e < 1e-12 {
+        return (p1, p2);
+    }
+    let (s, t) = if a < 1e-12 {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(r);
+        if e < 1e-12 {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(d2);
+            let denom = a * e - b * b;
+            let mut s = if denom > 1e-12 { ((b * f - c * e) / denom).clamp(0.0, 1.0) } else { 0.0 };
+            let mut t = (b * s + f) / e;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+    (p1 + d1 * s, p2 + d2 * t)
+}
+
+pub fn capsule_vs_sphere(capsule: &Capsule, sphere: &SphereBox) -> Option<Contact> {
+    let closest = closest_point_on_segment(capsule.a, capsule.b, sphere.center);
+    sphere_vs_sphere(&SphereBox::new(closest, capsule.radius), sphere)
+}
+
+pub fn capsule_vs_capsule(a: &Capsule, b: &Capsule) -> Option<Contact> {
+    let (pa, pb) = closest_points_segments(a.a, a.b, b.a, b.b);
+    sphere_vs_sphere(&SphereBox::new(pa, a.radius), &SphereBox::new(pb, b.radius))
+}
+
+/// 三角形上距 p 最近的点
+pub fn closest_point_on_triangle(tri: &[Vec3; 3], p: Vec3) -> Vec3 {
+    let [a, b, c] = *tri;
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+pub fn triangle_vs_sphere(tri: &[Vec3; 3], sphere: &SphereBox) -> Option<Contact> {
+    let closest = closest_point_on_triangle(tri, sphere.center);
+    let offset = sphere.center - closest;
+    let dist2 = offset.length_squared();
+    if dist2 >= sphere.radius * sphere.radius {
+        return None;
+    }
+    let dist = dist2.sqrt();
+    let normal = if dist > 1e-6 {
+        offset / dist
+    } else {
+        (tri[1] - tri[0]).cross(tri[2] - tri[0]).normalize_or_zero()
+    };
+    Some(Contact::new(normal, sphere.radius - dist))
+}
+
+/// 静态三角网格与凸体：逐个检测包围盒重叠的三角形，取最深的接触
+pub fn trimesh_vs_convex(mesh: &TriMesh, shape: &BoundingVolume) -> Option<Contact> {
+    let bounds = shape.bounds();
+    if !mesh.bounds.overlaps(&bounds) {
+        return None;
+    }
+    let local_bounds = bounds.get_global_aabb(mesh.matrix.inverse());
+    let mut contacts = Vec::new();
+    for tri in mesh.triangles.iter() {
+        let mut tri_bounds = AABB::default();
+        for p in tri {
+            tri_bounds.union_point_array(p.to_array());
+        }
+        if !tri_bounds.overlaps(&local_bounds) {
+            continue;
+        }
+        let world = tri.map(|p| mesh.matrix.transform_point3(p));
+        let contact = match shape {
+            BoundingVolume::Sphere(sphere) => triangle_vs_sphere(&world, sphere),
+            _ => gjk_epa(&world, shape),
+        };
+        if let Some(c) = contact {
+            contacts.push(with_manifold(&world, shape, c));
+        }
+    }
+    // 以最深的接触为准，合并法线相近的三角形上的接触点
+    let deepest = contacts
+        .iter()
+        .max_by(|a, b| a.penetration.total_cmp(&b.penetration))?;
+    let mut merged = Contact::new(deepest.normal, deepest.penetration);
+    for c in &contacts {
+        if c.normal.dot(merged.normal) > 0.95 {
+            merged.points.extend(c.points.iter().copied());
+        }
+    }
+    merged.points = reduce_manifold(merged.points);
+    Some(merged)
+}
+
+/// 由两个凸体沿法线方向的特征裁剪出接触点
+pub fn with_manifold(a: &impl ConvexShape, b: &impl ConvexShape, mut contact: Contact) -> Contact {
+    let n = contact.normal;
+    let (fa, fb) = (a.feature(n), b.feature(-n));
+    let half = n * contact.penetration * 0.5;
+    let single = |position: Vec3| ContactPoint { position, penetration: contact.penetration };
+
+    contact.points = if fb.len() == 1 {
+        vec![single(fb[0] + half)]
+    } else if fa.len() == 1 {
+        vec![single(fa[0] - half)]
+    } else if fa.len() == 2 && fb.len() == 2 {
+        let (pa, pb) = closest_points_segments(fa[0], fa[1], fb[0], fb[1]);
+        vec![single((pa + pb) * 0.5)]
+    } else {
+        // 顶点多的一侧作为参考面，另一侧的特征被裁剪到参考面范围内
+        let (reference, incident, normal) = if fa.len() >= fb.len() { (fa, fb, n) } else { (fb, fa, -n) };
+        let plane = reference.iter().map(|p| p.dot(normal)).fold(f32::NEG_INFINITY, f32::max);
+        let points: Vec<ContactPoint> = clip_to_face(incident, &reference, normal)
+            .into_iter()
+            .filter_map(|p| {
+                let depth = plane - p.dot(normal);
+                (depth >= 0.0).then_some(ContactPoint {
+                    position: p + normal * depth * 0.5,
+                    penetration: depth,
+                })
+            })
+            .collect();
+        if points.is_empty() {
+            vec![single(b.support(-n) + half)]
+        } else {
+            reduce_manifold(points)
+        }
+    };
+    contact
+}
+
+/// 用参考面各边所在的侧平面裁剪入射特征 (线段或多边形)
+fn clip_to_face(mut incident: Vec<Vec3>, reference: &[Vec3], normal: Vec3) -> Vec<Vec3> {
+    let center = reference.iter().copied().sum::<Vec3>() / reference.len() as f32;
+    for i in 0..reference.len() {
+        let (r0, r1) = (reference[i], reference[(i + 1) % reference.len()]);
+        let mut side = (r1 - r0).cross(normal);
+        if side.dot(center - r0) > 0.0 {
+            side = -side;
+        }
+        let dist = |p: Vec3| side.dot(p - r0);
+        let lerp = |p: Vec3, q: Vec3| p + (q - p) * (dist(p) / (dist(p) - dist(q)));
+        incident = if incident.len() == 2 {
+            let (p, q) = (incident[0], incident[1]);
+            match (dist(p) <= 0.0, dist(q) <= 0.0) {
+                (true, true) => vec![p, q],
+                (true, false) => vec![p, lerp(p, q)],
+                (false, true) => vec![lerp(p, q), q],
+                (false, false) => Vec::new(),
+            }
+        } else {
+            let mut clipped = Vec::new();
+            for k in 0..incident.len() {
+                let (p, q) = (incident[k], incident[(k + 1) % incident.len()]);
+                if dist(p) <= 0.0 {
+                    clipped.push(p);
+                }
+                if (dist(p) <= 0.0) != (dist(q) <= 0.0) {
+                    clipped.push(lerp(p, q));
+                }
+            }
+            clipped
+        };
+        if incident.is_empty() {
+            break;
+        }
+    }
+    incident
+}
+
+/// 接触点过多时保留最深的点，再依次挑选离已选点最远的点
+fn reduce_manifold(points: Vec<ContactPoint>) -> Vec<ContactPoint> {
+    if points.len() <= MAX_MANIFOLD_POINTS {
+        return points;
+    }
+    let deepest = (0..points.len())
+        .max_by(|&a, &b| points[a].penetration.total_cmp(&points[b].penetration))
+        .unwrap();
+    let mut chosen = vec![deepest];
+    while chosen.len() < MAX_MANIFOLD_POINTS {
+        let spread = |i: usize| {
+            chosen
+                .iter()
+                .map(|&c| points[c].position.distance_squared(points[i].position))
+                .fold(f32::INFINITY, f32::min)
+        };
+        let next = (0..points.len())
+            .max_by(|&a, &b| spread(a).total_cmp(&spread(b)))
+            .unwrap();
+        if spread(next) < 1e-8 {
+            break;
+        }
+        chosen.push(next);
+    }
+    chosen.into_iter().map(|i| points[i]).collect()
+}
+
+#[test]
+fn test_narrow_phase_shapes() {
+    use glam::Quat;
+
+    let unit = OBB { half_extents: Vec3::splat(0.5), ..Default::default() };
+    // 绕 y 轴旋转 45° 的盒子，对角线方向伸出 √2/2
+    let rotated = OBB {
+        center: Vec3::new(1.15, 0.0, 0.0),
+        asxes: [Vec3::X, Vec3::Y, Vec3::Z].map(|a| Quat::from_rotation_y(std::f32::consts::FRAC_PI_4) * a),
+        ..unit
+    };
+    let c = obb_vs_obb(&unit, &rotated).unwrap();
+    assert!((c.penetration - (0.5 + 0.5f32.sqrt() - 1.15)).abs() < 1e-4);
+    assert!(c.normal.abs_diff_eq(Vec3::X, 1e-4));
+    // 旋转后的 AABB 会错误地报告重叠
+    let far = OBB { center: Vec3::new(0.9, 0.0, 0.9), ..rotated };
+    assert!(obb_vs_obb(&unit, &far).is_none());
+
+    // GJK/EPA 与 SAT 结果一致
+    let g = gjk_epa(&unit, &rotated).unwrap();
+    assert!((g.penetration - c.penetration).abs() < 1e-3);
+    assert!(g.normal.abs_diff_eq(c.normal, 1e-3));
+    assert!(gjk_epa(&unit, &far).is_none());
+
+    let capsule = Capsule { a: Vec3::new(-1.0, 0.9, 0.0), b: Vec3::new(1.0, 0.9, 0.0), radius: 0.5 };
+    let c = gjk_epa(&unit, &capsule).unwrap();
+    assert!((c.penetration - 0.1).abs() < 1e-2);
+    assert!(c.normal.abs_diff_eq(Vec3::Y, 1e-2));
+
+    // 由两个三角形组成的地面
+    let floor = TriMesh {
+        triangles: std::sync::Arc::new(vec![
+            [Vec3::new(-5.0, 0.0, -5.0), Vec3::new(-5.0, 0.0, 5.0), Vec3::new(5.0, 0.0, 5.0)],
+            [Vec3::new(-5.0, 0.0, -5.0), Vec3::new(5.0, 0.0, 5.0), Vec3::new(5.0, 0.0, -5.0)],
+        ]),
+        matrix: glam::Mat4::IDENTITY,
+        bounds: AABB::from_array([-5.0, 0.0, -5.0], [5.0, 0.0, 5.0]),
+    };
+    let ball = BoundingVolume::Sphere(SphereBox::new(Vec3::new(1.0, 0.4, 1.0), 0.5));
+    let c = trimesh_vs_convex(&floor, &ball).unwrap();
+    assert!((c.penetration - 0.1).abs() < 1e-4 && c.normal.abs_diff_eq(Vec3::Y, 1e-4));
+    let box_above = BoundingVolume::OBB(OBB { center: Vec3::new(0.0, 0.45, 0.0), ..unit });
+    let c = trimesh_vs_convex(&floor, &box_above).unwrap();
+    assert!((c.penetration - 0.05).abs() < 1e-3 && c.normal.abs_diff_eq(Vec3::Y, 1e-3));
+    // 盒子跨在两个三角形上，接触点落在盒子底面的四个角
+    assert_eq!(c.points.len(), 4);
+    assert!(c.points.iter().all(|p| (p.position.y + 0.025).abs() < 1e-3 && p.position.x.abs() > 0.49));
+}
+
+#[test]
+fn test_contact_manifolds() {
+    use crate::physics::collision::board::collide;
+
+    let unit = OBB { half_extents: Vec3::splat(0.5), ..Default::default() };
+    // 叠放的盒子：上面的盒子偏移后，接触面为两者底面的交集
+    let top = OBB { center: Vec3::new(0.6, 0.95, 0.0), ..unit };
+    let c = collide(&BoundingVolume::OBB(unit), &BoundingVolume::OBB(top)).unwrap();
+    assert_eq!(c.points.len(), 4);
+    for p in &c.points {
+        assert!(p.position.x > 0.1 - 1e-4 && p.position.x < 0.5 + 1e-4);
+        assert!((p.penetration - 0.05).abs() < 1e-4);
+    }
+
+    // 倾斜的盒子只有一条棱压在地面上
+    let tilted = OBB {
+        center: Vec3::new(0.0, 0.5 + 0.5f32.sqrt() - 0.02, 0.0),
+        asxes: [Vec3::X, Vec3::Y, Vec3::Z].map(|a| glam::Quat::from_rotation_z(std::f32::consts::FRAC_PI_4) * a),
+        ..unit
+    };
+    let c = collide(&BoundingVolume::OBB(unit), &BoundingVolume::OBB(tilted)).unwrap();
+    assert_eq!(c.points.len(), 2);
+    assert!(c.points.iter().all(|p| p.position.x.abs() < 1e-3));
+
+    // 球只有一个接触点
+    let ball = SphereBox::new(Vec3::new(0.2, 0.9, 0.0), 0.5);
+    let c = collide(&BoundingVolume::OBB(unit), &BoundingVolume::Sphere(ball)).unwrap();
+    assert_eq!(c.points.len(), 1);
+    assert!(c.points[0].position.abs_diff_eq(Vec3::new(0.2, 0.45, 0.0), 1e-4));
+}
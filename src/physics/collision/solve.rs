@@ -1,70 +1,571 @@
-use crate::{physics::rigid::{Contact, RigidBody}, scene::{World, world::BodyHandle}};
+use std::collections::{HashMap, HashSet};
 
+use crate::{physics::{boundingbox::BoundingVolume, material::PhysicsMaterial, collision::{board::collide, broad::{BroadPhase, BroadProxy}, ccd::clamp_motion}, event::{ContactInfo, EventQueue}, joint::{Joint, JointConstraint}, layer::CollisionLayers, rigid::{Contact, RigidBody}, sleep::{SLEEP_ANGULAR_SPEED, SLEEP_LINEAR_SPEED}}, scene::world::BodyHandle};
 
-use glam::f32::Vec3;
-pub fn solve_contact(
-    a: &mut dyn RigidBody,
-    b: &mut dyn RigidBody,
-    contact: &Contact,
-    dt: f32,
-) {
-    positional_correction(a, b, contact);
-    update_vel(a, b, contact, dt);
+use glam::f32::{Mat3, Quat, Vec3};
+
+/// 每步速度迭代次数
+pub const VELOCITY_ITERATIONS: usize = 10;
+/// 穿透修正的比例与允许的穿透量
+const BAUMGARTE: f32 = 0.2;
+const PENETRATION_SLOP: f32 = 0.005;
+/// 低于该法向速度的碰撞不反弹，避免静止接触抖动
+const RESTITUTION_THRESHOLD: f32 = 0.5;
+/// 新旧接触点距离在此范围内视为同一点，沿用上一帧的冲量
+const WARM_START_DISTANCE: f32 = 0.05;
+
+/// 切向速度低于该值时使用静摩擦系数
+const STATIC_FRICTION_SPEED: f32 = 0.1;
+
+/// 求解过程中的刚体状态快照，求解结束后写回
+pub struct SolverBody {
+    pub velocity: Vec3,
+    pub angular_velocity: Vec3,
+    /// 穿透修正用的伪速度，只影响位置，不计入真实速度
+    pub pseudo_velocity: Vec3,
+    pub pseudo_angular_velocity: Vec3,
+    pub inv_mass: f32,
+    pub inv_inertia: Mat3,
+    pub center_of_mass: Vec3,
+    material: PhysicsMaterial,
 }
 
-pub fn positional_correction(
-    a: &mut dyn RigidBody,
-    b: &mut dyn RigidBody,
-    contact: &Contact,
-) {
-    // Baumgarte稳定性修正
-    let percent = 0.8;
-    let slop = 0.01;
-    let correction_mag = ((contact.penetration - slop).max(0.0) / (a.inv_mass() + b.inv_mass())) * percent;
-    let correction = contact.normal * correction_mag;
+impl SolverBody {
+    pub fn new(body: &dyn RigidBody) -> Self {
+        Self {
+            velocity: Vec3::from_array(body.velocity()),
+            angular_velocity: Vec3::from_array(body.angular_velocity()),
+            pseudo_velocity: Vec3::ZERO,
+            pseudo_angular_velocity: Vec3::ZERO,
+            inv_mass: body.inv_mass(),
+            inv_inertia: body.inv_inertia(),
+            center_of_mass: body.center_of_mass(),
+            material: body.material(),
+        }
+    }
 
+    /// 世界本身，关节的一端不连接物体时使用
+    pub fn world() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            pseudo_velocity: Vec3::ZERO,
+            pseudo_angular_velocity: Vec3::ZERO,
+            inv_mass: 0.0,
+            inv_inertia: Mat3::ZERO,
+            center_of_mass: Vec3::ZERO,
+            material: PhysicsMaterial::DEFAULT,
+        }
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.inv_mass > 0.0
+    }
 
-    if b.is_dynamic() {
-        let inv_mass = a.inv_mass();
-        a.transform_mut().position -= correction * inv_mass;
-        let inv_mass = b.inv_mass();
-        b.transform_mut().position += correction * inv_mass;
-    } else {
-        a.transform_mut().position -= contact.normal * contact.penetration * 1.1;
+    fn apply_impulse(&mut self, impulse: Vec3, r: Vec3) {
+        self.velocity += impulse * self.inv_mass;
+        self.angular_velocity += self.inv_inertia * r.cross(impulse);
     }
+
+    fn apply_pseudo_impulse(&mut self, impulse: Vec3, r: Vec3) {
+        self.pseudo_velocity += impulse * self.inv_mass;
+        self.pseudo_angular_velocity += self.inv_inertia * r.cross(impulse);
+    }
+}
+
+struct ConstraintPoint {
+    position: Vec3,
+    ra: Vec3,
+    rb: Vec3,
+    penetration: f32,
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    /// 反弹的目标法向速度
+    velocity_bias: f32,
+    /// 静止时用静摩擦，滑动时用动摩擦
+    friction: f32,
+    normal_impulse: f32,
+    tangent_impulse: [f32; 2],
+    pseudo_impulse: f32,
 }
 
-pub fn update_vel(
-    a: &mut dyn RigidBody,
-    b: &mut dyn RigidBody,
-    contact: &Contact,
+/// 一对物体之间的接触约束，a、b 为求解器刚体数组的下标
+pub struct ContactConstraint {
+    pub a: usize,
+    pub b: usize,
+    pub normal: Vec3,
+    tangents: [Vec3; 2],
+    points: Vec<ConstraintPoint>,
+}
+
+impl ContactConstraint {
+    pub fn new(a: usize, b: usize, contact: &Contact, bodies: &[SolverBody]) -> Self {
+        let (ba, bb) = (&bodies[a], &bodies[b]);
+        let normal = contact.normal;
+        let (t1, t2) = normal.any_orthonormal_pair();
+        let (static_friction, dynamic_friction, restitution) = ba.material.combine(&bb.material);
+        let effective_mass = |ra: Vec3, rb: Vec3, dir: Vec3| {
+            let k = ba.inv_mass
+                + bb.inv_mass
+                + (ba.inv_inertia * ra.cross(dir)).cross(ra).dot(dir)
+                + (bb.inv_inertia * rb.cross(dir)).cross(rb).dot(dir);
+            if k > 0.0 { 1.0 / k } else { 0.0 }
+        };
+        let points = contact
+            .points
+            .iter()
+            .map(|p| {
+                let (ra, rb) = (p.position - ba.center_of_mass, p.position - bb.center_of_mass);
+                let relative = bb.velocity + bb.angular_velocity.cross(rb)
+                    - ba.velocity
+                    - ba.angular_velocity.cross(ra);
+                let vn = relative.dot(normal);
+                let sliding = (relative - normal * vn).length() > STATIC_FRICTION_SPEED;
+                ConstraintPoint {
+                    position: p.position,
+                    ra,
+                    rb,
+                    penetration: p.penetration,
+                    normal_mass: effective_mass(ra, rb, normal),
+                    tangent_mass: [effective_mass(ra, rb, t1), effective_mass(ra, rb, t2)],
+                    velocity_bias: if -vn > RESTITUTION_THRESHOLD { -restitution * vn } else { 0.0 },
+                    friction: if sliding { dynamic_friction } else { static_friction },
+                    normal_impulse: 0.0,
+                    tangent_impulse: [0.0; 2],
+                    pseudo_impulse: 0.0,
+                }
+            })
+            .collect();
+        Self {
+            a,
+            b,
+            normal,
+            tangents: [t1, t2],
+            points,
+        }
+    }
+
+    /// 求解后的接触信息，供碰撞事件使用
+    pub fn info(&self) -> ContactInfo {
+        let count = self.points.len().max(1) as f32;
+        ContactInfo {
+            point: self.points.iter().map(|p| p.position).sum::<Vec3>() / count,
+            normal: self.normal,
+            penetration: self.points.iter().map(|p| p.penetration).fold(0.0, f32::max),
+            impulse: self.points.iter().map(|p| p.normal_impulse).sum(),
+        }
+    }
+
+    fn bodies_mut<'a>(&self, bodies: &'a mut [SolverBody]) -> (&'a mut SolverBody, &'a mut SolverBody) {
+        if self.a < self.b {
+            let (left, right) = bodies.split_at_mut(self.b);
+            (&mut left[self.a], &mut right[0])
+        } else {
+            let (left, right) = bodies.split_at_mut(self.a);
+            (&mut right[0], &mut left[self.b])
+        }
+    }
+
+    fn warm_start(&self, bodies: &mut [SolverBody]) {
+        let (a, b) = self.bodies_mut(bodies);
+        for p in &self.points {
+            let impulse = self.normal * p.normal_impulse
+                + self.tangents[0] * p.tangent_impulse[0]
+                + self.tangents[1] * p.tangent_impulse[1];
+            a.apply_impulse(-impulse, p.ra);
+            b.apply_impulse(impulse, p.rb);
+        }
+    }
+
+    fn solve_velocity(&mut self, bodies: &mut [SolverBody], dt: f32) {
+        let (a, b) = self.bodies_mut(bodies);
+        let inv_dt = if dt > 0.0 { 1.0 / dt } else { 0.0 };
+        for p in &mut self.points {
+            let (ra, rb) = (p.ra, p.rb);
+            let relative = |a: &SolverBody, b: &SolverBody| {
+                b.velocity + b.angular_velocity.cross(rb) - a.velocity - a.angular_velocity.cross(ra)
+            };
+
+            // 库仑摩擦：切向累积冲量限制在 μ·法向冲量 之内
+            let max_friction = p.friction * p.normal_impulse;
+            for k in 0..2 {
+                let t = self.tangents[k];
+                let lambda = -relative(a, b).dot(t) * p.tangent_mass[k];
+                let old = p.tangent_impulse[k];
+                p.tangent_impulse[k] = (old + lambda).clamp(-max_friction, max_friction);
+                let impulse = t * (p.tangent_impulse[k] - old);
+                a.apply_impulse(-impulse, p.ra);
+                b.apply_impulse(impulse, p.rb);
+            }
+
+            // 法向：累积冲量不小于零
+            let vn = relative(a, b).dot(self.normal);
+            let lambda = (p.velocity_bias - vn) * p.normal_mass;
+            let old = p.normal_impulse;
+            p.normal_impulse = (old + lambda).max(0.0);
+            let impulse = self.normal * (p.normal_impulse - old);
+            a.apply_impulse(-impulse, p.ra);
+            b.apply_impulse(impulse, p.rb);
+
+            // 分离冲量：用伪速度修正穿透，不会给物体带来额外的弹开速度
+            let pseudo = b.pseudo_velocity + b.pseudo_angular_velocity.cross(rb)
+                - a.pseudo_velocity
+                - a.pseudo_angular_velocity.cross(ra);
+            let bias = BAUMGARTE * inv_dt * (p.penetration - PENETRATION_SLOP).max(0.0);
+            let lambda = (bias - pseudo.dot(self.normal)) * p.normal_mass;
+            let old = p.pseudo_impulse;
+            p.pseudo_impulse = (old + lambda).max(0.0);
+            let impulse = self.normal * (p.pseudo_impulse - old);
+            a.apply_pseudo_impulse(-impulse, p.ra);
+            b.apply_pseudo_impulse(impulse, p.rb);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct CachedPoint {
+    position: Vec3,
+    normal_impulse: f32,
+    tangent_impulse: Vec3,
+}
+
+/// 跨帧保存的接触冲量，用于热启动
+#[derive(Default)]
+pub struct ContactCache {
+    entries: HashMap<(BodyHandle, BodyHandle), Vec<CachedPoint>>,
+}
+
+impl ContactCache {
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn restore(&self, key: (BodyHandle, BodyHandle), constraint: &mut ContactConstraint) {
+        let Some(cached) = self.entries.get(&key) else {
+            return;
+        };
+        for p in &mut constraint.points {
+            let nearest = cached
+                .iter()
+                .min_by(|x, y| x.position.distance_squared(p.position).total_cmp(&y.position.distance_squared(p.position)));
+            if let Some(c) = nearest
+                && c.position.distance(p.position) < WARM_START_DISTANCE
+            {
+                p.normal_impulse = c.normal_impulse;
+                p.tangent_impulse = constraint.tangents.map(|t| c.tangent_impulse.dot(t));
+            }
+        }
+    }
+
+    fn store(&mut self, key: (BodyHandle, BodyHandle), constraint: &ContactConstraint) {
+        let points = constraint
+            .points
+            .iter()
+            .map(|p| CachedPoint {
+                position: p.position,
+                normal_impulse: p.normal_impulse,
+                tangent_impulse: constraint.tangents[0] * p.tangent_impulse[0]
+                    + constraint.tangents[1] * p.tangent_impulse[1],
+            })
+            .collect();
+        self.entries.insert(key, points);
+    }
+}
+
+/// 物理步进之间需要保留的状态
+#[derive(Default)]
+pub struct PhysicsState {
+    pub contacts: ContactCache,
+    pub broad_phase: BroadPhase,
+    pub joints: Vec<Joint>,
+    pub layers: CollisionLayers,
+    pub events: EventQueue,
+}
+
+/// 一个岛内的动态物体、接触约束与关节约束的下标
+#[derive(Default)]
+pub struct Island {
+    pub bodies: Vec<usize>,
+    pub contacts: Vec<usize>,
+    pub joints: Vec<usize>,
+}
+
+/// 按接触和关节把动态物体划分为互不相关的岛，静态物体不连接不同的岛
+pub fn build_islands(
+    bodies: &[SolverBody],
+    constraints: &[ContactConstraint],
+    joints: &[JointConstraint],
+) -> Vec<Island> {
+    let mut parent: Vec<usize> = (0..bodies.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    let links = constraints.iter().map(|c| (c.a, c.b)).chain(joints.iter().map(|j| (j.a, j.b)));
+    for (a, b) in links.clone() {
+        if bodies[a].is_dynamic() && bodies[b].is_dynamic() {
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            parent[ra] = rb;
+        }
+    }
+    let mut islands: HashMap<usize, Island> = HashMap::new();
+    for i in (0..bodies.len()).filter(|&i| bodies[i].is_dynamic()) {
+        islands.entry(find(&mut parent, i)).or_default().bodies.push(i);
+    }
+    for (i, (a, b)) in links.enumerate() {
+        let root = find(&mut parent, if bodies[a].is_dynamic() { a } else { b });
+        let island = islands.entry(root).or_default();
+        if i < constraints.len() {
+            island.contacts.push(i);
+        } else {
+            island.joints.push(i - constraints.len());
+        }
+    }
+    let mut islands: Vec<Island> = islands.into_values().collect();
+    islands.sort_by_key(|island| island.bodies.first().copied());
+    islands
+}
+
+/// 顺序冲量法求解所有接触与关节：先热启动，再逐岛迭代
+pub fn solve_contacts(
+    bodies: &mut [SolverBody],
+    constraints: &mut [ContactConstraint],
+    joints: &mut [JointConstraint],
+    dt: f32,
+) {
+    for island in build_islands(bodies, constraints, joints) {
+        for &i in &island.contacts {
+            constraints[i].warm_start(bodies);
+        }
+        for _ in 0..VELOCITY_ITERATIONS {
+            for &i in &island.joints {
+                joints[i].solve_velocity(bodies);
+            }
+            for &i in &island.contacts {
+                constraints[i].solve_velocity(bodies, dt);
+            }
+        }
+    }
+}
+
+/// 对一组刚体推进一步：积分速度、检测碰撞、求解接触、积分位置
+/// 休眠的物体不积分也不做窄相检测，被运动的物体碰到、受到外力或连在醒着的关节上时唤醒
+pub fn step_bodies(
+    bodies: &mut [(BodyHandle, &mut dyn RigidBody)],
+    gravity: [f32; 3],
+    state: &mut PhysicsState,
     dt: f32,
 ) {
-    let relative_velocity = Vec3::from_array(a.velocity()) - Vec3::from_array(b.velocity());
-    let friction_a = a.friction();
-    // let friction_b = b.friction();
-    let mass_a = a.mass();
-    let mass_b = b.mass();
-    let restituion = a.restitution();
-    let vel_along_normal = relative_velocity.dot(contact.normal);
-    let vel_along_tangent = relative_velocity - contact.normal * vel_along_normal;
-    let mut frac_a: f32 = 1.0;
-    let mut frac_b: f32 = 0.0;
-    if b.is_dynamic(){
-        frac_a = (mass_a - restituion * mass_b) / (mass_a + mass_b);
-        frac_b = (mass_a + restituion * mass_a) / (mass_a + mass_b);    
-    } else {
-        frac_a = -restituion;
-        frac_b = 0.0;
-    }
-    let vel_b = Vec3::from_array(b.velocity());
-    let vel_a_new_normal = vel_b + frac_a * vel_along_normal * contact.normal;
-    let vel_a_new_tangent = vel_along_tangent - vel_along_tangent.normalize_or_zero() * friction_a * vel_along_tangent.length() * dt;
-    let vel_b_new = (vel_b + frac_b * vel_along_normal * contact.normal).to_array();
-    let vel_a_new = (vel_a_new_normal + vel_a_new_tangent).to_array();
-    for i in 0..3 {
-        if vel_a_new[i].abs() < 0.1 { a.velocity_mut()[i] = 0.0; } else { a.velocity_mut()[i] = vel_a_new[i]; }
-        if vel_b_new[i].abs() < 0.1 { b.velocity_mut()[i] = 0.0; } else { b.velocity_mut()[i] = vel_b_new[i]; }
+    for (_, body) in bodies.iter_mut() {
+        if body.is_asleep()
+            && (body.force() != [0.0; 3]
+                || body.torque() != [0.0; 3]
+                || body.velocity() != [0.0; 3]
+                || body.angular_velocity() != [0.0; 3])
+        {
+            body.wake();
+        }
+        if body.is_asleep() {
+            continue;
+        }
+        apply_gravity(*body, gravity);
+        body.integrate_velocity(dt);
+    }
+
+    let volumes: Vec<BoundingVolume> = bodies.iter().map(|(_, body)| body.bounding_volume()).collect();
+    let proxies: Vec<BroadProxy> = bodies
+        .iter()
+        .zip(&volumes)
+        .map(|((handle, body), volume)| BroadProxy {
+            handle: *handle,
+            aabb: volume.bounds(),
+            is_static: body.is_static(),
+        })
+        .collect();
+
+    // 关节：句柄映射到下标，连接世界的一端用数组末尾的世界刚体
+    let index: HashMap<BodyHandle, usize> = bodies.iter().enumerate().map(|(i, (handle, _))| (*handle, i)).collect();
+    let world = bodies.len();
+    let joint_bodies: Vec<Option<(usize, usize)>> = state
+        .joints
+        .iter()
+        .map(|joint| {
+            let a = *index.get(&joint.a)?;
+            let b = match joint.b {
+                Some(handle) => *index.get(&handle)?,
+                None => world,
+            };
+            Some((a, b))
+        })
+        .collect();
+    let jointed: HashSet<(usize, usize)> = joint_bodies.iter().flatten().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+    let sensors: Vec<bool> = bodies.iter().map(|(_, body)| body.is_sensor()).collect();
+    let pairs: Vec<(usize, usize)> = state
+        .broad_phase
+        .find_pairs(&proxies)
+        .into_iter()
+        // 关节连接的物体之间、不碰撞的层之间不产生接触，运动学物体与静态、运动学物体之间只检测触发器
+        .filter(|&(i, j)| {
+            !jointed.contains(&(i, j))
+                && state.layers.collides(bodies[i].1.layer(), bodies[j].1.layer())
+                && (sensors[i] || sensors[j] || bodies[i].1.is_dynamic() || bodies[j].1.is_dynamic())
+        })
+        .collect();
+
+    // 窄相：至少一方醒着才检测；运动的物体碰到休眠物体时唤醒它，被唤醒的物体再与其余休眠物体检测
+    let mut asleep: Vec<bool> = bodies.iter().map(|(_, body)| body.is_asleep()).collect();
+    let awake = |asleep: &[bool], i: usize| !asleep[i] && !bodies[i].1.is_static();
+    let moving = |asleep: &[bool], i: usize| {
+        let body = &bodies[i].1;
+        awake(asleep, i)
+            && (body.is_dynamic()
+                || Vec3::from_array(body.velocity()).length() > SLEEP_LINEAR_SPEED
+                || Vec3::from_array(body.angular_velocity()).length() > SLEEP_ANGULAR_SPEED)
+    };
+    let mut tested = vec![false; pairs.len()];
+    let mut hits = Vec::new();
+    let mut overlaps = Vec::new();
+    loop {
+        let mut woke = false;
+        for (k, &(i, j)) in pairs.iter().enumerate() {
+            if tested[k] || !awake(&asleep, i) && !awake(&asleep, j) {
+                continue;
+            }
+            tested[k] = true;
+            // 触发器只记录重叠，两个触发器之间不检测
+            if sensors[i] || sensors[j] {
+                if sensors[i] != sensors[j] && collide(&volumes[i], &volumes[j]).is_some() {
+                    let (trigger, other) = if sensors[i] { (i, j) } else { (j, i) };
+                    overlaps.push((bodies[trigger].0, bodies[other].0));
+                }
+                continue;
+            }
+            let Some(contact) = collide(&volumes[i], &volumes[j]) else { continue };
+            for (x, y) in [(i, j), (j, i)] {
+                if asleep[x] && moving(&asleep, y) {
+                    asleep[x] = false;
+                    woke = true;
+                }
+            }
+            hits.push((i, j, contact));
+        }
+        for &(a, b) in joint_bodies.iter().flatten() {
+            for (x, y) in [(a, b), (b, a)] {
+                if x != world && asleep[x] && y != world && moving(&asleep, y) {
+                    asleep[x] = false;
+                    woke = true;
+                }
+            }
+        }
+        if !woke {
+            break;
+        }
+    }
+    for ((_, body), &asleep) in bodies.iter_mut().zip(&asleep) {
+        if body.is_asleep() && !asleep {
+            body.wake();
+        }
+    }
+
+    // 休眠的物体在求解中视为静止
+    let mut solver_bodies: Vec<SolverBody> = bodies
+        .iter()
+        .map(|(_, body)| {
+            let mut solver_body = SolverBody::new(*body);
+            if body.is_asleep() {
+                solver_body.inv_mass = 0.0;
+                solver_body.inv_inertia = Mat3::ZERO;
+            }
+            solver_body
+        })
+        .collect();
+    solver_bodies.push(SolverBody::world());
+    let mut joints = Vec::new();
+    for (joint, &ends) in state.joints.iter().zip(&joint_bodies) {
+        let Some((a, b)) = ends else { continue };
+        if !solver_bodies[a].is_dynamic() && !solver_bodies[b].is_dynamic() {
+            continue;
+        }
+        let frame = |i: usize| {
+            bodies.get(i).map_or((Vec3::ZERO, Quat::IDENTITY), |(_, body)| {
+                (body.transform().position, body.transform().rotation)
+            })
+        };
+        joints.push(JointConstraint::new(joint, a, b, [frame(a), frame(b)], &solver_bodies, dt));
+    }
+
+    let mut constraints = Vec::new();
+    let mut keys = Vec::new();
+    let mut resting = HashSet::new();
+    for (i, j, contact) in hits {
+        // 休眠物体与静止的运动学物体之间不产生接触，接触状态保持不变
+        if !solver_bodies[i].is_dynamic() && !solver_bodies[j].is_dynamic() {
+            resting.insert((bodies[i].0, bodies[j].0));
+            continue;
+        }
+        let mut constraint = ContactConstraint::new(i, j, &contact, &solver_bodies);
+        let key = (bodies[i].0, bodies[j].0);
+        state.contacts.restore(key, &mut constraint);
+        constraints.push(constraint);
+        keys.push(key);
+    }
+
+    solve_contacts(&mut solver_bodies, &mut constraints, &mut joints, dt);
+
+    state.contacts.clear();
+    for (key, constraint) in keys.iter().zip(&constraints) {
+        state.contacts.store(*key, constraint);
+    }
+    // 休眠物体没有检测或没有求解的接触与重叠保持不变
+    let untested: HashSet<(BodyHandle, BodyHandle)> = pairs
+        .iter()
+        .zip(&tested)
+        .filter(|&(_, tested)| !tested)
+        .flat_map(|(&(i, j), _)| [(bodies[i].0, bodies[j].0), (bodies[j].0, bodies[i].0)])
+        .collect();
+    state.events.record(
+        keys.into_iter().zip(constraints.iter().map(ContactConstraint::info)).collect(),
+        overlaps,
+        |pair| untested.contains(pair) || resting.contains(pair),
+    );
+
+    // 高速物体截断到第一次碰撞，避免一步穿过薄墙
+    let body_layers: Vec<u32> = bodies.iter().map(|(_, body)| body.layer()).collect();
+    let layers = &state.layers;
+    let motions: Vec<Vec3> = solver_bodies.iter().map(|b| (b.velocity + b.pseudo_velocity) * dt).collect();
+    for (i, ((_, body), state)) in bodies.iter_mut().zip(&solver_bodies).enumerate() {
+        if body.is_static() || body.is_asleep() {
+            continue;
+        }
+        *body.velocity_mut() = state.velocity.to_array();
+        *body.angular_velocity_mut() = state.angular_velocity.to_array();
+        let fraction = if body.continuous() {
+            clamp_motion(&volumes, &motions, i, |j| !sensors[j] && layers.collides(body_layers[i], body_layers[j]))
+        } else {
+            1.0
+        };
+        body.integrate_position(
+            (state.velocity + state.pseudo_velocity) * fraction,
+            (state.angular_velocity + state.pseudo_angular_velocity) * fraction,
+            dt,
+        );
+    }
+
+    // 岛内所有物体都静止足够久时一起休眠
+    for island in build_islands(&solver_bodies, &constraints, &joints) {
+        let mut ready = true;
+        for &i in &island.bodies {
+            let (velocity, angular_velocity) = (solver_bodies[i].velocity, solver_bodies[i].angular_velocity);
+            ready &= bodies[i].1.sleep_state_mut().update(velocity, angular_velocity);
+        }
+        if ready {
+            for &i in &island.bodies {
+                bodies[i].1.fall_asleep();
+            }
+        }
     }
 }
 
@@ -77,17 +578,130 @@ pub fn apply_gravity(obj: &mut dyn RigidBody, gravity: [f32; 3]) {
     }
 }
 
-pub fn stimulate_step(handle: BodyHandle, world: &mut World, dt: f32) {
-    match handle {
-        BodyHandle::Object(idx) => {
-            let obj = &mut world.objects[idx];
-            apply_gravity(obj, world.gravity);
-            obj.stimulate(dt);
-        }
-        BodyHandle::Camera(idx) => {
-            let cam = &mut world.cameras[idx];
-            apply_gravity(cam, world.gravity);
-            cam.stimulate(dt);
-        }
+/// 测试用的盒子，底面为 width × width
+#[cfg(test)]
+pub(crate) fn crate_object(name: &str, width: f32, height: f32, dynamic: bool) -> crate::scene::world::GameObject {
+    use crate::core::material::Material;
+    use crate::geometry::shape::cube::Cube;
+    use crate::scene::world::{BodyType, GameObject};
+
+    let mut obj = GameObject::new(name, Box::new(Cube { width, height, depth: width }), Material::default());
+    if dynamic {
+        obj.set_body_type(BodyType::Dynamic);
+    }
+    obj
+}
+
+/// 测试用的地面，顶面位于 y = 0
+#[cfg(test)]
+pub(crate) fn floor_object(width: f32) -> crate::scene::world::GameObject {
+    let mut floor = crate_object("floor", width, 0.5, false);
+    floor.transform.position.y = -0.25;
+    floor
+}
+
+/// 测试用：按下标作为句柄，在重力下推进一步
+#[cfg(test)]
+pub(crate) fn step_objects(objects: &mut [&mut crate::scene::world::GameObject], state: &mut PhysicsState, dt: f32) {
+    let mut bodies: Vec<(BodyHandle, &mut dyn RigidBody)> = objects
+        .iter_mut()
+        .enumerate()
+        .map(|(i, obj)| (BodyHandle::Object(i), &mut **obj as &mut dyn RigidBody))
+        .collect();
+    step_bodies(&mut bodies, [0.0, -9.8, 0.0], state, dt);
+}
+
+#[test]
+fn test_off_center_contact_spins_box() {
+    let mut floor = crate_object("floor", 10.0, 0.5, false);
+    floor.transform.position.y = -0.5;
+    // 绕 z 轴倾斜的盒子以一条棱落地
+    let mut body = crate_object("box", 0.5, 0.5, true);
+    body.transform.rotation = glam::Quat::from_rotation_z(0.3);
+    body.transform.position.y = 0.6;
+    body.physics.velocity = [0.0, -3.0, 0.0];
+
+    let mut state = PhysicsState::default();
+    let mut spun = false;
+    for _ in 0..120 {
+        step_objects(&mut [&mut floor, &mut body], &mut state, 1.0 / 60.0);
+        spun |= body.physics.angular_velocity[2].abs() > 0.5;
+    }
+    assert!(spun);
+    // 倾倒后平放在地面上
+    let up = body.transform.rotation * Vec3::Y;
+    let flat = [Vec3::X, Vec3::Y, Vec3::Z].iter().any(|a| up.dot(*a).abs() > 0.99);
+    assert!(flat, "up = {up}");
+}
+
+#[test]
+fn test_crate_stack_stays_put() {
+    let mut floor = floor_object(10.0);
+    let material = PhysicsMaterial { static_friction: 0.5, dynamic_friction: 0.5, ..PhysicsMaterial::DEFAULT };
+    floor.physics.material = material;
+    let mut crates: Vec<_> = (0..4)
+        .map(|i| {
+            let mut c = crate_object("crate", 1.0, 1.0, true);
+            c.transform.position.y = 0.5 + i as f32;
+            c.physics.material = material;
+            c
+        })
+        .collect();
+
+    let mut state = PhysicsState::default();
+    for _ in 0..180 {
+        let mut objects: Vec<_> = std::iter::once(&mut floor).chain(crates.iter_mut()).collect();
+        step_objects(&mut objects, &mut state, 1.0 / 60.0);
+    }
+    for (i, c) in crates.iter().enumerate() {
+        let p = c.transform.position;
+        assert!((p.y - (0.5 + i as f32)).abs() < 0.02, "crate {i} at {p}");
+        assert!(p.x.abs() < 0.01 && p.z.abs() < 0.01, "crate {i} at {p}");
+        assert!(c.transform.rotation.to_scaled_axis().length() < 0.01);
+        assert!(Vec3::from_array(c.physics.velocity).length() < 0.05);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_kinematic_platform_lifts_box() {
+    use crate::scene::world::BodyType;
+
+    let mut platform = crate_object("platform", 4.0, 0.2, false);
+    platform.set_body_type(BodyType::Kinematic);
+    let mut body = crate_object("box", 1.0, 1.0, true);
+    body.transform.position.y = 0.6;
+
+    let mut state = PhysicsState::default();
+    let dt = 1.0 / 60.0;
+    for _ in 0..120 {
+        let mut target = platform.transform.clone();
+        target.position.y += dt;
+        platform.drive_to(target, dt);
+        step_objects(&mut [&mut platform, &mut body], &mut state, dt);
+    }
+    // 平台上升 2 m，箱子随之上升且不穿透
+    assert!((platform.transform.position.y - 2.0).abs() < 1e-3);
+    assert!((body.transform.position.y - 2.6).abs() < 0.02, "box at {}", body.transform.position);
+    assert!((body.physics.velocity[1] - 1.0).abs() < 0.1);
+}
+
+#[test]
+fn test_ice_floor_slides_further_than_wood() {
+    let slide = |floor_material: PhysicsMaterial| {
+        let mut floor = floor_object(40.0);
+        floor.physics.material = floor_material;
+        let mut body = crate_object("box", 1.0, 1.0, true);
+        body.transform.position.y = 0.5;
+        body.physics.material = PhysicsMaterial::WOOD;
+        body.physics.velocity = [5.0, 0.0, 0.0];
+        let mut state = PhysicsState::default();
+        for _ in 0..120 {
+            step_objects(&mut [&mut floor, &mut body], &mut state, 1.0 / 60.0);
+        }
+        body.transform.position.x
+    };
+    let (ice, wood) = (slide(PhysicsMaterial::ICE), slide(PhysicsMaterial::WOOD));
+    // 木头上 μ = 0.4，约 v²/(2μg) ≈ 3.2 m 后停下
+    assert!((wood - 3.2).abs() < 0.3, "wood = {wood}");
+    assert!(ice > 8.0, "ice = {ice}");
+}
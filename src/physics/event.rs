@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+
+use glam::f32::Vec3;
+
+use crate::scene::world::BodyHandle;
+
+/// 一对物体的接触信息：point 为接触点的平均位置，法线从 a 指向 b，impulse 为本步的法向冲量
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactInfo {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub penetration: f32,
+    pub impulse: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhysicsEvent {
+    CollisionBegin { a: BodyHandle, b: BodyHandle, contact: ContactInfo },
+    CollisionStay { a: BodyHandle, b: BodyHandle, contact: ContactInfo },
+    CollisionEnd { a: BodyHandle, b: BodyHandle },
+    TriggerEnter { trigger: BodyHandle, other: BodyHandle },
+    TriggerExit { trigger: BodyHandle, other: BodyHandle },
+}
+
+/// 物理事件队列：比较相邻两步的接触对与触发器重叠对生成事件
+#[derive(Default)]
+pub struct EventQueue {
+    events: Vec<PhysicsEvent>,
+    touching: HashSet<(BodyHandle, BodyHandle)>,
+    overlapping: HashSet<(BodyHandle, BodyHandle)>,
+}
+
+impl EventQueue {
+    pub fn events(&self) -> &[PhysicsEvent] {
+        &self.events
+    }
+
+    /// 丢弃已有事件，接触状态保留，下一步只报告变化
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn drain(&mut self) -> std::vec::Drain<'_, PhysicsEvent> {
+        self.events.drain(..)
+    }
+
+    /// 记录一步的结果：contacts 为产生接触的物体对，overlaps 为 (触发器, 其它物体)，
+    /// unchanged 为真的物体对 (如一方在休眠) 保持上一步的状态，不产生事件
+    pub fn record(
+        &mut self,
+        contacts: Vec<((BodyHandle, BodyHandle), ContactInfo)>,
+        overlaps: Vec<(BodyHandle, BodyHandle)>,
+        unchanged: impl Fn(&(BodyHandle, BodyHandle)) -> bool,
+    ) {
+        let mut touching: HashSet<_> = contacts.iter().map(|(pair, _)| *pair).collect();
+        let mut overlapping: HashSet<_> = overlaps.iter().copied().collect();
+        touching.extend(self.touching.iter().filter(|pair| unchanged(pair)));
+        overlapping.extend(self.overlapping.iter().filter(|pair| unchanged(pair)));
+        for ((a, b), contact) in contacts {
+            self.events.push(if self.touching.contains(&(a, b)) {
+                PhysicsEvent::CollisionStay { a, b, contact }
+            } else {
+                PhysicsEvent::CollisionBegin { a, b, contact }
+            });
+        }
+        for &(a, b) in self.touching.difference(&touching) {
+            self.events.push(PhysicsEvent::CollisionEnd { a, b });
+        }
+        for &(trigger, other) in overlaps.iter().filter(|pair| !self.overlapping.contains(pair)) {
+            self.events.push(PhysicsEvent::TriggerEnter { trigger, other });
+        }
+        for &(trigger, other) in self.overlapping.difference(&overlapping) {
+            self.events.push(PhysicsEvent::TriggerExit { trigger, other });
+        }
+        self.touching = touching;
+        self.overlapping = overlapping;
+    }
+
+    /// 物体被删除后下标前移：涉及它的接触与事件直接丢弃，不产生结束事件
+    pub fn remap_removed(&mut self, removed: BodyHandle) {
+        let shift = |handle: BodyHandle| match (handle, removed) {
+            (BodyHandle::Object(i), BodyHandle::Object(r)) if i > r => BodyHandle::Object(i - 1),
+            _ => handle,
+        };
+        let remap = |pairs: &HashSet<(BodyHandle, BodyHandle)>| {
+            pairs
+                .iter()
+                .filter(|(a, b)| *a != removed && *b != removed)
+                .map(|&(a, b)| (shift(a), shift(b)))
+                .collect()
+        };
+        self.touching = remap(&self.touching);
+        self.overlapping = remap(&self.overlapping);
+        self.events.retain_mut(|event| {
+            let (a, b) = match event {
+                PhysicsEvent::CollisionBegin { a, b, .. }
+                | PhysicsEvent::CollisionStay { a, b, .. }
+                | PhysicsEvent::CollisionEnd { a, b } => (a, b),
+                PhysicsEvent::TriggerEnter { trigger, other } | PhysicsEvent::TriggerExit { trigger, other } => {
+                    (trigger, other)
+                }
+            };
+            if *a == removed || *b == removed {
+                return false;
+            }
+            (*a, *b) = (shift(*a), shift(*b));
+            true
+        });
+    }
+}
+
+#[test]
+fn test_trigger_and_collision_events() {
+    use crate::physics::{collision::solve::{PhysicsState, crate_object, floor_object, step_objects}, rigid::RigidBody};
+    use crate::scene::world::GameObject;
+
+    // 盒子先穿过半空中的触发区域，再落到地面上
+    let mut floor = floor_object(10.0);
+    floor.physics.material.restitution = 0.0;
+    let mut sensor = crate_object("sensor", 4.0, 0.5, false);
+    sensor.transform.position.y = 2.0;
+    sensor.physics.sensor = true;
+    let mut body = crate_object("box", 0.5, 0.5, true);
+    body.transform.position.y = 3.0;
+    body.physics.material.restitution = 0.0;
+
+    let (floor_h, sensor_h, body_h) = (BodyHandle::Object(0), BodyHandle::Object(1), BodyHandle::Object(2));
+    let mut state = PhysicsState::default();
+    let mut step = |body: &mut GameObject, state: &mut PhysicsState| -> Vec<PhysicsEvent> {
+        step_objects(&mut [&mut floor, &mut sensor, body], state, 1.0 / 60.0);
+        state.events.drain().collect()
+    };
+    let mut log = Vec::new();
+    for _ in 0..90 {
+        log.extend(step(&mut body, &mut state));
+    }
+    // 触发器不挡住盒子，落地后保持接触
+    assert!(body.transform.position.y < 0.4, "y = {}", body.transform.position.y);
+    let index = |pred: &dyn Fn(&PhysicsEvent) -> bool| log.iter().position(pred).unwrap();
+    let enter = index(&|e| *e == PhysicsEvent::TriggerEnter { trigger: sensor_h, other: body_h });
+    let exit = index(&|e| *e == PhysicsEvent::TriggerExit { trigger: sensor_h, other: body_h });
+    let begin = index(&|e| matches!(e, PhysicsEvent::CollisionBegin { a, b, .. } if (*a, *b) == (floor_h, body_h)));
+    assert!(enter < exit && exit < begin);
+    let PhysicsEvent::CollisionBegin { contact, .. } = log[begin] else { unreachable!() };
+    assert!(contact.normal.dot(Vec3::Y) > 0.99 && contact.impulse > 0.0, "{contact:?}");
+    assert!(matches!(log.last(), Some(PhysicsEvent::CollisionStay { .. })));
+    assert_eq!(log.iter().filter(|e| matches!(e, PhysicsEvent::TriggerEnter { .. })).count(), 1);
+
+    // 离开地面时产生结束事件
+    body.transform.position.y = 5.0;
+    body.wake();
+    assert_eq!(step(&mut body, &mut state), [PhysicsEvent::CollisionEnd { a: floor_h, b: body_h }]);
+}
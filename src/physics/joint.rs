@@ -0,0 +1,428 @@
+use crate::{core::math::transform::Transform, physics::collision::solve::SolverBody, scene::world::BodyHandle};
+
+use glam::f32::{Quat, Vec3};
+
+/// 关节位置误差每步修正的比例
+const JOINT_BAUMGARTE: f32 = 0.2;
+/// 离限制还差多少 (弧度或米) 时开始生效，提前生效可避免越过限制
+const LIMIT_MARGIN: f32 = 0.05;
+
+/// 关节马达：以目标速度转动 (或平移)，出力不超过 max_force
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JointMotor {
+    pub speed: f32,
+    pub max_force: f32,
+}
+
+/// 关节类型，轴为世界坐标，建立关节时转换到 a 的局部坐标
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JointKind {
+    /// 铰链：只能绕 axis 转动，limits 为转角范围 (弧度)
+    Hinge { axis: Vec3, limits: Option<(f32, f32)>, motor: Option<JointMotor> },
+    /// 球窝：锚点重合，可以任意转动
+    BallSocket,
+    /// 固定：锚点重合且相对旋转不变
+    Fixed,
+    /// 滑轨：只能沿 axis 平移，limits 为位移范围
+    Slider { axis: Vec3, limits: Option<(f32, f32)> },
+    /// 两锚点保持固定距离，像一根轻杆
+    Distance { length: f32 },
+    /// 两锚点之间的阻尼弹簧
+    Spring { length: f32, stiffness: f32, damping: f32 },
+}
+
+impl JointKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JointKind::Hinge { .. } => "铰链",
+            JointKind::BallSocket => "球窝",
+            JointKind::Fixed => "固定",
+            JointKind::Slider { .. } => "滑轨",
+            JointKind::Distance { .. } => "距离",
+            JointKind::Spring { .. } => "弹簧",
+        }
+    }
+}
+
+/// 连接两个物体的关节，b 为 None 时连接到世界
+#[derive(Clone, Debug)]
+pub struct Joint {
+    pub a: BodyHandle,
+    pub b: Option<BodyHandle>,
+    /// 锚点在各自物体局部坐标中的位置，连接世界时 anchor_b 为世界坐标
+    pub anchor_a: Vec3,
+    pub anchor_b: Vec3,
+    pub kind: JointKind,
+    /// 建立时 b 相对 a 的旋转，固定关节与转角都以它为零点
+    reference: Quat,
+}
+
+/// 刚体的位置与旋转，世界为原点
+fn frame(transform: Option<&Transform>) -> (Vec3, Quat) {
+    transform.map_or((Vec3::ZERO, Quat::IDENTITY), |t| (t.position, t.rotation))
+}
+
+impl Joint {
+    /// 按两物体当前的位姿建立关节，锚点与轴都是世界坐标
+    pub fn new(
+        a: (BodyHandle, &Transform),
+        b: Option<(BodyHandle, &Transform)>,
+        anchor_a: Vec3,
+        anchor_b: Vec3,
+        kind: JointKind,
+    ) -> Self {
+        let (pa, qa) = frame(Some(a.1));
+        let (pb, qb) = frame(b.map(|(_, t)| t));
+        let kind = match kind {
+            JointKind::Hinge { axis, limits, motor } => {
+                JointKind::Hinge { axis: qa.inverse() * axis.normalize(), limits, motor }
+            }
+            JointKind::Slider { axis, limits } => JointKind::Slider { axis: qa.inverse() * axis.normalize(), limits },
+            kind => kind,
+        };
+        Self {
+            a: a.0,
+            b: b.map(|(handle, _)| handle),
+            anchor_a: qa.inverse() * (anchor_a - pa),
+            anchor_b: qb.inverse() * (anchor_b - pb),
+            kind,
+            reference: qa.inverse() * qb,
+        }
+    }
+
+    /// 是否连接了该物体
+    pub fn involves(&self, handle: BodyHandle) -> bool {
+        self.a == handle || self.b == Some(handle)
+    }
+
+    /// 物体被移除时修正句柄，返回 false 表示关节连接着被移除的物体
+    pub fn remap_removed(&mut self, removed: BodyHandle) -> bool {
+        if self.involves(removed) {
+            return false;
+        }
+        let shift = |handle: &mut BodyHandle| {
+            if let (BodyHandle::Object(i), BodyHandle::Object(r)) = (handle, removed)
+                && *i > r
+            {
+                *i -= 1;
+            }
+        };
+        shift(&mut self.a);
+        if let Some(b) = &mut self.b {
+            shift(b);
+        }
+        true
+    }
+}
+
+/// 一维约束行：J·v + bias = 0，累积冲量限制在 [min, max]
+struct JointRow {
+    linear_a: Vec3,
+    angular_a: Vec3,
+    linear_b: Vec3,
+    angular_b: Vec3,
+    bias: f32,
+    impulse: f32,
+    min: f32,
+    max: f32,
+}
+
+impl JointRow {
+    fn velocity_error(&self, a: &SolverBody, b: &SolverBody) -> f32 {
+        self.linear_a.dot(a.velocity)
+            + self.angular_a.dot(a.angular_velocity)
+            + self.linear_b.dot(b.velocity)
+            + self.angular_b.dot(b.angular_velocity)
+            + self.bias
+    }
+
+    /// 两行约束通过刚体质量耦合的程度 J_i M⁻¹ J_jᵀ
+    fn coupling(&self, other: &JointRow, a: &SolverBody, b: &SolverBody) -> f32 {
+        a.inv_mass * self.linear_a.dot(other.linear_a)
+            + self.angular_a.dot(a.inv_inertia * other.angular_a)
+            + b.inv_mass * self.linear_b.dot(other.linear_b)
+            + self.angular_b.dot(b.inv_inertia * other.angular_b)
+    }
+
+    fn apply(&mut self, a: &mut SolverBody, b: &mut SolverBody, lambda: f32) {
+        self.impulse += lambda;
+        a.velocity += self.linear_a * (a.inv_mass * lambda);
+        a.angular_velocity += a.inv_inertia * self.angular_a * lambda;
+        b.velocity += self.linear_b * (b.inv_mass * lambda);
+        b.angular_velocity += b.inv_inertia * self.angular_b * lambda;
+    }
+}
+
+/// 高斯消元解 n 阶线性方程组 (矩阵按行存储)，奇异时返回 None
+fn solve_linear(mut m: Vec<f32>, mut rhs: Vec<f32>) -> Option<Vec<f32>> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&x, &y| m[x * n + col].abs().total_cmp(&m[y * n + col].abs()))?;
+        if m[pivot * n + col].abs() < 1e-9 {
+            return None;
+        }
+        for k in 0..n {
+            m.swap(col * n + k, pivot * n + k);
+        }
+        rhs.swap(col, pivot);
+        for row in col + 1..n {
+            let factor = m[row * n + col] / m[col * n + col];
+            for k in col..n {
+                m[row * n + k] -= factor * m[col * n + k];
+            }
+            rhs[row] -= factor * rhs[col];
+        }
+    }
+    for col in (0..n).rev() {
+        let sum: f32 = (col + 1..n).map(|k| m[col * n + k] * rhs[k]).sum();
+        rhs[col] = (rhs[col] - sum) / m[col * n + col];
+    }
+    Some(rhs)
+}
+
+/// 一步内的关节约束，a、b 为求解器刚体数组的下标
+/// 同一关节的各行耦合很强 (例如锚点离质心很远时)，逐行迭代收敛太慢，因此整体求解
+pub struct JointConstraint {
+    pub a: usize,
+    pub b: usize,
+    rows: Vec<JointRow>,
+    /// 有效质量矩阵 J M⁻¹ Jᵀ，按行存储
+    mass: Vec<f32>,
+}
+
+impl JointConstraint {
+    /// frames 为两物体当前的 (位置, 旋转)
+    pub fn new(joint: &Joint, a: usize, b: usize, frames: [(Vec3, Quat); 2], bodies: &[SolverBody], dt: f32) -> Self {
+        let (ba, bb) = (&bodies[a], &bodies[b]);
+        let [(pos_a, qa), (pos_b, qb)] = frames;
+        let anchor_a = pos_a + qa * joint.anchor_a;
+        let anchor_b = pos_b + qb * joint.anchor_b;
+        let (ra, rb) = (anchor_a - ba.center_of_mass, anchor_b - bb.center_of_mass);
+        let d = anchor_b - anchor_a;
+        let beta = if dt > 0.0 { JOINT_BAUMGARTE / dt } else { 0.0 };
+
+        let mut rows = Vec::new();
+        // 约束行：线性方向 linear 作用在锚点上，angular 为纯转动部分
+        let mut push = |linear: Vec3, ra: Vec3, rb: Vec3, angular: Vec3, bias: f32, min: f32, max: f32| {
+            let (angular_a, angular_b) = (-ra.cross(linear) - angular, rb.cross(linear) + angular);
+            let k = (ba.inv_mass + bb.inv_mass) * linear.length_squared()
+                + angular_a.dot(ba.inv_inertia * angular_a)
+                + angular_b.dot(bb.inv_inertia * angular_b);
+            if k > f32::EPSILON {
+                rows.push(JointRow {
+                    linear_a: -linear,
+                    angular_a,
+                    linear_b: linear,
+                    angular_b,
+                    bias,
+                    impulse: 0.0,
+                    min,
+                    max,
+                });
+            }
+        };
+        // 单向的限制：separation 为离限制的余量，余量为正时允许在一步内恰好到达
+        let limit_bias = |separation: f32| if separation > 0.0 { separation / dt } else { beta * separation };
+
+        let axes = [Vec3::X, Vec3::Y, Vec3::Z];
+        // b 应有的旋转与实际旋转的差，用于固定关节与滑轨
+        let rotation_error = || {
+            let q = qb * (qa * joint.reference).inverse();
+            let q = if q.w < 0.0 { -q } else { q };
+            Vec3::new(q.x, q.y, q.z) * 2.0
+        };
+
+        match joint.kind {
+            JointKind::BallSocket => {
+                for e in axes {
+                    push(e, ra, rb, Vec3::ZERO, beta * d.dot(e), f32::MIN, f32::MAX);
+                }
+            }
+            JointKind::Fixed => {
+                for e in axes {
+                    push(e, ra, rb, Vec3::ZERO, beta * d.dot(e), f32::MIN, f32::MAX);
+                }
+                let error = rotation_error();
+                for e in axes {
+                    push(Vec3::ZERO, ra, rb, e, beta * error.dot(e), f32::MIN, f32::MAX);
+                }
+            }
+            JointKind::Hinge { axis, limits, motor } => {
+                for e in axes {
+                    push(e, ra, rb, Vec3::ZERO, beta * d.dot(e), f32::MIN, f32::MAX);
+                }
+                // 两物体上的铰链轴保持平行
+                let axis_a = qa * axis;
+                let axis_b = qb * (joint.reference.inverse() * axis);
+                let error = axis_a.cross(axis_b);
+                let (t1, t2) = axis_a.any_orthonormal_pair();
+                for t in [t1, t2] {
+                    push(Vec3::ZERO, ra, rb, t, beta * error.dot(t), f32::MIN, f32::MAX);
+                }
+                if let Some((lower, upper)) = limits {
+                    let perp = axis.any_orthonormal_vector();
+                    let (u, v) = (qa * perp, qb * (joint.reference.inverse() * perp));
+                    let angle = v.cross(u).dot(axis_a).atan2(v.dot(u));
+                    if angle - lower < LIMIT_MARGIN {
+                        push(Vec3::ZERO, ra, rb, -axis_a, limit_bias(angle - lower), 0.0, f32::MAX);
+                    }
+                    if upper - angle < LIMIT_MARGIN {
+                        push(Vec3::ZERO, ra, rb, axis_a, limit_bias(upper - angle), 0.0, f32::MAX);
+                    }
+                }
+                if let Some(motor) = motor {
+                    let max = motor.max_force * dt;
+                    push(Vec3::ZERO, ra, rb, -axis_a, -motor.speed, -max, max);
+                }
+            }
+            JointKind::Slider { axis, limits } => {
+                // 垂直于轴的方向上锚点重合，约束作用在 b 的锚点上
+                let axis_a = qa * axis;
+                let ra = anchor_b - ba.center_of_mass;
+                let (t1, t2) = axis_a.any_orthonormal_pair();
+                for t in [t1, t2] {
+                    push(t, ra, rb, Vec3::ZERO, beta * d.dot(t), f32::MIN, f32::MAX);
+                }
+                let error = rotation_error();
+                for e in axes {
+                    push(Vec3::ZERO, ra, rb, e, beta * error.dot(e), f32::MIN, f32::MAX);
+                }
+                if let Some((lower, upper)) = limits {
+                    let offset = -d.dot(axis_a);
+                    if offset - lower < LIMIT_MARGIN {
+                        push(-axis_a, ra, rb, Vec3::ZERO, limit_bias(offset - lower), 0.0, f32::MAX);
+                    }
+                    if upper - offset < LIMIT_MARGIN {
+                        push(axis_a, ra, rb, Vec3::ZERO, limit_bias(upper - offset), 0.0, f32::MAX);
+                    }
+                }
+            }
+            JointKind::Distance { length } => {
+                let current = d.length();
+                if current > f32::EPSILON {
+                    push(d / current, ra, rb, Vec3::ZERO, beta * (current - length), f32::MIN, f32::MAX);
+                }
+            }
+            JointKind::Spring { length, stiffness, damping } => {
+                let current = d.length();
+                if current > f32::EPSILON {
+                    // 弹簧力在本步内恒定，用上下限相等的行施加一次
+                    let n = d / current;
+                    let relative = bb.velocity + bb.angular_velocity.cross(rb)
+                        - ba.velocity
+                        - ba.angular_velocity.cross(ra);
+                    let impulse = -(stiffness * (current - length) + damping * relative.dot(n)) * dt;
+                    push(n, ra, rb, Vec3::ZERO, 0.0, impulse, impulse);
+                }
+            }
+        }
+        let n = rows.len();
+        let mass = (0..n * n).map(|i| rows[i / n].coupling(&rows[i % n], ba, bb)).collect();
+        Self { a, b, rows, mass }
+    }
+
+    pub fn solve_velocity(&mut self, bodies: &mut [SolverBody]) {
+        if self.a == self.b {
+            return;
+        }
+        let (a, b) = if self.a < self.b {
+            let (left, right) = bodies.split_at_mut(self.b);
+            (&mut left[self.a], &mut right[0])
+        } else {
+            let (left, right) = bodies.split_at_mut(self.a);
+            (&mut right[0], &mut left[self.b])
+        };
+        // 先对所有行整体求解，超出冲量范围的行截断后固定，其余行重新求解
+        let n = self.rows.len();
+        let mut free: Vec<usize> = (0..n).collect();
+        while !free.is_empty() {
+            let m = free.iter().flat_map(|&i| free.iter().map(move |&j| (i, j))).map(|(i, j)| self.mass[i * n + j]).collect();
+            let rhs = free.iter().map(|&i| -self.rows[i].velocity_error(a, b)).collect();
+            let Some(lambda) = solve_linear(m, rhs) else {
+                // 约束线性相关时退回逐行求解
+                for &i in &free {
+                    let row = &mut self.rows[i];
+                    let lambda = -row.velocity_error(a, b) / self.mass[i * n + i];
+                    let clamped = (row.impulse + lambda).clamp(row.min, row.max) - row.impulse;
+                    row.apply(a, b, clamped);
+                }
+                break;
+            };
+            let clamped: Vec<usize> = (0..free.len())
+                .filter(|&k| {
+                    let row = &self.rows[free[k]];
+                    !(row.min..=row.max).contains(&(row.impulse + lambda[k]))
+                })
+                .collect();
+            if clamped.is_empty() {
+                for (k, &i) in free.iter().enumerate() {
+                    self.rows[i].apply(a, b, lambda[k]);
+                }
+                break;
+            }
+            for &k in &clamped {
+                let row = &mut self.rows[free[k]];
+                let lambda = (row.impulse + lambda[k]).clamp(row.min, row.max) - row.impulse;
+                row.apply(a, b, lambda);
+            }
+            free = (0..free.len()).filter(|k| !clamped.contains(k)).map(|k| free[k]).collect();
+        }
+    }
+}
+
+#[test]
+fn test_joints_hold_bodies() {
+    use crate::physics::{collision::solve::{PhysicsState, crate_object, step_objects}, rigid::RigidBody};
+
+    // 一个挂在世界上的小盒子，anchors 为盒子与世界上的锚点，模拟 seconds 秒后返回盒子
+    let simulate = |position: Vec3, anchors: (Vec3, Vec3), kind: JointKind, velocity: Vec3, seconds: f32| {
+        let mut body = crate_object("box", 0.2, 0.2, true);
+        body.transform.position = position;
+        body.physics.velocity = velocity.to_array();
+        let mut state = PhysicsState::default();
+        state.joints.push(Joint::new((BodyHandle::Object(0), &body.transform), None, anchors.0, anchors.1, kind));
+        let mut lowest = f32::INFINITY;
+        for _ in 0..(seconds * 60.0) as usize {
+            step_objects(&mut [&mut body], &mut state, 1.0 / 60.0);
+            lowest = lowest.min(body.transform.position.y);
+        }
+        (body, lowest)
+    };
+
+    // 球窝：单摆摆下来，摆长不变
+    let (body, lowest) = simulate(Vec3::X, (Vec3::ZERO, Vec3::ZERO), JointKind::BallSocket, Vec3::ZERO, 0.8);
+    assert!(lowest < -0.95, "lowest = {lowest}");
+    assert!((body.transform.position.length() - 1.0).abs() < 0.02, "position = {}", body.transform.position);
+
+    // 固定：不受重力下落，也不转动
+    let (body, _) = simulate(Vec3::X, (Vec3::ZERO, Vec3::ZERO), JointKind::Fixed, Vec3::ZERO, 2.0);
+    assert!(body.transform.position.distance(Vec3::X) < 0.02, "position = {}", body.transform.position);
+    assert!(body.transform.rotation.angle_between(Quat::IDENTITY) < 0.02);
+
+    // 铰链：绕竖直轴转动，转角限制在 [-45°, 45°]，不会下垂
+    let limit = std::f32::consts::FRAC_PI_4;
+    let hinge = JointKind::Hinge { axis: Vec3::Y, limits: Some((-limit, limit)), motor: None };
+    let (body, _) = simulate(Vec3::X, (Vec3::ZERO, Vec3::ZERO), hinge, Vec3::Z * 3.0, 1.0);
+    let angle = body.transform.position.z.atan2(body.transform.position.x);
+    assert!(angle.abs() < limit + 0.05 && angle.abs() > limit - 0.1, "angle = {angle}");
+    assert!(body.transform.position.y.abs() < 0.02, "position = {}", body.transform.position);
+
+    // 马达：以给定转速转动
+    let motor = JointMotor { speed: 1.0, max_force: 100.0 };
+    let hinge = JointKind::Hinge { axis: Vec3::Y, limits: None, motor: Some(motor) };
+    let (body, _) = simulate(Vec3::X, (Vec3::ZERO, Vec3::ZERO), hinge, Vec3::ZERO, 1.0);
+    assert!((body.angular_velocity()[1] - 1.0).abs() < 0.05, "omega = {:?}", body.angular_velocity());
+
+    // 滑轨：沿 x 方向滑出，停在限制处
+    let slider = JointKind::Slider { axis: Vec3::X, limits: Some((0.0, 0.5)) };
+    let (body, _) = simulate(Vec3::ZERO, (Vec3::ZERO, Vec3::ZERO), slider, Vec3::X * 3.0, 1.0);
+    assert!(body.transform.position.distance(Vec3::X * 0.5) < 0.03, "position = {}", body.transform.position);
+
+    // 距离与弹簧：挂在正上方，弹簧平衡时伸长 mg / k
+    let (body, _) = simulate(-Vec3::Y, (-Vec3::Y, Vec3::ZERO), JointKind::Distance { length: 1.0 }, Vec3::X * 2.0, 1.0);
+    assert!((body.transform.position.length() - 1.0).abs() < 0.02, "position = {}", body.transform.position);
+    let spring = JointKind::Spring { length: 1.0, stiffness: 100.0, damping: 5.0 };
+    let (body, _) = simulate(-Vec3::Y, (-Vec3::Y, Vec3::ZERO), spring, Vec3::ZERO, 5.0);
+    let stretch = body.mass() * 9.8 / 100.0;
+    assert!((body.transform.position.y + 1.0 + stretch).abs() < 0.02, "position = {}", body.transform.position);
+}
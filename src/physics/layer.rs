@@ -0,0 +1,106 @@
+/// 最多 32 层，与查询过滤的 mask 位数一致
+pub const MAX_LAYERS: usize = 32;
+
+/// 命名的碰撞层与层间碰撞矩阵：matrix[i] 的第 j 位表示第 i 层与第 j 层是否碰撞
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollisionLayers {
+    names: Vec<String>,
+    matrix: [u32; MAX_LAYERS],
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        let mut layers = Self {
+            names: ["默认", "静态", "碎片", "玩家", "触发器", "可穿过"].map(String::from).to_vec(),
+            matrix: [u32::MAX; MAX_LAYERS],
+        };
+        layers.set_collides(Self::DEBRIS, Self::DEBRIS, false);
+        layers.set_collides(Self::TRIGGER, Self::STATIC, false);
+        layers.set_collides(Self::PLAYER, Self::PASS_THROUGH, false);
+        layers
+    }
+}
+
+impl CollisionLayers {
+    pub const DEFAULT: u32 = 0;
+    pub const STATIC: u32 = 1;
+    /// 碎片之间不碰撞
+    pub const DEBRIS: u32 = 2;
+    pub const PLAYER: u32 = 3;
+    /// 触发器不与静态几何碰撞
+    pub const TRIGGER: u32 = 4;
+    /// 玩家可以穿过
+    pub const PASS_THROUGH: u32 = 5;
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn name(&self, layer: u32) -> &str {
+        self.names.get(layer as usize).map_or("未命名", String::as_str)
+    }
+
+    pub fn rename(&mut self, layer: u32, name: &str) {
+        if let Some(n) = self.names.get_mut(layer as usize) {
+            *n = name.to_string();
+        }
+    }
+
+    /// 新增一层，默认与所有层碰撞；层数已满时返回 None
+    pub fn add(&mut self, name: &str) -> Option<u32> {
+        if self.names.len() >= MAX_LAYERS {
+            return None;
+        }
+        self.names.push(name.to_string());
+        Some(self.names.len() as u32 - 1)
+    }
+
+    pub fn collides(&self, a: u32, b: u32) -> bool {
+        self.mask(a) & 1 << (b as usize).min(MAX_LAYERS - 1) != 0
+    }
+
+    /// 矩阵是对称的，同时修改两行
+    pub fn set_collides(&mut self, a: u32, b: u32, collides: bool) {
+        let (a, b) = ((a as usize).min(MAX_LAYERS - 1), (b as usize).min(MAX_LAYERS - 1));
+        if collides {
+            self.matrix[a] |= 1 << b;
+            self.matrix[b] |= 1 << a;
+        } else {
+            self.matrix[a] &= !(1 << b);
+            self.matrix[b] &= !(1 << a);
+        }
+    }
+
+    /// 与 layer 碰撞的所有层，可直接用作查询的 mask
+    pub fn mask(&self, layer: u32) -> u32 {
+        self.matrix[(layer as usize).min(MAX_LAYERS - 1)]
+    }
+}
+
+#[test]
+fn test_layers_filter_contacts() {
+    use crate::physics::collision::solve::{PhysicsState, crate_object, floor_object, step_objects};
+
+    let mut layers = CollisionLayers::default();
+    assert!(!layers.collides(CollisionLayers::DEBRIS, CollisionLayers::DEBRIS));
+    assert!(!layers.collides(CollisionLayers::STATIC, CollisionLayers::TRIGGER));
+    assert!(layers.collides(CollisionLayers::DEBRIS, CollisionLayers::DEFAULT));
+    let ghost = layers.add("幽灵").unwrap();
+    layers.set_collides(ghost, CollisionLayers::DEFAULT, false);
+    assert_eq!(layers.mask(CollisionLayers::DEFAULT) & 1 << ghost, 0);
+
+    // 盒子落向地面，按层决定是否被接住
+    let falls_through = |layer: u32| {
+        let mut floor = floor_object(10.0);
+        let mut body = crate_object("box", 0.5, 0.5, true);
+        body.transform.position.y = 0.5;
+        body.physics.layer = layer;
+        let mut state = PhysicsState { layers: layers.clone(), ..Default::default() };
+        for _ in 0..60 {
+            step_objects(&mut [&mut floor, &mut body], &mut state, 1.0 / 60.0);
+        }
+        body.transform.position.y < -1.0
+    };
+    assert!(!falls_through(CollisionLayers::DEBRIS));
+    assert!(falls_through(ghost));
+}
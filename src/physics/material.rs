@@ -0,0 +1,142 @@
+/// 两个物体的系数如何合成，声明顺序即优先级
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum CombineRule {
+    #[default]
+    Average,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl CombineRule {
+    pub const ALL: [CombineRule; 4] = [
+        CombineRule::Average,
+        CombineRule::Min,
+        CombineRule::Multiply,
+        CombineRule::Max,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CombineRule::Average => "平均",
+            CombineRule::Min => "最小",
+            CombineRule::Multiply => "相乘",
+            CombineRule::Max => "最大",
+        }
+    }
+
+    /// 两物体规则不同时取优先级高的：最大 > 相乘 > 最小 > 平均
+    pub fn resolve(a: CombineRule, b: CombineRule) -> CombineRule {
+        a.max(b)
+    }
+
+    pub fn combine(&self, a: f32, b: f32) -> f32 {
+        match self {
+            CombineRule::Average => (a + b) * 0.5,
+            CombineRule::Min => a.min(b),
+            CombineRule::Multiply => a * b,
+            CombineRule::Max => a.max(b),
+        }
+    }
+}
+
+/// 物理材质：摩擦、弹性与密度，可在多个物体间复用
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicsMaterial {
+    /// 静止接触时的摩擦系数
+    pub static_friction: f32,
+    /// 相对滑动时的摩擦系数
+    pub dynamic_friction: f32,
+    pub restitution: f32,
+    /// 密度 (kg/m³)，按体积计算质量时使用
+    pub density: f32,
+    pub friction_combine: CombineRule,
+    pub restitution_combine: CombineRule,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl PhysicsMaterial {
+    pub const DEFAULT: Self = Self {
+        static_friction: 0.01,
+        dynamic_friction: 0.01,
+        restitution: 0.5,
+        density: 1000.0,
+        friction_combine: CombineRule::Average,
+        restitution_combine: CombineRule::Average,
+    };
+
+    /// 冰面：几乎没有摩擦，且压过对方的摩擦
+    pub const ICE: Self = Self {
+        static_friction: 0.05,
+        dynamic_friction: 0.02,
+        restitution: 0.1,
+        density: 917.0,
+        friction_combine: CombineRule::Min,
+        restitution_combine: CombineRule::Average,
+    };
+
+    /// 橡胶：高摩擦，弹性取两者中较大的
+    pub const RUBBER: Self = Self {
+        static_friction: 1.0,
+        dynamic_friction: 0.8,
+        restitution: 0.9,
+        density: 1100.0,
+        friction_combine: CombineRule::Average,
+        restitution_combine: CombineRule::Max,
+    };
+
+    pub const WOOD: Self = Self {
+        static_friction: 0.5,
+        dynamic_friction: 0.4,
+        restitution: 0.3,
+        density: 700.0,
+        friction_combine: CombineRule::Average,
+        restitution_combine: CombineRule::Average,
+    };
+
+    pub const METAL: Self = Self {
+        static_friction: 0.6,
+        dynamic_friction: 0.4,
+        restitution: 0.2,
+        density: 7850.0,
+        friction_combine: CombineRule::Average,
+        restitution_combine: CombineRule::Average,
+    };
+
+    pub const PRESETS: [(&'static str, PhysicsMaterial); 5] = [
+        ("默认", Self::DEFAULT),
+        ("冰面", Self::ICE),
+        ("橡胶", Self::RUBBER),
+        ("木头", Self::WOOD),
+        ("金属", Self::METAL),
+    ];
+
+    /// 两种材质接触时的 (静摩擦, 动摩擦, 弹性系数)
+    pub fn combine(&self, other: &PhysicsMaterial) -> (f32, f32, f32) {
+        let friction = CombineRule::resolve(self.friction_combine, other.friction_combine);
+        let restitution = CombineRule::resolve(self.restitution_combine, other.restitution_combine);
+        (
+            friction.combine(self.static_friction, other.static_friction),
+            friction.combine(self.dynamic_friction, other.dynamic_friction),
+            restitution.combine(self.restitution, other.restitution),
+        )
+    }
+}
+
+#[test]
+fn test_material_combine_rules() {
+    let (s, d, e) = PhysicsMaterial::WOOD.combine(&PhysicsMaterial::METAL);
+    assert!((s - 0.55).abs() < 1e-6 && (d - 0.4).abs() < 1e-6 && (e - 0.25).abs() < 1e-6);
+    // 冰面的 Min 规则优先于木头的 Average
+    let (s, d, _) = PhysicsMaterial::WOOD.combine(&PhysicsMaterial::ICE);
+    assert_eq!((s, d), (0.05, 0.02));
+    // 橡胶的 Max 规则让任何地面都能弹起来
+    let (_, _, e) = PhysicsMaterial::ICE.combine(&PhysicsMaterial::RUBBER);
+    assert_eq!(e, 0.9);
+    assert_eq!(CombineRule::resolve(CombineRule::Multiply, CombineRule::Min), CombineRule::Multiply);
+}
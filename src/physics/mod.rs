@@ -1,3 +1,12 @@
 pub mod collision;
 pub mod rigid;
-pub mod boundingbox;
\ No newline at end of file
+pub mod boundingbox;
+pub mod collider;
+pub mod character;
+pub mod material;
+pub mod joint;
+pub mod layer;
+pub mod event;
+pub mod sleep;
+pub mod query;
+pub mod timestep;
@@ -0,0 +1,221 @@
+use crate::{
+    physics::{
+        boundingbox::{AABB, BoundingVolume, OBB, SphereBox},
+        collision::{board::collide, broad::{BroadPhase, BroadProxy}, ccd::{cast_volume, slab}, narrow::ConvexShape},
+    },
+    scene::world::BodyHandle,
+};
+
+use glam::f32::{Quat, Vec3};
+
+/// 场景查询的过滤条件：mask 的第 i 位表示是否检测第 i 层，exclude 用于跳过发起者自身，
+/// 触发器默认不参与查询
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryFilter {
+    pub mask: u32,
+    pub exclude: Option<BodyHandle>,
+    pub sensors: bool,
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self { mask: u32::MAX, exclude: None, sensors: false }
+    }
+}
+
+impl QueryFilter {
+    pub fn excluding(handle: BodyHandle) -> Self {
+        Self { exclude: Some(handle), ..Self::default() }
+    }
+
+    fn accepts(&self, body: &QueryBody) -> bool {
+        self.exclude != Some(body.handle) && self.mask & (1 << body.layer.min(31)) != 0 && (self.sensors || !body.sensor)
+    }
+}
+
+/// 一次命中：物体、命中点、表面法线 (朝向查询的来向) 与沿方向移动的距离
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryHit {
+    pub handle: BodyHandle,
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+/// 参与查询的物体
+pub struct QueryBody {
+    pub handle: BodyHandle,
+    pub volume: BoundingVolume,
+    pub layer: u32,
+    pub is_static: bool,
+    pub sensor: bool,
+}
+
+/// 一次查询用到的场景快照，候选物体由宽相给出
+pub struct SceneQuery<'a> {
+    bodies: Vec<QueryBody>,
+    proxies: Vec<BroadProxy>,
+    broad_phase: &'a BroadPhase,
+}
+
+impl<'a> SceneQuery<'a> {
+    pub fn new(bodies: Vec<QueryBody>, broad_phase: &'a BroadPhase) -> Self {
+        let proxies = bodies
+            .iter()
+            .map(|b| BroadProxy { handle: b.handle, aabb: b.volume.bounds(), is_static: b.is_static })
+            .collect();
+        Self { bodies, proxies, broad_phase }
+    }
+
+    /// 凸体沿 direction 移动至多 max_distance，返回所有命中，按距离排序
+    pub fn cast_all(
+        &self,
+        shape: &impl ConvexShape,
+        direction: Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Vec<QueryHit> {
+        let direction = direction.normalize_or_zero();
+        let motion = direction * max_distance;
+        // 宽相：按形状的半尺寸扩张包围盒后与射线求交
+        let mut bounds = AABB::default();
+        for d in [Vec3::X, Vec3::Y, Vec3::Z, -Vec3::X, -Vec3::Y, -Vec3::Z] {
+            bounds.union_point_array(shape.support(d).to_array());
+        }
+        let (origin, half) = (bounds.center(), bounds.get_half_extents());
+        let candidates = self.broad_phase.query(&self.proxies, |b| slab(origin, motion, b.min - half, b.max + half).is_some());
+
+        let mut hits: Vec<QueryHit> = candidates
+            .into_iter()
+            .filter(|&i| filter.accepts(&self.bodies[i]))
+            .filter_map(|i| {
+                let (t, point, normal) = cast_volume(shape, motion, &self.bodies[i].volume)?;
+                Some(QueryHit {
+                    handle: self.bodies[i].handle,
+                    point,
+                    // 开始时就重叠，没有确定的表面，法线取来向
+                    normal: if normal == Vec3::ZERO { -direction } else { normal },
+                    distance: t * max_distance,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    pub fn cast(&self, shape: &impl ConvexShape, direction: Vec3, max_distance: f32, filter: &QueryFilter) -> Option<QueryHit> {
+        self.cast_all(shape, direction, max_distance, filter).into_iter().next()
+    }
+
+    /// 只做宽相：包围盒与射线段相交的物体，由调用者再做精确检测 (如网格三角形)
+    pub fn ray_candidates(&self, origin: Vec3, direction: Vec3, max_distance: f32, filter: &QueryFilter) -> Vec<BodyHandle> {
+        let motion = direction.normalize_or_zero() * max_distance;
+        self.broad_phase
+            .query(&self.proxies, |b| slab(origin, motion, b.min, b.max).is_some())
+            .into_iter()
+            .filter(|&i| filter.accepts(&self.bodies[i]))
+            .map(|i| self.bodies[i].handle)
+            .collect()
+    }
+
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32, filter: &QueryFilter) -> Option<QueryHit> {
+        self.cast(&SphereBox::new(origin, 0.0), direction, max_distance, filter)
+    }
+
+    pub fn raycast_all(&self, origin: Vec3, direction: Vec3, max_distance: f32, filter: &QueryFilter) -> Vec<QueryHit> {
+        self.cast_all(&SphereBox::new(origin, 0.0), direction, max_distance, filter)
+    }
+
+    pub fn sphere_cast(
+        &self,
+        center: Vec3,
+        radius: f32,
+        direction: Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<QueryHit> {
+        self.cast(&SphereBox::new(center, radius), direction, max_distance, filter)
+    }
+
+    pub fn box_cast(
+        &self,
+        center: Vec3,
+        half_extents: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<QueryHit> {
+        let obb = OBB { center, half_extents, asxes: [rotation * Vec3::X, rotation * Vec3::Y, rotation * Vec3::Z] };
+        self.cast(&obb, direction, max_distance, filter)
+    }
+
+    /// 与 volume 重叠的物体
+    pub fn overlap(&self, volume: &BoundingVolume, filter: &QueryFilter) -> Vec<BodyHandle> {
+        let bounds = volume.bounds();
+        self.broad_phase
+            .query(&self.proxies, |b| b.overlaps(&bounds))
+            .into_iter()
+            .filter(|&i| filter.accepts(&self.bodies[i]) && collide(volume, &self.bodies[i].volume).is_some())
+            .map(|i| self.bodies[i].handle)
+            .collect()
+    }
+}
+
+#[test]
+fn test_scene_queries() {
+    use crate::core::math::transform::Transform;
+
+    let block = |center: Vec3, half: Vec3| {
+        let mut transform = Transform::default();
+        transform.position = center;
+        BoundingVolume::OBB(OBB::from_transform(&AABB::from_vec(-half, half), &transform))
+    };
+    // 一面墙挡在两个目标球前面，第二个球位于另一层
+    let bodies = vec![
+        QueryBody { handle: BodyHandle::Object(0), volume: block(Vec3::new(0.0, 0.0, -3.0), Vec3::new(2.0, 2.0, 0.1)), layer: 0, is_static: true, sensor: false },
+        QueryBody { handle: BodyHandle::Object(1), volume: BoundingVolume::Sphere(SphereBox::new(Vec3::new(0.0, 0.0, -6.0), 0.5)), layer: 0, is_static: false, sensor: false },
+        QueryBody { handle: BodyHandle::Object(2), volume: BoundingVolume::Sphere(SphereBox::new(Vec3::new(3.0, 0.0, -6.0), 0.5)), layer: 1, is_static: false, sensor: false },
+    ];
+    // 先建好静态树，查询走树而不是逐个检测
+    let mut broad_phase = BroadPhase::default();
+    let proxies: Vec<BroadProxy> = bodies
+        .iter()
+        .map(|b| BroadProxy { handle: b.handle, aabb: b.volume.bounds(), is_static: b.is_static })
+        .collect();
+    broad_phase.find_pairs(&proxies);
+    let query = SceneQuery::new(bodies, &broad_phase);
+    let all = QueryFilter::default();
+
+    // 墙先被击中，法线朝向射线来向
+    let hit = query.raycast(Vec3::ZERO, -Vec3::Z, 100.0, &all).unwrap();
+    assert_eq!(hit.handle, BodyHandle::Object(0));
+    assert!((hit.distance - 2.9).abs() < 0.01 && hit.normal.dot(Vec3::Z) > 0.99, "{hit:?}");
+    assert!(hit.point.distance(Vec3::new(0.0, 0.0, -2.9)) < 0.01, "{hit:?}");
+    let hits = query.raycast_all(Vec3::ZERO, -Vec3::Z, 100.0, &all);
+    assert_eq!(hits.iter().map(|h| h.handle).collect::<Vec<_>>(), [BodyHandle::Object(0), BodyHandle::Object(1)]);
+    assert!((hits[1].distance - 5.5).abs() < 0.01, "{:?}", hits[1]);
+    // 射程不够或方向相反都打不到
+    assert!(query.raycast(Vec3::ZERO, -Vec3::Z, 2.0, &all).is_none());
+    assert!(query.raycast(Vec3::ZERO, Vec3::Z, 100.0, &all).is_none());
+
+    // 层过滤：只检测第 1 层
+    let layer1 = QueryFilter { mask: 1 << 1, ..QueryFilter::default() };
+    let origin = Vec3::new(3.0, 0.0, 0.0);
+    assert_eq!(query.raycast(origin, -Vec3::Z, 100.0, &layer1).unwrap().handle, BodyHandle::Object(2));
+    assert!(query.raycast(Vec3::ZERO, -Vec3::Z, 100.0, &layer1).is_none());
+
+    // 球扫掠比射线早半径的距离碰到墙；从墙边擦过的射线打不到，半径 0.3 的球能碰到
+    let hit = query.sphere_cast(Vec3::ZERO, 0.3, -Vec3::Z, 100.0, &all).unwrap();
+    assert!((hit.distance - 2.6).abs() < 0.01, "{hit:?}");
+    let edge = Vec3::new(2.2, 0.0, 0.0);
+    assert!(query.raycast(edge, -Vec3::Z, 4.0, &all).is_none());
+    assert_eq!(query.sphere_cast(edge, 0.3, -Vec3::Z, 4.0, &all).unwrap().handle, BodyHandle::Object(0));
+    let hit = query.box_cast(Vec3::ZERO, Vec3::splat(0.2), Quat::IDENTITY, -Vec3::Z, 100.0, &all).unwrap();
+    assert!((hit.distance - 2.7).abs() < 0.01, "{hit:?}");
+
+    // 重叠查询
+    let probe = BoundingVolume::Sphere(SphereBox::new(Vec3::new(0.0, 0.0, -5.0), 1.2));
+    assert_eq!(query.overlap(&probe, &all), [BodyHandle::Object(1)]);
+    assert_eq!(query.overlap(&probe, &QueryFilter::excluding(BodyHandle::Object(1))), []);
+}
@@ -1,13 +1,28 @@
 use crate::{
-    core::math::transform::Transform, physics::boundingbox::BoundingVolume, scene::world::BodyType
+    core::math::transform::Transform, physics::{boundingbox::BoundingVolume, material::PhysicsMaterial, sleep::SleepState}, scene::world::BodyType
 };
 
-use glam::f32::Vec3;
+use glam::f32::{Mat3, Quat, Vec3};
 
+/// 接触点：位置取两物体表面的中点
 #[derive(Clone, Copy, Debug)]
+pub struct ContactPoint {
+    pub position: Vec3,
+    pub penetration: f32,
+}
+
+/// 接触流形：法线从 a 指向 b，penetration 为最大穿透深度
+#[derive(Clone, Debug)]
 pub struct Contact {
     pub normal: glam::f32::Vec3,
     pub penetration: f32,
+    pub points: Vec<ContactPoint>,
+}
+
+impl Contact {
+    pub fn new(normal: Vec3, penetration: f32) -> Self {
+        Self { normal, penetration, points: Vec::new() }
+    }
 }
 
 pub trait RigidBody {
@@ -17,37 +32,127 @@ pub trait RigidBody {
     fn velocity(&self) -> [f32; 3];
     fn velocity_mut(&mut self) -> &mut [f32; 3];
 
-    fn body_type(&self) -> BodyType;
+    fn angular_velocity(&self) -> [f32; 3];
+    fn angular_velocity_mut(&mut self) -> &mut [f32; 3];
 
-    fn restitution(&self) -> f32;
+    fn body_type(&self) -> BodyType;
 
     fn bounding_volume(&self) -> BoundingVolume;
 
     fn mass(&self) -> f32;
 
-    fn inv_mass(&self) -> f32 { if self.mass().abs() < 0.0001 { f32::INFINITY } else { 1.0 / self.mass() }  }
+    fn inv_mass(&self) -> f32 {
+        if !self.is_dynamic() { return 0.0; }
+        if self.mass().abs() < 0.0001 { f32::INFINITY } else { 1.0 / self.mass() }
+    }
+
+    /// 世界空间下的逆惯性张量，默认不能转动
+    fn inv_inertia(&self) -> Mat3 { Mat3::ZERO }
+
+    fn center_of_mass(&self) -> Vec3 { self.transform().position }
 
     fn force(&self) -> [f32; 3];
 
     fn force_mut(&mut self) -> &mut [f32; 3];
 
-    fn friction(&self) -> f32;
+    fn torque(&self) -> [f32; 3];
+
+    fn torque_mut(&mut self) -> &mut [f32; 3];
+
+    fn material(&self) -> PhysicsMaterial;
+
+    /// 是否对该物体做连续碰撞检测，用于高速物体
+    fn continuous(&self) -> bool { false }
+
+    /// 所在的碰撞层
+    fn layer(&self) -> u32 { 0 }
+
+    /// 触发器只检测重叠，不参与碰撞响应
+    fn is_sensor(&self) -> bool { false }
+
+    fn sleep_state(&self) -> SleepState;
+
+    fn sleep_state_mut(&mut self) -> &mut SleepState;
 
-    fn stimulate(&mut self, dt: f32) {
+    fn is_asleep(&self) -> bool {
+        self.sleep_state().asleep
+    }
+
+    fn wake(&mut self) {
+        self.sleep_state_mut().wake();
+    }
+
+    /// 进入休眠，速度清零
+    fn fall_asleep(&mut self) {
+        self.sleep_state_mut().asleep = true;
+        *self.velocity_mut() = [0.0; 3];
+        *self.angular_velocity_mut() = [0.0; 3];
+    }
+
+    /// 刚体上某点的速度
+    fn velocity_at(&self, point: Vec3) -> Vec3 {
+        let omega = Vec3::from_array(self.angular_velocity());
+        Vec3::from_array(self.velocity()) + omega.cross(point - self.center_of_mass())
+    }
+
+    /// 在 point 处施加力，偏离质心的部分产生力矩
+    fn apply_force_at(&mut self, force: Vec3, point: Vec3) {
+        let torque = (point - self.center_of_mass()).cross(force);
+        let f = self.force_mut();
+        *f = (Vec3::from_array(*f) + force).to_array();
+        let t = self.torque_mut();
+        *t = (Vec3::from_array(*t) + torque).to_array();
+    }
+
+    /// 在 point 处施加冲量，同时改变线速度与角速度
+    fn apply_impulse(&mut self, impulse: Vec3, point: Vec3) {
         if !self.is_dynamic() { return; }
-        let mass = self.mass();
-        let force = Vec3::from_array(self.force());
-        let mut vel = Vec3::from_array(self.velocity());
-        // 半隐式 Euler：先更新速度，再更新位置
-        let acceleration = force / mass;
-        vel += acceleration * dt;  // 先更新速度
-        self.transform_mut().position += vel * dt; // 再更新位置
-        // 写回速度
-        let velocity = self.velocity_mut();
-        velocity[0] = vel.x;
-        velocity[1] = vel.y;
-        velocity[2] = vel.z;
+        let dv = impulse * self.inv_mass();
+        let dw = self.inv_inertia() * (point - self.center_of_mass()).cross(impulse);
+        let v = self.velocity_mut();
+        *v = (Vec3::from_array(*v) + dv).to_array();
+        let w = self.angular_velocity_mut();
+        *w = (Vec3::from_array(*w) + dw).to_array();
+    }
+
+    /// 半隐式 Euler 的第一步：由力和力矩更新速度
+    fn integrate_velocity(&mut self, dt: f32) {
+        if !self.is_dynamic() { return; }
+        let acceleration = Vec3::from_array(self.force()) * self.inv_mass();
+        let vel = Vec3::from_array(self.velocity()) + acceleration * dt;
+        *self.velocity_mut() = vel.to_array();
         self.force_mut().fill(0.0);
+
+        let torque = Vec3::from_array(std::mem::take(self.torque_mut()));
+        let omega = Vec3::from_array(self.angular_velocity()) + self.inv_inertia() * torque * dt;
+        *self.angular_velocity_mut() = omega.to_array();
+    }
+
+    /// 半隐式 Euler 的第二步：按给定速度移动位置、绕质心旋转
+    fn integrate_position(&mut self, velocity: Vec3, angular_velocity: Vec3, dt: f32) {
+        if self.is_static() { return; }
+        let com = self.center_of_mass();
+        let transform = self.transform_mut();
+        transform.position += velocity * dt;
+        if angular_velocity != Vec3::ZERO {
+            // 绕质心旋转，需要同时修正位置
+            let spin = Quat::from_scaled_axis(angular_velocity * dt);
+            let offset = com - (transform.position - velocity * dt);
+            transform.position += offset - spin * offset;
+            transform.rotation = (spin * transform.rotation).normalize();
+        }
+    }
+
+    /// 运动学物体：设置速度，使积分 dt 之后恰好到达 target
+    fn set_kinematic_target(&mut self, target: &Transform, dt: f32) {
+        let transform = self.transform();
+        let spin = target.rotation * transform.rotation.inverse();
+        let spin = if spin.w < 0.0 { -spin } else { spin };
+        // 速度定义在质心上，绕质心旋转会带动 transform.position
+        let offset = self.center_of_mass() - transform.position;
+        let velocity = (target.position - transform.position - offset + spin * offset) / dt;
+        *self.velocity_mut() = velocity.to_array();
+        *self.angular_velocity_mut() = (spin.to_scaled_axis() / dt).to_array();
     }
 
     fn is_dynamic(&self) -> bool {
@@ -0,0 +1,83 @@
+use glam::f32::Vec3;
+
+/// 速度低于阈值连续 SLEEP_FRAMES 步后可以休眠
+pub const SLEEP_LINEAR_SPEED: f32 = 0.05;
+pub const SLEEP_ANGULAR_SPEED: f32 = 0.05;
+pub const SLEEP_FRAMES: u32 = 30;
+
+/// 动态物体的休眠状态：休眠时不积分也不做窄相检测，直到被碰撞或外力唤醒
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SleepState {
+    pub asleep: bool,
+    /// 连续静止的步数
+    pub still_frames: u32,
+}
+
+impl SleepState {
+    pub fn wake(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 按本步的速度累计静止步数，返回是否已经可以休眠
+    pub fn update(&mut self, velocity: Vec3, angular_velocity: Vec3) -> bool {
+        if velocity.length() < SLEEP_LINEAR_SPEED && angular_velocity.length() < SLEEP_ANGULAR_SPEED {
+            self.still_frames = self.still_frames.saturating_add(1);
+        } else {
+            self.still_frames = 0;
+        }
+        self.still_frames >= SLEEP_FRAMES
+    }
+}
+
+#[test]
+fn test_islands_sleep_and_wake_together() {
+    use crate::physics::{collision::solve::{PhysicsState, crate_object, step_objects}, rigid::RigidBody};
+    use crate::scene::world::{BodyType, GameObject};
+
+    let cube = |width: f32, y: f32, dynamic: bool| {
+        let mut obj = crate_object("cube", width, 0.5, dynamic);
+        obj.transform.position.y = y;
+        obj.physics.material.restitution = 0.0;
+        obj
+    };
+    // 地面上叠着两个盒子，第三个盒子停在远处的高空，暂不参与
+    let mut objects = [cube(10.0, -0.25, false), cube(0.5, 0.25, true), cube(0.5, 0.75, true), cube(0.5, 20.0, false)];
+    let mut state = PhysicsState::default();
+    let step = |objects: &mut [GameObject; 4], state: &mut PhysicsState| step_objects(&mut objects.each_mut(), state, 1.0 / 60.0);
+
+    // 叠在一起的两个盒子在同一步进入休眠，休眠后位置不再变化
+    let mut asleep_at = [None; 2];
+    for frame in 0..180 {
+        step(&mut objects, &mut state);
+        for (k, obj) in objects[1..3].iter().enumerate() {
+            if obj.is_asleep() && asleep_at[k].is_none() {
+                asleep_at[k] = Some(frame);
+            }
+        }
+    }
+    assert!(asleep_at[0].is_some() && asleep_at[0] == asleep_at[1], "{asleep_at:?}");
+    let rest = objects[2].transform.position;
+    step(&mut objects, &mut state);
+    assert_eq!(objects[2].transform.position, rest);
+
+    // 落下的盒子砸到上面的盒子，整叠都被唤醒
+    objects[3].set_body_type(BodyType::Dynamic);
+    objects[3].transform.position.y = 1.5;
+    let mut woke = [false; 2];
+    for _ in 0..30 {
+        step(&mut objects, &mut state);
+        woke[0] |= !objects[1].is_asleep();
+        woke[1] |= !objects[2].is_asleep();
+    }
+    assert_eq!(woke, [true, true]);
+    assert!(objects[3].transform.position.y > 1.0, "y = {}", objects[3].transform.position.y);
+
+    // 休眠后受到外力被唤醒
+    for _ in 0..180 {
+        step(&mut objects, &mut state);
+    }
+    assert!(objects[1..].iter().all(|obj| obj.is_asleep()));
+    objects[3].physics.force = [50.0, 0.0, 0.0];
+    step(&mut objects, &mut state);
+    assert!(!objects[3].is_asleep() && objects[3].physics.velocity[0] > 0.0);
+}
@@ -0,0 +1,155 @@
+use crate::core::math::transform::Transform;
+
+/// 固定步长累加器：把不定长的帧时间切成等长的物理步
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    /// 每秒物理步数
+    pub rate: f32,
+    /// 单帧最多执行的物理步数，超出的时间直接丢弃
+    pub max_substeps: u32,
+    accumulator: f32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self {
+            rate: 60.0,
+            max_substeps: 8,
+            accumulator: 0.0,
+        }
+    }
+}
+
+impl FixedTimestep {
+    /// 每一步的时长
+    pub fn dt(&self) -> f32 {
+        1.0 / self.rate.max(1.0)
+    }
+
+    /// 累加一帧的时间，返回本帧需要执行的物理步数
+    pub fn advance(&mut self, frame_dt: f32) -> u32 {
+        let dt = self.dt();
+        self.accumulator += frame_dt.max(0.0);
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < self.max_substeps {
+            self.accumulator -= dt;
+            steps += 1;
+        }
+        // 追不上时丢弃积压的时间，避免越卡越慢
+        if steps == self.max_substeps {
+            self.accumulator = self.accumulator.min(dt);
+        }
+        steps
+    }
+
+    /// 剩余时间占一步的比例，用于在前后两个物理状态之间插值
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.dt()).clamp(0.0, 1.0)
+    }
+}
+
+/// 渲染插值状态：物体的 transform 在两帧之间显示为插值结果，
+/// 下一次物理步之前再恢复成真实的物理状态
+#[derive(Clone, Debug, Default)]
+pub struct Interpolation {
+    /// 最近一个物理步之前的状态
+    previous: Option<Transform>,
+    /// 上一帧显示时的 (物理状态, 插值结果)
+    shown: Option<(Transform, Transform)>,
+}
+
+impl Interpolation {
+    /// 恢复物理状态；帧间被外部修改过的分量保留修改，且不再插值
+    pub fn restore(&mut self, transform: &mut Transform) {
+        let Some((physics, shown)) = self.shown.take() else { return };
+        let previous = self.previous.get_or_insert_with(|| physics.clone());
+        if transform.position == shown.position {
+            transform.position = physics.position;
+        } else {
+            previous.position = transform.position;
+        }
+        if transform.rotation == shown.rotation {
+            transform.rotation = physics.rotation;
+        } else {
+            previous.rotation = transform.rotation;
+        }
+        if transform.scale == shown.scale {
+            transform.scale = physics.scale;
+        } else {
+            previous.scale = transform.scale;
+        }
+    }
+
+    /// 物理步之前记录当前状态
+    pub fn record(&mut self, transform: &Transform) {
+        self.previous = Some(transform.clone());
+    }
+
+    /// 把 transform 替换为上一状态与当前状态之间的插值
+    pub fn apply(&mut self, transform: &mut Transform, alpha: f32) {
+        let Some(previous) = &self.previous else { return };
+        let shown = previous.lerp(transform, alpha);
+        self.shown = Some((std::mem::replace(transform, shown.clone()), shown));
+    }
+}
+
+#[test]
+fn test_fixed_timestep_is_frame_rate_independent() {
+    use crate::physics::collision::solve::{PhysicsState, crate_object, floor_object, step_objects};
+
+    // 相近的总时长，分别按 25 帧和 144 帧喂给累加器
+    let simulate = |frame_dt: f32, frames: usize| {
+        let mut floor = floor_object(10.0);
+        let mut body = crate_object("box", 1.0, 1.0, true);
+        body.transform.position.y = 3.0;
+        body.transform.rotation = glam::Quat::from_rotation_z(0.4);
+        let mut timestep = FixedTimestep::default();
+        let mut state = PhysicsState::default();
+        let mut steps = 0;
+        for _ in 0..frames {
+            for _ in 0..timestep.advance(frame_dt) {
+                step_objects(&mut [&mut floor, &mut body], &mut state, timestep.dt());
+                steps += 1;
+            }
+        }
+        (steps, body.transform)
+    };
+    let (steps_a, a) = simulate(1.0 / 25.0, 51);
+    let (steps_b, b) = simulate(1.0 / 144.0, 294);
+    assert_eq!(steps_a, steps_b);
+    assert_eq!(a, b);
+
+    // 卡顿的一帧最多执行 max_substeps 步
+    let mut timestep = FixedTimestep::default();
+    assert_eq!(timestep.advance(5.0), timestep.max_substeps);
+    assert!(timestep.alpha() <= 1.0);
+}
+
+#[test]
+fn test_world_step_interpolates_between_physics_states() {
+    use crate::physics::collision::solve::crate_object;
+    use crate::scene::world::World;
+    use glam::Vec3;
+
+    // 无重力下以 6 m/s 匀速运动，每个物理步前进 0.1
+    let mut world = World::new();
+    world.gravity = [0.0; 3];
+    let mut body = crate_object("box", 1.0, 1.0, true);
+    body.physics.velocity = [6.0, 0.0, 0.0];
+    world.add_object(body);
+    let dt = world.timestep.dt();
+    let x = |world: &World| world.objects[0].transform.position.x;
+
+    // 一步半：执行一步，显示为前后两个状态的中点
+    world.step(dt * 1.5);
+    assert!((x(&world) - 0.05).abs() < 1e-3, "x = {}", x(&world));
+    // 下一帧从物理状态 0.1 而不是显示的 0.05 继续
+    world.step(dt);
+    assert!((x(&world) - 0.15).abs() < 1e-3, "x = {}", x(&world));
+
+    // 帧间直接修改的位置被保留，并从修改处继续运动
+    world.objects[0].transform.position = Vec3::new(5.0, 2.0, 0.0);
+    world.step(dt);
+    let position = world.objects[0].transform.position;
+    assert!(position.distance(Vec3::new(5.05, 2.0, 0.0)) < 1e-3, "position = {position}");
+}
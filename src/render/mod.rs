@@ -1,4 +1,5 @@
 pub mod scene_renderer;
 pub use scene_renderer::SceneRenderer;
+pub mod normal_map;
 pub mod pass;
 pub mod shader;
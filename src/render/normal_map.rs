@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+use glium::glutin::surface::WindowSurface;
+
+use crate::core::material::NormalMap;
+
+/// 程序化法线贴图的分辨率
+const BUILTIN_SIZE: usize = 256;
+
+fn smoothstep(e0: f32, e1: f32, x: f32) -> f32 {
+    let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// 可平铺的值噪声
+fn hash_noise(x: i32, y: i32, period: i32) -> f32 {
+    let (x, y) = (x.rem_euclid(period) as u32, y.rem_euclid(period) as u32);
+    let mut h = x.wrapping_mul(374761393) ^ y.wrapping_mul(668265263);
+    h = (h ^ (h >> 13)).wrapping_mul(1274126177);
+    (h ^ (h >> 16)) as f32 / u32::MAX as f32
+}
+
+fn value_noise(u: f32, v: f32, period: i32) -> f32 {
+    let (x, y) = (u * period as f32, v * period as f32);
+    let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let (sx, sy) = (fx * fx * (3.0 - 2.0 * fx), fy * fy * (3.0 - 2.0 * fy));
+    let a = hash_noise(x0, y0, period) + (hash_noise(x0 + 1, y0, period) - hash_noise(x0, y0, period)) * sx;
+    let b = hash_noise(x0, y0 + 1, period)
+        + (hash_noise(x0 + 1, y0 + 1, period) - hash_noise(x0, y0 + 1, period)) * sx;
+    a + (b - a) * sy
+}
+
+/// 内置贴图的高度场，u、v ∈ [0, 1) 且在边界处连续
+fn height(kind: &NormalMap, u: f32, v: f32) -> f32 {
+    match kind {
+        NormalMap::Bricks => {
+            // 4 行砖，奇数行错开半块
+            let rows = 4.0;
+            let row = (v * rows).floor();
+            let y = v * rows - row;
+            let x = (u * 2.0 + if row as i32 % 2 == 1 { 0.5 } else { 0.0 }).fract();
+            // 砖块宽 1/2、高 1/4，灰缝在两个方向上宽度一致
+            let mortar = 0.02;
+            let edge = smoothstep(0.0, mortar * 2.0, x.min(1.0 - x))
+                .min(smoothstep(0.0, mortar * 4.0, y.min(1.0 - y)));
+            edge * (0.9 + 0.1 * value_noise(u, v, 32))
+        }
+        NormalMap::Tiles => {
+            let n = 4.0;
+            let (x, y) = ((u * n).fract(), (v * n).fract());
+            let groove = 0.03;
+            smoothstep(0.0, groove, x.min(1.0 - x)).min(smoothstep(0.0, groove, y.min(1.0 - y)))
+        }
+        NormalMap::WoodGrain => {
+            // 沿 u 方向的年轮，用低频噪声扭曲
+            let warp = value_noise(u, v, 4) * 0.8 + value_noise(u, v, 16) * 0.1;
+            let rings = (0.5 + 0.5 * ((v * 6.0 + warp) * TAU).sin()).powf(0.6);
+            rings * 0.8 + value_noise(u, v * 8.0 % 1.0, 64) * 0.2
+        }
+        NormalMap::File(_) => 0.0,
+    }
+}
+
+/// 由高度场生成切线空间法线贴图 (RGBA8，第一行对应 v = 0)
+pub fn generate(kind: &NormalMap, size: usize, bump: f32) -> Vec<u8> {
+    let heights: Vec<f32> = (0..size * size)
+        .map(|i| height(kind, (i % size) as f32 / size as f32, (i / size) as f32 / size as f32))
+        .collect();
+    let at = |x: usize, y: usize| heights[(y % size) * size + x % size];
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = at(x + 1, y) - at(x + size - 1, y);
+            let dy = at(x, y + 1) - at(x, y + size - 1);
+            let n = glam::vec3(-dx * bump, -dy * bump, 1.0).normalize();
+            let encode = |c: f32| ((c * 0.5 + 0.5) * 255.0).round() as u8;
+            data.extend([encode(n.x), encode(n.y), encode(n.z), 255]);
+        }
+    }
+    data
+}
+
+/// 按来源缓存法线贴图纹理，文件只加载一次
+#[derive(Default)]
+pub struct NormalMapCache {
+    textures: HashMap<String, Option<glium::texture::Texture2d>>,
+}
+
+impl NormalMapCache {
+    pub fn get(
+        &mut self,
+        display: &glium::Display<WindowSurface>,
+        kind: &NormalMap,
+    ) -> Option<&glium::texture::Texture2d> {
+        let key = format!("{:?}", kind);
+        self.textures
+            .entry(key)
+            .or_insert_with(|| match kind {
+                NormalMap::File(path) => match image::open(path) {
+                    Ok(img) => {
+                        let img = img.flipv().to_rgba8();
+                        let dims = img.dimensions();
+                        let raw = glium::texture::RawImage2d::from_raw_rgba_reversed(&img.into_raw(), dims);
+                        glium::texture::Texture2d::new(display, raw).ok()
+                    }
+                    Err(e) => {
+                        println!("法线贴图加载失败: {}", e);
+                        None
+                    }
+                },
+                builtin => {
+                    let data = generate(builtin, BUILTIN_SIZE, BUILTIN_SIZE as f32 * 0.08);
+                    let raw = glium::texture::RawImage2d::from_raw_rgba(data, (BUILTIN_SIZE as u32, BUILTIN_SIZE as u32));
+                    glium::texture::Texture2d::with_mipmaps(
+                        display,
+                        raw,
+                        glium::texture::MipmapsOption::AutoGeneratedMipmaps,
+                    )
+                    .ok()
+                }
+            })
+            .as_ref()
+    }
+}
+
+#[test]
+fn test_builtin_maps_tile() {
+    for kind in NormalMap::BUILTIN {
+        let size = 64;
+        let data = generate(&kind, size, 4.0);
+        assert_eq!(data.len(), size * size * 4);
+        // 平坦区域朝 +z，且左右边界的高度连续
+        assert!(data.chunks(4).all(|p| p[2] >= 128));
+        for y in 0..size {
+            let h0 = height(&kind, 0.0, y as f32 / size as f32);
+            let h1 = height(&kind, 1.0 - 1e-4, y as f32 / size as f32);
+            assert!((h0 - h1).abs() < 0.2, "{:?} row {}", kind, y);
+        }
+    }
+}
@@ -52,6 +52,7 @@ impl DebugPass {
                     view: view.to_cols_array_2d(),
                     projection: perspective.to_cols_array_2d(),
                     selected_idx: pt as i32,
+                    highlight_all: false,
                 };
                 target
                     .draw(
@@ -74,6 +75,7 @@ impl DebugPass {
                     view: view.to_cols_array_2d(),
                     projection: perspective.to_cols_array_2d(),
                     selected_idx: 0,
+                    highlight_all: false,
                 };
                 let params = glium::DrawParameters {
                     depth: glium::Depth {
@@ -88,6 +90,7 @@ impl DebugPass {
                     position: debug_vertex,
                     tex_coord: [0.0; 2],
                     normal: [0.0; 3],
+                    tangent: [1.0, 0.0, 0.0, 1.0],
                 }];
                 let debug_vbo = glium::vertex::VertexBuffer::new(display, &vertex_data).unwrap();
                 target
@@ -101,6 +104,47 @@ impl DebugPass {
                     .unwrap();
                // println!("Selected vertex position: {:?}", debug_vertex);
             }
+
+            // 选中的边 / 面轮廓
+            let highlight = &obj.rendering.selection.highlight;
+            if !highlight.is_empty() {
+                let obj_matrix = obj.transform.get_matrix();
+                let uniforms = uniform! {
+                    model: obj_matrix.to_cols_array_2d(),
+                    view: view.to_cols_array_2d(),
+                    projection: perspective.to_cols_array_2d(),
+                    selected_idx: -1,
+                    highlight_all: true,
+                };
+                let params = glium::DrawParameters {
+                    depth: glium::Depth {
+                        test: glium::draw_parameters::DepthTest::Overwrite,
+                        write: false,
+                        ..Default::default()
+                    },
+                    line_width: Some(3.0),
+                    ..Default::default()
+                };
+                let vertex_data: Vec<Vertex> = highlight
+                    .iter()
+                    .map(|&position| Vertex {
+                        position,
+                        tex_coord: [0.0; 2],
+                        normal: [0.0; 3],
+                        tangent: [1.0, 0.0, 0.0, 1.0],
+                    })
+                    .collect();
+                let line_vbo = glium::vertex::VertexBuffer::new(display, &vertex_data).unwrap();
+                target
+                    .draw(
+                        &line_vbo,
+                        glium::index::NoIndices(glium::index::PrimitiveType::LinesList),
+                        &self.program,
+                        &uniforms,
+                        &params,
+                    )
+                    .unwrap();
+            }
         }
     }
 }
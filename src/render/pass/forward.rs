@@ -1,6 +1,7 @@
 use crate::core::material;
 use crate::core::vertex::Vertex;
 use crate::implement_uniform_block_new;
+use crate::render::normal_map::NormalMapCache;
 use crate::render::scene_renderer::{LightSpaceMatrixBlock, PointLightSpaceMatrixBlock, SpotLightSpaceMatrixBlock};
 use crate::render::shader::{create_program, paths};
 use crate::scene::World;
@@ -30,6 +31,9 @@ pub struct ForwardPass {
     cascade_zfars_ubo: UniformBuffer<CascadeZfarsUbo>,
     default_texture: glium::texture::SrgbTexture2d,
     loaded_texture: Option<glium::texture::SrgbTexture2d>,
+    normal_maps: NormalMapCache,
+    /// 未使用法线贴图时绑定的 (0.5, 0.5, 1) 平坦贴图
+    flat_normal_texture: glium::texture::Texture2d,
 }
 
 impl ForwardPass {
@@ -107,6 +111,11 @@ impl ForwardPass {
                 None
             }
         };
+        let flat_normal_texture = glium::texture::Texture2d::new(
+            display,
+            glium::texture::RawImage2d::from_raw_rgba(vec![128u8, 128, 255, 255], (1, 1)),
+        )
+        .unwrap();
         Self {
             program,
             light_space_matrix_ubo,
@@ -117,6 +126,8 @@ impl ForwardPass {
             cascade_zfars_ubo,
             default_texture,
             loaded_texture,
+            normal_maps: NormalMapCache::default(),
+            flat_normal_texture,
         }
     }
 
@@ -137,6 +148,7 @@ impl ForwardPass {
         self.spot_space_matrix_ubo.write(spot_light_space_matrix);
         self.point_space_matrix_ubo.write(point_light_space_matrix);
         self.light_block_ubo.write(light_block);
+        let lod_view = world.lod_view();
         if let Some(idx) = world.get_selected_camera() {
             let camera_obj = &mut world.cameras[idx];
 
@@ -181,19 +193,41 @@ impl ForwardPass {
                 };
                 self.material_ubo.write(&m_block);
 
-                let count = obj.mesh.vertices.len();
-                assert_eq!(count, obj.mesh.tex_coords.len());
-                assert_eq!(count, obj.mesh.normals.len());
+                let mesh = obj.render_mesh(lod_view.as_ref());
+                // 属性或索引不合法的网格直接跳过，可在属性面板中检查修复
+                if !mesh.is_renderable() {
+                    continue;
+                }
+                let count = mesh.vertices.len();
 
-                let mut vertex_data = Vec::with_capacity(count);
+                // 只有使用法线贴图的物体才需要切线，切线在生成网格时已经算好
+                let normal_tex = obj
+                    .rendering
+                    .normal_map
+                    .as_ref()
+                    .and_then(|kind| self.normal_maps.get(display, kind));
+                let fallback;
+                let tangents = match normal_tex {
+                    Some(_) if mesh.tangents.len() == count => &mesh.tangents,
+                    _ => {
+                        fallback = vec![[1.0, 0.0, 0.0, 1.0]; count];
+                        &fallback
+                    }
+                };
 
-                for i in 0..count {
-                    vertex_data.push(Vertex {
-                        position: obj.mesh.vertices[i],
-                        tex_coord: obj.mesh.tex_coords[i],
-                        normal: obj.mesh.normals[i],
-                    });
-                }
+                let vertex_data: Vec<Vertex> = mesh
+                    .vertices
+                    .iter()
+                    .zip(&mesh.tex_coords)
+                    .zip(&mesh.normals)
+                    .zip(tangents)
+                    .map(|(((&position, &tex_coord), &normal), &tangent)| Vertex {
+                        position,
+                        tex_coord,
+                        normal,
+                        tangent,
+                    })
+                    .collect();
                 if vertex_data.is_empty() {
                     continue;
                 }
@@ -203,7 +237,7 @@ impl ForwardPass {
                 let indices = glium::IndexBuffer::new(
                     display,
                     glium::index::PrimitiveType::TrianglesList,
-                    &obj.mesh.indices,
+                    &mesh.indices,
                 )
                 .unwrap();
 
@@ -242,6 +276,11 @@ impl ForwardPass {
                             Light_Block: &self.light_block_ubo,
                             diffuse_tex: use_tex,
                             has_texture: obj.rendering.use_texture,
+                            normal_tex: glium::uniforms::Sampler::new(normal_tex.unwrap_or(&self.flat_normal_texture))
+                                .wrap_function(glium::uniforms::SamplerWrapFunction::Repeat)
+                                .minify_filter(glium::uniforms::MinifySamplerFilter::LinearMipmapLinear),
+                            has_normal_map: normal_tex.is_some(),
+                            normal_strength: obj.rendering.normal_strength,
                             // 传入阴影参数
                             LightSpaceMatrix_Block: &self.light_space_matrix_ubo,
                             SpotLightMatrix_Block: &self.spot_space_matrix_ubo,
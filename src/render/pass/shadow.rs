@@ -389,20 +389,25 @@ impl ShadowPass {
         };
 
         // 渲染所有网格
+        let lod_view = scene.lod_view();
         for obj in &scene.objects {
             if !obj.rendering.visible {
                 continue;
             }
 
-            let vertices: Vec<Vertex> = obj
-                .mesh
+            let mesh = obj.render_mesh(lod_view.as_ref());
+            if !mesh.is_renderable() {
+                continue;
+            }
+            let vertices: Vec<Vertex> = mesh
                 .vertices
                 .iter()
-                .zip(obj.mesh.tex_coords.iter())
+                .zip(mesh.tex_coords.iter())
                 .map(|(v, t)| Vertex {
                     position: *v,
                     tex_coord: *t,
                     normal: [0.0, 0.0, 0.0],
+                    tangent: [1.0, 0.0, 0.0, 1.0],
                 })
                 .collect();
 
@@ -414,7 +419,7 @@ impl ShadowPass {
             let ibo = glium::IndexBuffer::new(
                 display,
                 glium::index::PrimitiveType::TrianglesList,
-                &obj.mesh.indices,
+                &mesh.indices,
             )
             .unwrap();
 
@@ -460,20 +465,25 @@ impl ShadowPass {
         };
 
         // 渲染所有网格
+        let lod_view = scene.lod_view();
         for obj in &scene.objects {
             if !obj.rendering.visible {
                 continue;
             }
 
-            let vertices: Vec<Vertex> = obj
-                .mesh
+            let mesh = obj.render_mesh(lod_view.as_ref());
+            if !mesh.is_renderable() {
+                continue;
+            }
+            let vertices: Vec<Vertex> = mesh
                 .vertices
                 .iter()
-                .zip(obj.mesh.tex_coords.iter())
+                .zip(mesh.tex_coords.iter())
                 .map(|(v, t)| Vertex {
                     position: *v,
                     tex_coord: *t,
                     normal: [0.0, 0.0, 0.0],
+                    tangent: [1.0, 0.0, 0.0, 1.0],
                 })
                 .collect();
 
@@ -485,7 +495,7 @@ impl ShadowPass {
             let ibo = glium::IndexBuffer::new(
                 display,
                 glium::index::PrimitiveType::TrianglesList,
-                &obj.mesh.indices,
+                &mesh.indices,
             )
             .unwrap();
 
@@ -530,20 +540,25 @@ impl ShadowPass {
         };
 
         // 渲染所有网格
+        let lod_view = scene.lod_view();
         for obj in &scene.objects {
             if !obj.rendering.visible {
                 continue;
             }
 
-            let vertices: Vec<Vertex> = obj
-                .mesh
+            let mesh = obj.render_mesh(lod_view.as_ref());
+            if !mesh.is_renderable() {
+                continue;
+            }
+            let vertices: Vec<Vertex> = mesh
                 .vertices
                 .iter()
-                .zip(obj.mesh.tex_coords.iter())
+                .zip(mesh.tex_coords.iter())
                 .map(|(v, t)| Vertex {
                     position: *v,
                     tex_coord: *t,
                     normal: [0.0, 0.0, 0.0],
+                    tangent: [1.0, 0.0, 0.0, 1.0],
                 })
                 .collect();
 
@@ -555,7 +570,7 @@ impl ShadowPass {
             let ibo = glium::IndexBuffer::new(
                 display,
                 glium::index::PrimitiveType::TrianglesList,
-                &obj.mesh.indices,
+                &mesh.indices,
             )
             .unwrap();
 
@@ -2,6 +2,7 @@ use std::f32;
 
 use crate::{
     core::math::transform,
+    physics::{character::CharacterController, layer::CollisionLayers},
     scene::world::{BodyType, PhysicalProperties},
     geometry::shape::mesh::Mesh,
 };
@@ -41,6 +42,7 @@ pub struct Camera {
     pub crouch_half_height: f32,  // 0.45
     pub current_half_height: f32, // 实际使用
     pub is_crouching: bool,
+    pub controller: CharacterController,
 
     pub move_state: MoveState,
     pub pan_obit_speed: f32,
@@ -83,6 +85,7 @@ impl Camera {
             crouch_half_height: 0.45,  // 0.45
             current_half_height: 0.85, // 实际使用
             is_crouching: false,
+            controller: CharacterController::default(),
 
             move_state: MoveState::Locked,
             pan_obit_speed: 1.0,
@@ -100,8 +103,7 @@ impl Camera {
         self.transform.position = [0.0, 0.0, 10.0].into();
         self.transform
             .look_at([0.0, 0.0, 0.0].into(), [0.0, 1.0, 0.0].into());
-        self.physics.friction = 5.5;
-        self.physics.restitution = 0.0;
+        self.physics.material.restitution = 0.0;
         self.rotate(0.0, 0.0);
     }
 
@@ -117,9 +119,11 @@ impl Camera {
         self.transform.rotation = yaw_quat * pitch_quat * glam::f32::Quat::IDENTITY;
     }
 
-    pub fn set_dynamic(&mut self) {
-        self.physics.body_type = BodyType::Dynamic;
+    /// 由角色控制器驱动，对其它刚体而言是运动学物体
+    pub fn set_character(&mut self) {
+        self.physics.body_type = BodyType::Kinematic;
         self.physics.collision = true;
+        self.physics.layer = CollisionLayers::PLAYER;
     }
 
     pub fn set_static(&mut self) {
@@ -195,8 +199,8 @@ impl Camera {
         } else if flag[2] == flag[3] {
             r_force *= 0.0;
         }
-        if flag[4] && self.physics.velocity[1].abs() < 0.01 {
-            self.physics.velocity[1] = self.up_velocity;
+        if flag[4] && self.controller.can_jump() {
+            self.controller.jump(self.up_velocity);
         }
         self.physics.force = (f_force + r_force).to_array();
     }
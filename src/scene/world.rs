@@ -1,22 +1,41 @@
 use super::camera::{Camera, MouseState};
 use super::light::Light;
 
-use crate::core::material::Material;
+use crate::core::material::{Material, NormalMap};
 use crate::core::math::transform::Transform;
 use crate::core::vertex::Vertex;
-use crate::geometry::shape::mesh::{AsMesh, Mesh};
+use crate::geometry::csg::{CsgOp, boolean};
+use crate::geometry::edit::MeshSelection;
+use crate::geometry::normals::NormalModifier;
+use crate::geometry::repair::{MeshReport, RepairStats};
+use crate::geometry::shape::csg::{CsgNode, CsgOperand};
+use crate::geometry::shape::mesh::{AsMesh, Mesh, MeshShape};
 use crate::geometry::shape::nurbs::NurbsSurface;
+use crate::geometry::simplify::{LodChain, LodMetric, LodView};
+use crate::geometry::subdivision::SubdivisionModifier;
+use crate::geometry::uv::UvModifier;
 use crate::geometry::shape::{cone::Cone, cube::Cube, cylinder::Cylinder, sphere::Sphere};
-use crate::physics::boundingbox::{AABB, BoundingVolume};
-use crate::physics::collision::board::collide;
-use crate::physics::collision::solve::{solve_contact, stimulate_step};
+use crate::physics::boundingbox::{AABB, BoundingVolume, SphereBox};
+use crate::physics::collider::{ColliderCache, ColliderShape};
+use crate::physics::collision::solve::{PhysicsState, step_bodies};
+use crate::physics::event::{EventQueue, PhysicsEvent};
+use crate::physics::layer::CollisionLayers;
+use crate::physics::sleep::SleepState;
+use crate::physics::timestep::{FixedTimestep, Interpolation};
+use crate::physics::character::Obstacle;
+use crate::physics::joint::{Joint, JointKind, JointMotor};
+use crate::physics::material::PhysicsMaterial;
+use crate::physics::query::{QueryBody, QueryFilter, QueryHit, SceneQuery};
 use crate::physics::rigid::RigidBody;
 
 use glutin::surface::WindowSurface;
 use std::fmt::Debug;
 use std::time::Instant;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// 射击的最远距离
+const SHOOT_RANGE: f32 = 100.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BodyHandle {
     Object(usize),
     Camera(usize),
@@ -26,6 +45,8 @@ pub enum BodyHandle {
 pub enum BodyType {
     Static,
     Dynamic,
+    /// 由脚本或动画驱动，不受力影响，但会推动动态物体
+    Kinematic,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -33,6 +54,10 @@ pub enum InteractionBehavior {
     None,
     Door { is_open: bool, base_yaw: f32 },
     Window { is_broken: bool },
+    /// 在 origin 与 origin + offset 之间往返的平台或电梯，period 为往返一次的秒数
+    Mover { origin: glam::f32::Vec3, offset: glam::f32::Vec3, period: f32, time: f32 },
+    /// 玩家进入时打开下标为 door 的门，离开时关上
+    DoorSensor { door: usize },
 }
 
 #[derive(Clone, PartialEq)]
@@ -47,12 +72,24 @@ pub enum ShapeKind {
 #[derive(Clone)]
 pub struct PhysicalProperties {
     pub velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub torque: [f32; 3],
     pub collision: bool,
     pub force: [f32; 3],
-    pub friction: f32,
+    pub material: PhysicsMaterial,
     pub mass: f32,
+    /// 质量由材质密度乘碰撞形状体积得到，忽略 mass
+    pub mass_from_density: bool,
     pub body_type: BodyType,
-    pub restitution: f32,
+    pub shape: ColliderShape,
+    /// 高速物体开启连续碰撞检测
+    pub ccd: bool,
+    /// 所在的碰撞层 (0~31)，层间是否碰撞由 PhysicsState::layers 决定
+    pub layer: u32,
+    /// 触发器：只检测重叠并产生事件，不参与碰撞响应
+    pub sensor: bool,
+    pub sleep: SleepState,
+    pub interpolation: Interpolation,
 }
 
 impl PhysicalProperties {
@@ -61,18 +98,34 @@ impl PhysicalProperties {
         default.collision = false;
         default
     }
+
+    /// 实际使用的碰撞形状：动态物体不能用三角网格，退化为凸包
+    pub fn effective_shape(&self) -> ColliderShape {
+        match self.shape {
+            ColliderShape::TriMesh if self.body_type == BodyType::Dynamic => ColliderShape::ConvexHull,
+            shape => shape,
+        }
+    }
 }
 
 impl Default for PhysicalProperties {
     fn default() -> Self {
         Self {
             velocity: [0.0, 0.0, 0.0],
+            angular_velocity: [0.0, 0.0, 0.0],
+            torque: [0.0, 0.0, 0.0],
             force: [0.0, 0.0, 0.0],
-            friction: 0.01,
+            material: PhysicsMaterial::DEFAULT,
             collision: true,
             mass: 1.0,
+            mass_from_density: false,
             body_type: BodyType::Static,
-            restitution: 0.5,
+            shape: ColliderShape::default(),
+            ccd: false,
+            layer: 0,
+            sensor: false,
+            sleep: SleepState::default(),
+            interpolation: Interpolation::default(),
         }
     }
 }
@@ -83,6 +136,9 @@ pub struct RenderProperties {
     pub visible: bool,
     pub use_texture: bool,
     pub selected_vertex_index: Option<usize>,
+    pub selection: MeshSelection,
+    pub normal_map: Option<NormalMap>,
+    pub normal_strength: f32,
 }
 
 pub trait EditableMesh: AsMesh {
@@ -108,6 +164,11 @@ pub struct GameObject {
     pub transform: Transform,
     pub physics: PhysicalProperties,
     pub behavior: InteractionBehavior,
+    pub subdivision: Option<SubdivisionModifier>,
+    pub uv_modifier: Option<UvModifier>,
+    pub normal_modifier: Option<NormalModifier>,
+    pub lod: Option<LodChain>,
+    pub collider: ColliderCache,
 }
 
 pub struct LightObject {
@@ -134,6 +195,18 @@ impl RigidBody for CameraObject {
     fn velocity_mut(&mut self) -> &mut [f32; 3] {
         &mut self.camera.physics.velocity
     }
+    fn angular_velocity(&self) -> [f32; 3] {
+        self.camera.physics.angular_velocity
+    }
+    fn angular_velocity_mut(&mut self) -> &mut [f32; 3] {
+        &mut self.camera.physics.angular_velocity
+    }
+    fn torque(&self) -> [f32; 3] {
+        self.camera.physics.torque
+    }
+    fn torque_mut(&mut self) -> &mut [f32; 3] {
+        &mut self.camera.physics.torque
+    }
     fn mass(&self) -> f32 {
         self.camera.physics.mass
     }
@@ -144,24 +217,27 @@ impl RigidBody for CameraObject {
     fn force_mut(&mut self) -> &mut [f32; 3] {
         &mut self.camera.physics.force
     }
-    fn friction(&self) -> f32 {
-        self.camera.physics.friction
+    fn material(&self) -> PhysicsMaterial {
+        self.camera.physics.material
+    }
+    fn continuous(&self) -> bool {
+        self.camera.physics.ccd
+    }
+    fn layer(&self) -> u32 {
+        self.camera.physics.layer
+    }
+    fn sleep_state(&self) -> SleepState {
+        self.camera.physics.sleep
+    }
+    fn sleep_state_mut(&mut self) -> &mut SleepState {
+        &mut self.camera.physics.sleep
     }
     fn body_type(&self) -> BodyType {
         self.camera.physics.body_type
     }
-    fn restitution(&self) -> f32 {
-        self.camera.physics.restitution
-    }
     fn bounding_volume(&self) -> BoundingVolume {
-        let h = self.camera.current_half_height;
-
-        let local_aabb = AABB {
-            min: glam::Vec3::new(-0.25, -h * 2.0, -0.25),
-            max: glam::Vec3::new(0.25, 0.0, 0.25),
-        };
-
-        BoundingVolume::AABB(local_aabb.get_global_aabb(self.camera.transform.get_matrix()))
+        let camera = &self.camera;
+        BoundingVolume::Capsule(camera.controller.capsule(camera.transform.position, camera.current_half_height))
     }
 }
 
@@ -178,12 +254,21 @@ impl RigidBody for GameObject {
     fn velocity_mut(&mut self) -> &mut [f32; 3] {
         &mut self.physics.velocity
     }
+    fn angular_velocity(&self) -> [f32; 3] {
+        self.physics.angular_velocity
+    }
+    fn angular_velocity_mut(&mut self) -> &mut [f32; 3] {
+        &mut self.physics.angular_velocity
+    }
+    fn torque(&self) -> [f32; 3] {
+        self.physics.torque
+    }
+    fn torque_mut(&mut self) -> &mut [f32; 3] {
+        &mut self.physics.torque
+    }
     fn body_type(&self) -> BodyType {
         self.physics.body_type
     }
-    fn restitution(&self) -> f32 {
-        self.physics.restitution
-    }
     fn force(&self) -> [f32; 3] {
         self.physics.force
     }
@@ -191,21 +276,56 @@ impl RigidBody for GameObject {
     fn force_mut(&mut self) -> &mut [f32; 3] {
         &mut self.physics.force
     }
-    fn friction(&self) -> f32 {
-        self.physics.friction
+    fn material(&self) -> PhysicsMaterial {
+        self.physics.material
+    }
+    fn continuous(&self) -> bool {
+        self.physics.ccd
+    }
+    fn layer(&self) -> u32 {
+        self.physics.layer
+    }
+    fn is_sensor(&self) -> bool {
+        self.physics.sensor
+    }
+    fn sleep_state(&self) -> SleepState {
+        self.physics.sleep
+    }
+    fn sleep_state_mut(&mut self) -> &mut SleepState {
+        &mut self.physics.sleep
     }
     fn mass(&self) -> f32 {
-        self.physics.mass
+        if !self.physics.mass_from_density {
+            return self.physics.mass;
+        }
+        let volume = self.collider.volume(self.physics.effective_shape(), &self.mesh.bounding_volume, self.transform.scale);
+        self.physics.material.density * volume
     }
-    fn bounding_volume(&self) -> BoundingVolume {
-        match self.mesh.bounding_volume {
-            BoundingVolume::AABB(ref aabb) => {
-                BoundingVolume::AABB(aabb.get_global_aabb(self.transform.get_matrix()))
-            }
-            BoundingVolume::Sphere(ref sphere) => BoundingVolume::Sphere(
-                sphere.get_global_sphere(self.transform.scale, self.transform.position),
-            ),
+    fn inv_inertia(&self) -> glam::Mat3 {
+        if !self.is_dynamic() {
+            return glam::Mat3::ZERO;
         }
+        let inertia = self.collider.inertia(
+            self.physics.effective_shape(),
+            &self.mesh.bounding_volume,
+            self.transform.scale,
+            self.mass(),
+        );
+        let inv = glam::Vec3::select(inertia.cmpgt(glam::Vec3::ZERO), inertia.recip(), glam::Vec3::ZERO);
+        let rotation = glam::Mat3::from_quat(self.transform.rotation);
+        rotation * glam::Mat3::from_diagonal(inv) * rotation.transpose()
+    }
+    fn center_of_mass(&self) -> glam::Vec3 {
+        self.transform
+            .get_matrix()
+            .transform_point3(self.mesh.bounding_volume.bounds().center())
+    }
+    fn bounding_volume(&self) -> BoundingVolume {
+        self.collider.world_volume(
+            self.physics.effective_shape(),
+            &self.mesh.bounding_volume,
+            &self.transform,
+        )
     }
 }
 
@@ -221,6 +341,8 @@ impl GameObject {
                 tex_coords: vec![],
                 indices: vec![],
                 bounding_volume: BoundingVolume::AABB(AABB::default()),
+                tangents: Vec::new(),
+                smoothing_groups: Vec::new(),
                 // bvh: None,
             },
             physics: PhysicalProperties::default(),
@@ -229,8 +351,16 @@ impl GameObject {
                 visible: true,
                 use_texture: false,
                 selected_vertex_index: None,
+                selection: MeshSelection::default(),
+                normal_map: None,
+                normal_strength: 1.0,
             },
             behavior: InteractionBehavior::None,
+            subdivision: None,
+            uv_modifier: None,
+            normal_modifier: None,
+            lod: None,
+            collider: ColliderCache::default(),
         };
         obj.regenerate_mesh();
         obj
@@ -241,11 +371,104 @@ impl GameObject {
             return;
         }
         self.physics.velocity = [0.0, 0.0, 0.0];
+        self.physics.angular_velocity = [0.0, 0.0, 0.0];
         self.physics.body_type = new_type;
+        self.physics.sleep.wake();
     }
 
+    /// 脚本驱动的运动：运动学物体交给物理积分以便推动其它物体，否则直接移动
+    pub fn drive_to(&mut self, target: Transform, dt: f32) {
+        if self.physics.body_type == BodyType::Kinematic && self.physics.collision {
+            self.set_kinematic_target(&target, dt);
+        } else {
+            self.transform = target;
+        }
+    }
+
+    /// 形状或修改器变化后重建网格，并重新生成 LOD 链
     pub fn regenerate_mesh(&mut self) {
+        self.rebuild_mesh();
+        self.regenerate_lod();
+    }
+
+    /// 只按形状与修改器重建最精细一级网格，LOD 链保持不变
+    pub fn rebuild_mesh(&mut self) {
         self.mesh = self.shape.as_mesh();
+        if let Some(modifier) = &self.subdivision {
+            self.mesh = modifier.apply(&self.mesh);
+        }
+        if let Some(modifier) = &self.uv_modifier {
+            self.mesh = modifier.apply(&self.mesh, self.transform.scale);
+        }
+        if let Some(modifier) = &self.normal_modifier {
+            self.mesh = modifier.apply(&self.mesh);
+        }
+        self.mesh.update_tangents();
+        self.collider.invalidate();
+    }
+
+    pub fn regenerate_lod(&mut self) {
+        if let Some(lod) = &mut self.lod {
+            lod.generate(&self.mesh);
+        }
+    }
+
+    pub fn enable_lod(&mut self, metric: LodMetric) {
+        let mut lod = LodChain::new(metric);
+        lod.generate(&self.mesh);
+        self.lod = Some(lod);
+    }
+
+    /// 渲染用网格：启用 LOD 时按观察者选择层级
+    pub fn render_mesh(&self, view: Option<&LodView>) -> &Mesh {
+        match (&self.lod, view) {
+            (Some(lod), Some(view)) if !lod.levels.is_empty() => {
+                let level = lod.select(
+                    &self.mesh.bounding_volume,
+                    self.transform.get_matrix(),
+                    view,
+                );
+                // 第 0 级直接用当前网格，重建网格而未重新生成 LOD 时也不会过期
+                if level == 0 {
+                    &self.mesh
+                } else {
+                    &lod.levels[level]
+                }
+            }
+            _ => &self.mesh,
+        }
+    }
+
+    /// 是否有改变网格拓扑的修改器
+    pub fn has_modifiers(&self) -> bool {
+        self.subdivision.is_some() || self.uv_modifier.is_some() || self.normal_modifier.is_some()
+    }
+
+    /// 世界空间射线与网格三角形求交，返回击中点到起点的距离
+    pub fn ray_hit(&self, origin: glam::f32::Vec3, direction: glam::f32::Vec3) -> Option<f32> {
+        let matrix = self.transform.get_matrix();
+        let inv = matrix.inverse();
+        let (_, hit) = self
+            .mesh
+            .compute_intersecting_face(inv.transform_point3(origin).to_array(), inv.transform_vector3(direction).to_array())?;
+        Some(matrix.transform_point3(hit.into()).distance(origin))
+    }
+
+    /// 编辑操作作用的网格：启用修改器时为修改前的控制网格
+    pub fn cage_mesh(&self) -> Mesh {
+        if self.has_modifiers() {
+            self.shape.as_mesh()
+        } else {
+            self.mesh.clone()
+        }
+    }
+
+    /// 用编辑后的网格替换当前形状，并清空选择
+    pub fn replace_mesh(&mut self, mesh: Mesh) {
+        self.shape = Box::new(MeshShape::new(mesh));
+        self.regenerate_mesh();
+        self.rendering.selected_vertex_index = None;
+        self.rendering.selection.clear();
     }
 }
 
@@ -257,6 +480,7 @@ pub struct DebugParams {
     pub game_object_property: bool,
     pub camera_property: bool,
     pub light_property: bool,
+    pub collision_layers: bool,
 }
 
 
@@ -277,6 +501,14 @@ pub struct World {
     pub camera_force: [bool; 6],
     pub layer: usize,
     pub gravity: [f32; 3],
+    pub csg_operand: Option<usize>,
+    /// 最近一次布尔运算失败的原因，显示在属性面板中
+    pub csg_error: Option<String>,
+    /// 最近导入模型的检查报告 (物体索引, 报告, 上次修复的统计)
+    pub import_report: Option<(usize, MeshReport, Option<RepairStats>)>,
+    /// 上一步的接触冲量，用于求解器热启动
+    pub physics_state: PhysicsState,
+    pub timestep: FixedTimestep,
 }
 
 impl Default for World {
@@ -304,76 +536,282 @@ impl World {
             layer: 0,
             camera_force: [false; 6],
             gravity: [0.0, -9.8, 0.0],
+            csg_operand: None,
+            csg_error: None,
+            import_report: None,
+            physics_state: PhysicsState::default(),
+            timestep: FixedTimestep::default(),
         }
     }
 
+    /// 按固定步长推进物理，渲染用的 transform 在前后两个物理状态之间插值
+    /// 本帧各子步产生的物理事件保留到下一次 step，可通过 physics_events 读取
     pub fn step(&mut self, dt: f32) {
-        // 1. 处理门窗动画 %
+        let steps = self.timestep.advance(dt);
+        self.physics_state.events.clear();
         for obj in &mut self.objects {
-            if let InteractionBehavior::Door { is_open, base_yaw } = obj.behavior {
-                let target_yaw = if is_open { base_yaw + 1.5708 } else { base_yaw };
-                let current_rot = obj.transform.rotation;
-                let target_rot = glam::f32::Quat::from_rotation_y(target_yaw);
-                obj.transform.rotation = current_rot.slerp(target_rot, dt * 5.0);
-            }
+            obj.physics.interpolation.restore(&mut obj.transform);
+        }
+        for cam in &mut self.cameras {
+            cam.camera.physics.interpolation.restore(&mut cam.camera.transform);
         }
 
-        // 2. 收集所有物体
-        let mut bodies: Vec<BodyHandle> = Vec::new();
-        for i in 0..self.objects.len() {
-            if self.objects[i].physics.collision {
-                bodies.push(BodyHandle::Object(i));
+        // 外力在整帧内保持，每个子步都重新施加
+        let object_forces: Vec<_> = self.objects.iter().map(|o| (o.physics.force, o.physics.torque)).collect();
+        let camera_forces: Vec<_> = self
+            .cameras
+            .iter()
+            .map(|c| (c.camera.physics.force, c.camera.physics.torque))
+            .collect();
+        let fixed_dt = self.timestep.dt();
+        for _ in 0..steps {
+            for (obj, (force, torque)) in self.objects.iter_mut().zip(&object_forces) {
+                obj.physics.interpolation.record(&obj.transform);
+                (obj.physics.force, obj.physics.torque) = (*force, *torque);
+            }
+            for (cam, (force, torque)) in self.cameras.iter_mut().zip(&camera_forces) {
+                cam.camera.physics.interpolation.record(&cam.camera.transform);
+                (cam.camera.physics.force, cam.camera.physics.torque) = (*force, *torque);
             }
+            self.fixed_step(fixed_dt);
         }
-        if let Some(idx) = self.get_selected_camera() {
-            let camera = &mut self.cameras[idx].camera;
-            if camera.physics.collision {
-                bodies.push(BodyHandle::Camera(idx));
+        self.handle_trigger_events();
+
+        let alpha = self.timestep.alpha();
+        for obj in &mut self.objects {
+            obj.physics.interpolation.apply(&mut obj.transform, alpha);
+        }
+        for cam in &mut self.cameras {
+            cam.camera.physics.interpolation.apply(&mut cam.camera.transform, alpha);
+        }
+    }
+
+    /// 一个固定长度的物理步
+    fn fixed_step(&mut self, dt: f32) {
+        // 1. 处理门与平台等脚本运动
+        for obj in &mut self.objects {
+            let mut target = obj.transform.clone();
+            match &mut obj.behavior {
+                InteractionBehavior::Door { is_open, base_yaw } => {
+                    let target_yaw = if *is_open { *base_yaw + std::f32::consts::FRAC_PI_2 } else { *base_yaw };
+                    let target_rot = glam::f32::Quat::from_rotation_y(target_yaw);
+                    target.rotation = target.rotation.slerp(target_rot, dt * 5.0);
+                }
+                InteractionBehavior::Mover { origin, offset, period, time } => {
+                    *time = (*time + dt) % period.max(f32::EPSILON);
+                    let phase = 0.5 - 0.5 * (std::f32::consts::TAU * *time / *period).cos();
+                    target.position = *origin + *offset * phase;
+                }
+                _ => continue,
             }
-            let target = if camera.is_crouching {
-                camera.crouch_half_height
-            } else {
-                camera.stand_half_height
-            };
+            obj.drive_to(target, dt);
+        }
 
-            let speed = 6.0; // 下蹲/站起速度
-            let current_height = camera.current_half_height;
-            camera.current_half_height = lerp(current_height, target, dt * speed);
+        // 2. 玩家由角色控制器移动
+        for obj in &mut self.objects {
+            if obj.physics.collision {
+                obj.collider.update(&obj.mesh, obj.physics.effective_shape());
+            }
+        }
+        let selected_camera = self.get_selected_camera();
+        if let Some(idx) = selected_camera {
+            self.step_character(idx, dt);
         }
 
-        for i in 0..bodies.len() {
-            if !is_dynamic(bodies[i], self) {
-                continue;
+        // 3. 收集所有参与碰撞的物体
+        let mut bodies: Vec<(BodyHandle, &mut dyn RigidBody)> = Vec::new();
+        for (i, obj) in self.objects.iter_mut().enumerate() {
+            if obj.physics.collision {
+                bodies.push((BodyHandle::Object(i), obj));
             }
-            stimulate_step(bodies[i], self, dt);
+        }
+        if let Some(idx) = selected_camera
+            && self.cameras[idx].camera.physics.collision
+        {
+            bodies.push((BodyHandle::Camera(idx), &mut self.cameras[idx]));
+        }
 
-            for j in 0..bodies.len() {
-                if i == j {
-                    continue;
-                }
+        // 4. 积分速度、求解全部接触、积分位置
+        step_bodies(&mut bodies, self.gravity, &mut self.physics_state, dt);
+    }
 
-                let (a, b) = self.get_two_bodies_mut(bodies[i], bodies[j]);
+    /// 下蹲/站起，并用角色控制器求出本步的目标位置；角色作为运动学物体推动动态物体
+    fn step_character(&mut self, idx: usize, dt: f32) {
+        let collision = self.cameras[idx].camera.physics.collision;
+        // 与玩家所在层不碰撞的物体可以直接穿过
+        let (layers, layer) = (&self.physics_state.layers, self.cameras[idx].camera.physics.layer);
+        let obstacles: Vec<Obstacle> = if collision {
+            self.objects
+                .iter()
+                .filter(|obj| obj.physics.collision && !obj.physics.sensor && layers.collides(layer, obj.physics.layer))
+                .map(|obj| Obstacle {
+                    volume: obj.bounding_volume(),
+                    velocity: glam::Vec3::from_array(obj.physics.velocity),
+                    body_type: obj.physics.body_type,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-                // 只有当 b 是静态物体时我们才处理碰撞 (简化逻辑，防止物体互挤乱飞)
-                // 如果你想让物体之间也能推着走，可以把这个限制去掉，但 resolving 逻辑会变复杂
-                let contact = collide(&a.bounding_volume(), &b.bounding_volume());
-                if let Some(contact) = contact {
-                    solve_contact(a, b, &contact, dt);
-                }
-            }
+        let camera_object = &mut self.cameras[idx];
+        let camera = &mut camera_object.camera;
+        let mut target = if camera.is_crouching {
+            camera.crouch_half_height
+        } else {
+            camera.stand_half_height
+        };
+        // 头顶有障碍时保持下蹲
+        let feet = camera.transform.position - glam::Vec3::Y * (2.0 * camera.current_half_height);
+        if target > camera.current_half_height
+            && !camera.controller.fits(feet + glam::Vec3::Y * (2.0 * target), target, &obstacles)
+        {
+            target = camera.current_half_height;
         }
+
+        let speed = 6.0; // 下蹲/站起速度
+        let current_height = camera.current_half_height;
+        camera.current_half_height = lerp(current_height, target, dt * speed);
+        if !collision {
+            return;
+        }
+
+        let acceleration = glam::Vec3::from_array(camera.physics.force) / camera.physics.mass.max(0.0001)
+            + glam::Vec3::from_array(self.gravity);
+        let eye = camera.controller.update(
+            camera.transform.position,
+            camera.current_half_height,
+            acceleration,
+            &obstacles,
+            dt,
+        );
+        let mut target = camera.transform.clone();
+        target.position = eye;
+        camera_object.set_kinematic_target(&target, dt);
+    }
+
+    /// 以当前参与碰撞的物体构造场景查询，候选物体由物理宽相给出
+    pub fn scene_query(&self) -> SceneQuery<'_> {
+        let mut bodies: Vec<QueryBody> = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(_, obj)| obj.physics.collision)
+            .map(|(i, obj)| QueryBody {
+                handle: BodyHandle::Object(i),
+                volume: obj.bounding_volume(),
+                layer: obj.physics.layer,
+                is_static: obj.is_static(),
+                sensor: obj.physics.sensor,
+            })
+            .collect();
+        if let Some(idx) = self.get_selected_camera()
+            && self.cameras[idx].camera.physics.collision
+        {
+            let camera = &self.cameras[idx];
+            bodies.push(QueryBody {
+                handle: BodyHandle::Camera(idx),
+                volume: camera.bounding_volume(),
+                layer: camera.camera.physics.layer,
+                is_static: false,
+                sensor: false,
+            });
+        }
+        SceneQuery::new(bodies, &self.physics_state.broad_phase)
+    }
+
+    /// 拾取用的场景查询：包含所有可见物体，包围体取网格本身的包围体；
+    /// 不参与碰撞的物体不在静态树里，按动态物体逐个检测
+    pub fn pick_query(&self) -> SceneQuery<'_> {
+        let bodies = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(_, obj)| obj.rendering.visible)
+            .map(|(i, obj)| QueryBody {
+                handle: BodyHandle::Object(i),
+                volume: obj.collider.world_volume(ColliderShape::Auto, &obj.mesh.bounding_volume, &obj.transform),
+                layer: obj.physics.layer,
+                is_static: obj.is_static() && obj.physics.collision,
+                sensor: obj.physics.sensor,
+            })
+            .collect();
+        SceneQuery::new(bodies, &self.physics_state.broad_phase)
+    }
+
+    /// 射线检测，返回最近的命中
+    pub fn raycast(
+        &self,
+        origin: glam::f32::Vec3,
+        direction: glam::f32::Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<QueryHit> {
+        self.scene_query().raycast(origin, direction, max_distance, filter)
+    }
+
+    /// 射线检测，返回所有命中，按距离排序
+    pub fn raycast_all(
+        &self,
+        origin: glam::f32::Vec3,
+        direction: glam::f32::Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Vec<QueryHit> {
+        self.scene_query().raycast_all(origin, direction, max_distance, filter)
+    }
+
+    pub fn sphere_cast(
+        &self,
+        center: glam::f32::Vec3,
+        radius: f32,
+        direction: glam::f32::Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<QueryHit> {
+        self.scene_query().sphere_cast(center, radius, direction, max_distance, filter)
+    }
+
+    pub fn box_cast(
+        &self,
+        center: glam::f32::Vec3,
+        half_extents: glam::f32::Vec3,
+        rotation: glam::f32::Quat,
+        direction: glam::f32::Vec3,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<QueryHit> {
+        self.scene_query().box_cast(center, half_extents, rotation, direction, max_distance, filter)
+    }
+
+    /// 与 volume 重叠的物体
+    pub fn overlap(&self, volume: &BoundingVolume, filter: &QueryFilter) -> Vec<BodyHandle> {
+        self.scene_query().overlap(volume, filter)
     }
 
+    /// 与附近的门窗交互：取 3 米内、视线不被遮挡的最近一个
     pub fn handle_interaction_input(&mut self, player_pos: glam::f32::Vec3) {
+        let query = self.scene_query();
+        let filter = match self.get_selected_camera() {
+            Some(idx) => QueryFilter::excluding(BodyHandle::Camera(idx)),
+            None => QueryFilter::default(),
+        };
+        let reach = BoundingVolume::Sphere(SphereBox::new(player_pos, 3.0));
         let mut nearest_idx = None;
-        let mut min_dist = 3.0;
-
-        for (i, obj) in self.objects.iter().enumerate() {
-            if !obj.rendering.visible {
+        let mut min_dist = f32::MAX;
+        for handle in query.overlap(&reach, &filter) {
+            let BodyHandle::Object(i) = handle else { continue };
+            let obj = &self.objects[i];
+            if !obj.rendering.visible || obj.behavior == InteractionBehavior::None {
                 continue;
             }
-            let dist = obj.transform.position.distance(player_pos);
-            if dist < min_dist {
+            let center = obj.bounding_volume().bounds().center();
+            let to_center = center - player_pos;
+            let visible = query
+                .raycast(player_pos, to_center, to_center.length(), &filter)
+                .is_none_or(|hit| hit.handle == handle);
+            let dist = to_center.length();
+            if visible && dist < min_dist {
                 min_dist = dist;
                 nearest_idx = Some(i);
             }
@@ -401,6 +839,7 @@ impl World {
     fn break_window(&mut self, window_idx: usize) {
         let window = &mut self.objects[window_idx];
         window.rendering.visible = false;
+        window.physics.collision = false;
         if let InteractionBehavior::Window { is_broken } = &mut window.behavior {
             *is_broken = true;
         }
@@ -428,46 +867,15 @@ impl World {
                 );
                 shard.transform.position = pos + offset;
                 shard.physics.body_type = BodyType::Dynamic;
+                shard.physics.ccd = true;
+                shard.physics.layer = CollisionLayers::DEBRIS;
                 shard.physics.velocity = [(c as f32 - 1.5) * 2.0, (r as f32 - 1.5) * 2.0, 5.0];
+                shard.physics.angular_velocity = [(r as f32 - 1.5) * 3.0, 0.0, (1.5 - c as f32) * 3.0];
                 shards.push(shard);
             }
         }
         self.objects.append(&mut shards);
     }
-    fn get_two_bodies_mut(
-        &mut self,
-        a: BodyHandle,
-        b: BodyHandle,
-    ) -> (&mut dyn RigidBody, &mut dyn RigidBody) {
-        match (a, b) {
-            (BodyHandle::Object(i), BodyHandle::Object(j)) => {
-                if i < j {
-                    let (left, right) = self.objects.split_at_mut(i + 1);
-                    (&mut left[i], &mut right[j - i - 1])
-                } else {
-                    let (left, right) = self.objects.split_at_mut(j + 1);
-                    (&mut right[i - j - 1], &mut left[j])
-                }
-            }
-
-            (BodyHandle::Camera(i), BodyHandle::Camera(j)) => {
-                let (left, right) = self.cameras.split_at_mut(j);
-                (&mut left[i], &mut right[0])
-            }
-
-            (BodyHandle::Object(i), BodyHandle::Camera(j)) => {
-                let obj = &mut self.objects[i];
-                let cam = &mut self.cameras[j];
-                (obj, cam)
-            }
-
-            (BodyHandle::Camera(i), BodyHandle::Object(j)) => {
-                let cam = &mut self.cameras[i];
-                let obj = &mut self.objects[j];
-                (cam, obj)
-            }
-        }
-    }
     pub fn create_door(&mut self, pos: glam::f32::Vec3) {
         let width = 1.0;
         let height = 2.0;
@@ -491,8 +899,173 @@ impl World {
             is_open: false,
             base_yaw: 0.0,
         };
-        door.set_body_type(BodyType::Static);
+        door.set_body_type(BodyType::Kinematic);
+        self.add_object(door);
+    }
+
+    /// 生成自动门：门前后各有一段触发区域，玩家走近时打开
+    pub fn create_auto_door(&mut self, pos: glam::f32::Vec3) {
+        self.create_door(pos);
+        let door = self.objects.len() - 1;
+        let mut sensor = GameObject::new(
+            "Door Sensor",
+            Box::new(Cube {
+                width: 1.6,
+                height: 2.0,
+                depth: 3.0,
+            }),
+            self.default_mat,
+        );
+        sensor.transform.position = pos + glam::vec3(0.5, 0.0, 0.0);
+        sensor.set_body_type(BodyType::Static);
+        sensor.physics.sensor = true;
+        sensor.physics.layer = CollisionLayers::TRIGGER;
+        sensor.rendering.visible = false;
+        sensor.behavior = InteractionBehavior::DoorSensor { door };
+        self.add_object(sensor);
+    }
+
+    /// 本帧产生的物理事件
+    pub fn physics_events(&self) -> &[PhysicsEvent] {
+        self.physics_state.events.events()
+    }
+
+    /// 触发器事件驱动的玩法：玩家进出门的触发区域时开关门
+    fn handle_trigger_events(&mut self) {
+        for event in self.physics_state.events.events() {
+            let (trigger, open) = match *event {
+                PhysicsEvent::TriggerEnter { trigger: BodyHandle::Object(t), other: BodyHandle::Camera(_) } => (t, true),
+                PhysicsEvent::TriggerExit { trigger: BodyHandle::Object(t), other: BodyHandle::Camera(_) } => (t, false),
+                _ => continue,
+            };
+            let InteractionBehavior::DoorSensor { door } = self.objects[trigger].behavior else { continue };
+            if let InteractionBehavior::Door { is_open, .. } = &mut self.objects[door].behavior {
+                *is_open = open;
+            }
+        }
+    }
+
+    /// 生成上下往返的升降台
+    pub fn create_platform(&mut self, pos: glam::f32::Vec3) {
+        let mut platform = GameObject::new(
+            "Platform",
+            Box::new(Cube {
+                width: 2.0,
+                height: 0.2,
+                depth: 2.0,
+            }),
+            self.default_mat,
+        );
+        platform.transform.position = pos;
+        platform.behavior = InteractionBehavior::Mover {
+            origin: pos,
+            offset: glam::vec3(0.0, 2.0, 0.0),
+            period: 6.0,
+            time: 0.0,
+        };
+        platform.set_body_type(BodyType::Kinematic);
+        self.add_object(platform);
+    }
+
+    /// 在物体 a 与 b 之间建立关节，b 为 None 时连接到世界；锚点与轴都是世界坐标
+    pub fn add_joint(
+        &mut self,
+        a: usize,
+        b: Option<usize>,
+        anchor_a: glam::f32::Vec3,
+        anchor_b: glam::f32::Vec3,
+        kind: JointKind,
+    ) -> usize {
+        let joint = Joint::new(
+            (BodyHandle::Object(a), &self.objects[a].transform),
+            b.map(|b| (BodyHandle::Object(b), &self.objects[b].transform)),
+            anchor_a,
+            anchor_b,
+            kind,
+        );
+        self.physics_state.joints.push(joint);
+        self.objects[a].wake();
+        if let Some(b) = b {
+            self.objects[b].wake();
+        }
+        self.physics_state.joints.len() - 1
+    }
+
+    pub fn remove_joint(&mut self, idx: usize) -> Joint {
+        let joint = self.physics_state.joints.remove(idx);
+        for handle in [Some(joint.a), joint.b].into_iter().flatten() {
+            if let BodyHandle::Object(i) = handle {
+                self.objects[i].wake();
+            }
+        }
+        joint
+    }
+
+    /// 生成用铰链挂在世界上的门，可以推开，转角限制在 ±90° 之间
+    pub fn create_hinged_door(&mut self, pos: glam::f32::Vec3) {
+        let (width, height) = (1.0, 2.0);
+        let mut door = GameObject::new(
+            "Hinged Door",
+            Box::new(Cube {
+                width,
+                height,
+                depth: 0.1,
+            }),
+            self.default_mat,
+        );
+        door.transform.position = pos + glam::vec3(width / 2.0, 0.0, 0.0);
+        door.physics.material = PhysicsMaterial::WOOD;
+        door.physics.mass = 10.0;
+        door.set_body_type(BodyType::Dynamic);
         self.add_object(door);
+        let limit = std::f32::consts::FRAC_PI_2;
+        let hinge = JointKind::Hinge {
+            axis: glam::Vec3::Y,
+            limits: Some((-limit, limit)),
+            motor: Some(JointMotor { speed: 0.0, max_force: 2.0 }),
+        };
+        self.add_joint(self.objects.len() - 1, None, pos, pos, hinge);
+    }
+
+    /// 生成挂在 pos 处的吊灯：几节链条用球窝关节串起来，末端是灯罩
+    pub fn create_hanging_lamp(&mut self, pos: glam::f32::Vec3, links: usize) {
+        let link_length = 0.3;
+        let mut anchor = pos;
+        let mut previous = None;
+        for i in 0..=links {
+            let lamp = i == links;
+            let mut obj = if lamp {
+                GameObject::new(
+                    "Lamp",
+                    Box::new(Sphere {
+                        radius: 0.25,
+                        col_divisions: 16,
+                        row_divisions: 16,
+                    }),
+                    self.default_mat,
+                )
+            } else {
+                GameObject::new(
+                    "Chain Link",
+                    Box::new(Cube {
+                        width: 0.05,
+                        height: link_length,
+                        depth: 0.05,
+                    }),
+                    self.default_mat,
+                )
+            };
+            let half = if lamp { 0.25 } else { link_length / 2.0 };
+            obj.transform.position = anchor - glam::Vec3::Y * half;
+            obj.physics.material = PhysicsMaterial::METAL;
+            obj.physics.mass = if lamp { 2.0 } else { 0.2 };
+            obj.set_body_type(BodyType::Dynamic);
+            self.add_object(obj);
+            let idx = self.objects.len() - 1;
+            self.add_joint(idx, previous, anchor, anchor, JointKind::BallSocket);
+            previous = Some(idx);
+            anchor -= glam::Vec3::Y * (2.0 * half);
+        }
     }
 
     pub fn create_window(&mut self, pos: glam::f32::Vec3) {
@@ -556,6 +1129,15 @@ impl World {
         self.cameras.push(camera);
         self.selected_camera = Some(self.cameras.len() - 1);
     }
+    /// 当前相机的观察信息，用于 LOD 选择
+    pub fn lod_view(&self) -> Option<LodView> {
+        let cam = &self.cameras[self.get_selected_camera()?].camera;
+        Some(LodView {
+            position: cam.transform.position,
+            fovy: cam.fovy,
+        })
+    }
+
     pub fn get_selected_camera(&self) -> Option<usize> {
         if let Some(idx) = self.selected_camera
             && idx < self.cameras.len()
@@ -582,6 +1164,122 @@ impl World {
         self.objects.push(obj);
         self.selected_index = Some(self.objects.len() - 1);
     }
+    /// 移除物体并修正选中索引
+    pub fn remove_object(&mut self, idx: usize) -> GameObject {
+        let obj = self.objects.remove(idx);
+        // 物体下标整体前移，缓存的接触对不再对应
+        self.physics_state.contacts.clear();
+        self.physics_state.joints.retain_mut(|joint| joint.remap_removed(BodyHandle::Object(idx)));
+        self.physics_state.events.remap_removed(BodyHandle::Object(idx));
+        // 被删除的物体可能支撑着休眠的物体
+        for other in &mut self.objects {
+            other.wake();
+        }
+        for other in &mut self.objects {
+            if let InteractionBehavior::DoorSensor { door } = &mut other.behavior {
+                match (*door).cmp(&idx) {
+                    std::cmp::Ordering::Equal => other.behavior = InteractionBehavior::None,
+                    std::cmp::Ordering::Greater => *door -= 1,
+                    std::cmp::Ordering::Less => {}
+                }
+            }
+        }
+        if self.selected_index == Some(idx) {
+            self.selected_index = None;
+        } else if let Some(sel) = self.selected_index
+            && sel > idx
+        {
+            self.selected_index = Some(sel - 1);
+        }
+        match self.import_report {
+            Some((i, ..)) if i == idx => self.import_report = None,
+            Some((ref mut i, ..)) if *i > idx => *i -= 1,
+            _ => {}
+        }
+        if self.csg_operand == Some(idx) {
+            self.csg_operand = None;
+        } else if let Some(op) = self.csg_operand
+            && op > idx
+        {
+            self.csg_operand = Some(op - 1);
+        }
+        obj
+    }
+
+    /// 将两个物体合并为一个 CSG 节点，原物体作为可编辑的操作数保留；
+    /// 运算失败时返回原因，场景保持不变
+    pub fn combine_objects(&mut self, a: usize, b: usize, op: CsgOp) -> Result<(), String> {
+        if a == b || a >= self.objects.len() || b >= self.objects.len() {
+            return Ok(());
+        }
+        // B 的变换表示为相对 A 的变换，先在原物体上试算
+        let relative = self.objects[a].transform.get_matrix().inverse() * self.objects[b].transform.get_matrix();
+        let operand_mesh = |obj: &GameObject| CsgOperand::shape_mesh(&*obj.shape, obj.subdivision.as_ref());
+        let result = boolean(
+            &operand_mesh(&self.objects[a]),
+            glam::Mat4::IDENTITY,
+            &operand_mesh(&self.objects[b]),
+            relative,
+            op,
+        )?;
+
+        // 先移除索引较大的一个，避免另一个索引失效
+        let (obj_a, obj_b) = if a > b {
+            let obj_a = self.remove_object(a);
+            (obj_a, self.remove_object(b))
+        } else {
+            let obj_b = self.remove_object(b);
+            (self.remove_object(a), obj_b)
+        };
+
+        let (scale, rotation, position) = relative.to_scale_rotation_translation();
+        let symbol = match op {
+            CsgOp::Union => "+",
+            CsgOp::Difference => "-",
+            CsgOp::Intersection => "&",
+        };
+        let name = format!("{} {} {}", obj_a.name, symbol, obj_b.name);
+        let node = CsgNode::with_result(
+            op,
+            CsgOperand {
+                name: obj_a.name,
+                shape: obj_a.shape,
+                subdivision: obj_a.subdivision,
+                transform: Transform::default(),
+            },
+            CsgOperand {
+                name: obj_b.name,
+                shape: obj_b.shape,
+                subdivision: obj_b.subdivision,
+                transform: Transform::new(position, rotation, scale),
+            },
+            result,
+        );
+        let mut obj = GameObject::new(&name, Box::new(node), obj_a.rendering.material);
+        obj.rendering.use_texture = obj_a.rendering.use_texture;
+        obj.transform = obj_a.transform;
+        obj.physics = obj_a.physics;
+        obj.behavior = obj_a.behavior;
+        self.add_object(obj);
+        Ok(())
+    }
+
+    /// 鼠标拾取：宽相给出射线经过的物体，再与网格三角形精确求交，返回最近的可见物体；
+    /// 不参与碰撞的物体也能选中
+    pub fn pick_object(&self, origin: glam::f32::Vec3, direction: glam::f32::Vec3, max_distance: f32) -> Option<usize> {
+        let filter = QueryFilter { sensors: true, ..QueryFilter::default() };
+        self.pick_query()
+            .ray_candidates(origin, direction, max_distance, &filter)
+            .into_iter()
+            .filter_map(|handle| match handle {
+                BodyHandle::Object(i) => Some((i, self.objects[i].ray_hit(origin, direction)?)),
+                BodyHandle::Camera(_) => None,
+            })
+            .filter(|&(_, distance)| distance <= max_distance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
     pub fn get_selected_mut(&mut self) -> Option<&mut GameObject> {
         if let Some(idx) = self.selected_index
             && idx < self.objects.len()
@@ -658,6 +1356,9 @@ impl World {
     pub fn init_house_scene(&mut self, _display: &glium::Display<glutin::surface::WindowSurface>) {
         // 清理
         self.objects.clear();
+        self.physics_state.joints.clear();
+        self.physics_state.layers = CollisionLayers::default();
+        self.physics_state.events = EventQueue::default();
         self.lights.clear();
         self.cameras.clear();
 
@@ -738,6 +1439,7 @@ impl World {
         );
         floor.transform.position = [0.0, -0.25, 0.0].into();
         floor.set_body_type(BodyType::Static);
+        floor.physics.layer = CollisionLayers::STATIC;
         self.add_object(floor);
 
         // B. 天花板
@@ -752,6 +1454,7 @@ impl World {
         );
         ceiling.transform.position = [0.0, room_h + 0.25, 0.0].into();
         ceiling.set_body_type(BodyType::Static);
+        ceiling.physics.layer = CollisionLayers::STATIC;
         self.add_object(ceiling);
 
         // 后墙 (实心)
@@ -766,6 +1469,7 @@ impl World {
         );
         wall_back.transform.position = [0.0, room_h / 2.0, room_d / 2.0 + wall_thick / 2.0].into(); // 往外推半个墙厚
         wall_back.set_body_type(BodyType::Static);
+        wall_back.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_back);
 
         // 右墙 (实心)
@@ -780,6 +1484,7 @@ impl World {
         );
         wall_right.transform.position = [room_w / 2.0 + wall_thick / 2.0, room_h / 2.0, 0.0].into();
         wall_right.set_body_type(BodyType::Static);
+        wall_right.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_right);
 
         // 前墙 (带门洞)
@@ -802,6 +1507,7 @@ impl World {
         ]
         .into();
         wall_front_header.set_body_type(BodyType::Static);
+        wall_front_header.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_front_header);
 
         // 左右两块大墙
@@ -824,6 +1530,7 @@ impl World {
         ]
         .into(); // -0.2 是修正重叠量
         wall_front_l.set_body_type(BodyType::Static);
+        wall_front_l.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_front_l);
 
         let mut wall_front_r = GameObject::new(
@@ -842,6 +1549,7 @@ impl World {
         ]
         .into();
         wall_front_r.set_body_type(BodyType::Static);
+        wall_front_r.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_front_r);
 
         // 左墙 (带窗洞)
@@ -860,6 +1568,7 @@ impl World {
         wall_left_bot.transform.position =
             [-room_w / 2.0 - wall_thick / 2.0, win_bottom_h / 2.0, 0.0].into();
         wall_left_bot.set_body_type(BodyType::Static);
+        wall_left_bot.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_left_bot);
 
         // 窗上墙
@@ -881,6 +1590,7 @@ impl World {
         ]
         .into();
         wall_left_top.set_body_type(BodyType::Static);
+        wall_left_top.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_left_top);
 
         // 窗两侧墙
@@ -900,6 +1610,7 @@ impl World {
         wall_left_front.transform.position =
             [-room_w / 2.0 - wall_thick / 2.0, room_h / 2.0, -side_pos_z].into();
         wall_left_front.set_body_type(BodyType::Static);
+        wall_left_front.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_left_front);
 
         // 左墙-后段
@@ -915,6 +1626,7 @@ impl World {
         wall_left_back.transform.position =
             [-room_w / 2.0 - wall_thick / 2.0, room_h / 2.0, side_pos_z].into();
         wall_left_back.set_body_type(BodyType::Static);
+        wall_left_back.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_left_back);
 
         // 门框与门
@@ -987,7 +1699,7 @@ impl World {
             is_open: false,
             base_yaw: 0.0,
         };
-        door.set_body_type(BodyType::Static);
+        door.set_body_type(BodyType::Kinematic);
         self.add_object(door);
 
         // 窗框与窗
@@ -1083,6 +1795,8 @@ impl World {
             selected_point_idx: 0,
             u_knots: vec![],
             v_knots: vec![],
+            trims: vec![],
+            selected_trim_idx: 0,
         };
         let mut screen = GameObject::new("Screen", Box::new(nurbs), screen_mat);
         screen.set_body_type(BodyType::Static);
@@ -1327,6 +2041,9 @@ impl World {
     pub fn init_aimlab_scene(&mut self, _display: &glium::Display<glutin::surface::WindowSurface>) {
         // 清理旧场景
         self.objects.clear();
+        self.physics_state.joints.clear();
+        self.physics_state.layers = CollisionLayers::default();
+        self.physics_state.events = EventQueue::default();
         self.lights.clear();
         self.cameras.clear();
 
@@ -1378,6 +2095,7 @@ impl World {
         );
         floor.transform.position = [0.0, -0.5, 0.0].into();
         floor.set_body_type(BodyType::Static);
+        floor.physics.layer = CollisionLayers::STATIC;
         self.add_object(floor);
 
         // 前墙
@@ -1399,6 +2117,7 @@ impl World {
         );
         wall_front.transform.position = [0.0, 4.5, -10.0].into();
         wall_front.set_body_type(BodyType::Static);
+        wall_front.physics.layer = CollisionLayers::STATIC;
         self.add_object(wall_front);
 
         // 生成初始靶子
@@ -1446,38 +2165,19 @@ impl World {
 
             println!("Bang! Shot fired from {:?} dir {:?}", origin, forward);
 
-            let mut hit_idx = None;
-            let mut min_dist = f32::MAX;
-
-            // 遍历所有物体进行检测
-            for (i, obj) in self.objects.iter().enumerate() {
-                if !obj.name.starts_with("Target") {
-                    continue;
-                }
-
-                let radius = 0.5 * obj.transform.scale.x;
-                let center = obj.transform.position;
-
-                if let Some(dist) = ray_intersect_sphere(origin, forward, center, radius)
-                    && dist < min_dist {
-                        min_dist = dist;
-                        hit_idx = Some(i);
-                    }
-            }
+            // 只有第一个命中的物体是靶子才算打中，墙会挡住子弹
+            let filter = QueryFilter::excluding(BodyHandle::Camera(cam_idx));
+            let hit = self.raycast(origin, forward, SHOOT_RANGE, &filter).and_then(|hit| match hit.handle {
+                BodyHandle::Object(i) if self.objects[i].name.starts_with("Target") => Some((i, hit.distance)),
+                _ => None,
+            });
 
             // 处理命中结果
-            if let Some(idx) = hit_idx {
-                println!("Hit Target! Distance: {:.2}", min_dist);
+            if let Some((idx, distance)) = hit {
+                println!("Hit Target! Distance: {:.2}", distance);
 
                 // 移除旧靶子
-                self.objects.remove(idx);
-
-                if self.selected_index == Some(idx) {
-                    self.selected_index = None;
-                } else if let Some(sel) = self.selected_index
-                    && sel > idx {
-                        self.selected_index = Some(sel - 1);
-                    }
+                self.remove_object(idx);
 
                 // 生成新靶子
                 // self.spawn_target();
@@ -1489,42 +2189,6 @@ impl World {
     }
 }
 
-fn ray_intersect_sphere(
-    origin: glam::Vec3,
-    dir: glam::Vec3,
-    center: glam::Vec3,
-    radius: f32,
-) -> Option<f32> {
-    let l = center - origin;
-    let tca = l.dot(dir);
-    if tca < 0.0 {
-        return None;
-    }
-
-    let d2 = l.dot(l) - tca * tca;
-    let radius2 = radius * radius;
-    if d2 > radius2 {
-        return None;
-    }
-
-    let thc = (radius2 - d2).sqrt();
-    let t0 = tca - thc;
-    let t1 = tca + thc;
-
-    if t0 < 0.0 && t1 < 0.0 {
-        return None;
-    }
-
-    if t0 < 0.0 { Some(t1) } else { Some(t0) }
-}
-
-fn is_dynamic(handle: BodyHandle, world: &mut World) -> bool {
-    match handle {
-        BodyHandle::Object(idx) => world.objects[idx].is_dynamic(),
-        BodyHandle::Camera(idx) => world.cameras[idx].is_dynamic(),
-    }
-}
-
 fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t.clamp(0.0, 1.0)
 }
@@ -35,12 +35,20 @@ impl UIBuild for CameraObject {
                 }
                 ui.slider("force", 1.0, 100.0, &mut self.camera.force);
                 ui.slider("up_vel", 0.0, 100.0, &mut self.camera.up_velocity);
-                ui.slider(
-                    "friction_0",
-                    0.0,
-                    10.0,
-                    &mut self.camera.physics.friction,
-                );
+
+                ui.separator();
+                ui.text("角色控制器");
+                let controller = &mut self.camera.controller;
+                ui.slider("台阶高度", 0.0, 1.0, &mut controller.step_height);
+                let mut slope = controller.max_slope.to_degrees();
+                if ui.slider("最大坡度", 0.0, 89.0, &mut slope) {
+                    controller.max_slope = slope.to_radians();
+                }
+                ui.slider("土狼时间", 0.0, 0.5, &mut controller.coyote_time);
+                ui.slider("地面阻力", 0.0, 10.0, &mut controller.ground_drag);
+                ui.text(format!("着地: {}", controller.grounded));
+                let n = controller.ground_normal;
+                ui.text(format!("地面法线: ({:.2}, {:.2}, {:.2})", n.x, n.y, n.z));
 
             });
     }
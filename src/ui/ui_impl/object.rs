@@ -1,4 +1,14 @@
 
+use crate::core::material::NormalMap;
+use crate::geometry::edit::{self, SelectionMode};
+use crate::geometry::shape::mesh::MeshShape;
+use crate::geometry::normals::{NormalModifier, NormalWeighting};
+use crate::geometry::simplify::{self, LodChain, LodMetric, SimplifyTarget};
+use crate::geometry::subdivision::{SubdivisionModifier, SubdivisionScheme};
+use crate::geometry::uv::{UvAxis, UvModifier, UvProjection};
+use crate::physics::collider::ColliderShape;
+use crate::physics::material::{CombineRule, PhysicsMaterial};
+use crate::physics::rigid::RigidBody;
 use crate::scene::world::{BodyType, EditableMesh, GameObject};
 use crate::ui::UIBuild;
 use imgui::{Condition, Drag};
@@ -15,17 +25,31 @@ impl UIBuild for GameObject {
                 let mut pos = self.transform.position.to_array();
                 if Drag::new("位置").speed(0.1).build_array(ui, &mut pos) {
                     self.transform.position = pos.into();
+                    self.wake();
                 }
                 let mut vel = self.physics.velocity;
                 if Drag::new("速度").speed(0.1).build_array(ui, &mut vel) {
                     self.physics.velocity = vel;
                 }
+                let mut omega = self.physics.angular_velocity;
+                if Drag::new("角速度").speed(0.1).build_array(ui, &mut omega) {
+                    self.physics.angular_velocity = omega;
+                }
                 let mut scale = self.transform.scale.to_array();
                 if Drag::new("缩放").speed(0.01).build_array(ui, &mut scale) {
                     self.transform.scale = scale.into();
+                    self.wake();
+                    // 世界空间 UV 随缩放更新，保持纹理密度；拖动中只重建网格，松开后再重新生成 LOD
+                    if self.uv_modifier.as_ref().is_some_and(|m| m.world_space) {
+                        self.rebuild_mesh();
+                    }
+                }
+                if ui.is_item_deactivated_after_edit() && self.uv_modifier.as_ref().is_some_and(|m| m.world_space) {
+                    self.regenerate_lod();
                 }
                 if ui.button("重置旋转") {
                     self.transform.rotation = glam::f32::Quat::IDENTITY;
+                    self.wake();
                 }
 
                 ui.separator();
@@ -43,35 +67,62 @@ impl UIBuild for GameObject {
                 {
                     *self = GameObject {
                         name: self.name.clone() + " (Meshed)",
-                        shape: Box::new(self.mesh.clone()),
+                        shape: Box::new(MeshShape::new(self.cage_mesh())),
                         mesh: self.mesh.clone(),
                         rendering: self.rendering.clone(),
                         physics: self.physics.clone(),
                         transform: self.transform.clone(),
                         behavior: self.behavior,
+                        subdivision: self.subdivision.clone(),
+                        uv_modifier: self.uv_modifier.clone(),
+                        normal_modifier: self.normal_modifier.clone(),
+                        lod: self.lod.clone(),
+                        collider: self.collider.clone(),
                     };
                 }
 
-                match self.rendering.selected_vertex_index {
-                    Some(idx) => {
-                        ui.text_colored([1.0, 1.0, 0.0, 1.0], format!("编辑顶点 {}", idx));
-                        let v = &mut self.mesh.vertices[idx];
-                        Drag::new("X").speed(0.01).build(ui, &mut v[0]);
-                        Drag::new("Y").speed(0.01).build(ui, &mut v[1]);
-                        Drag::new("Z").speed(0.01).build(ui, &mut v[2]);
+                self.build_subdivision_ui(ui);
+                self.build_uv_ui(ui);
+                self.build_normal_ui(ui);
+                self.build_lod_ui(ui);
 
-                        let t = &mut self.mesh.tex_coords[idx];
-                        Drag::new("U").speed(0.01).build(ui, &mut t[0]);
-                        if Drag::new("V").speed(0.01).build(ui, &mut t[1]) {}
-                    }
-                    None => {
-                        ui.text("未选中顶点");
+                if !self.shape.intermediate_mesh() || self.has_modifiers() {
+                    self.build_edit_ui(ui);
+                }
+
+                if self.rendering.selection.mode == SelectionMode::Vertex && !self.has_modifiers() {
+                    match self.rendering.selected_vertex_index {
+                        Some(idx) => {
+                            ui.text_colored([1.0, 1.0, 0.0, 1.0], format!("编辑顶点 {}", idx));
+                            let mut changed = false;
+                            let v = &mut self.mesh.vertices[idx];
+                            changed |= Drag::new("X").speed(0.01).build(ui, &mut v[0]);
+                            changed |= Drag::new("Y").speed(0.01).build(ui, &mut v[1]);
+                            changed |= Drag::new("Z").speed(0.01).build(ui, &mut v[2]);
+
+                            let t = &mut self.mesh.tex_coords[idx];
+                            changed |= Drag::new("U").speed(0.01).build(ui, &mut t[0]);
+                            changed |= Drag::new("V").speed(0.01).build(ui, &mut t[1]);
+
+                            if changed {
+                                self.mesh.update_tangents();
+                                self.collider.invalidate();
+                            }
+                            // 网格体的形状就是网格本身，同步后细分等修改器才能看到改动
+                            if changed && !self.shape.intermediate_mesh() {
+                                self.shape = Box::new(MeshShape::new(self.mesh.clone()));
+                            }
+                        }
+                        None => {
+                            ui.text("未选中顶点");
+                        }
                     }
                 }
 
                 ui.separator();
                 ui.checkbox("显示/隐藏", &mut self.rendering.visible);
                 ui.checkbox("启用纹理贴图", &mut self.rendering.use_texture);
+                self.build_normal_map_ui(ui);
                 if ui.button("保存当前模型") {
                     let _ = self.mesh.save_obj("output.obj");
                 }
@@ -79,20 +130,485 @@ impl UIBuild for GameObject {
 
                 ui.text("物理属性(Physics)");
 
-                let is_dynamic = self.physics.body_type == BodyType::Dynamic;
-                if ui.radio_button_bool("自由物体(Dynamic}", is_dynamic) {
+                let body_type = self.physics.body_type;
+                if ui.radio_button_bool("自由物体(Dynamic}", body_type == BodyType::Dynamic) {
                     self.set_body_type(BodyType::Dynamic);
                 }
-                if ui.radio_button_bool("静态物体(Static)", !is_dynamic) {
+                if ui.radio_button_bool("静态物体(Static)", body_type == BodyType::Static) {
                     self.set_body_type(BodyType::Static);
                 }
+                if ui.radio_button_bool("运动学物体(Kinematic)", body_type == BodyType::Kinematic) {
+                    self.set_body_type(BodyType::Kinematic);
+                }
 
                 if self.physics.body_type == BodyType::Dynamic {
-                    Drag::new("弹性系数")
-                        .speed(0.01)
-                        .range(0.0, 1.0)
-                        .build(ui, &mut self.physics.restitution);
+                    if self.is_asleep() {
+                        ui.text_colored([0.5, 0.5, 1.0, 1.0], "状态: 休眠");
+                        ui.same_line();
+                        if ui.small_button("唤醒") {
+                            self.wake();
+                        }
+                    } else {
+                        ui.text("状态: 活动");
+                    }
+                    ui.checkbox("按密度计算质量", &mut self.physics.mass_from_density);
+                    if self.physics.mass_from_density {
+                        ui.text(format!("质量: {:.3} kg", self.mass()));
+                    } else {
+                        Drag::new("质量").speed(0.01).range(0.001, f32::MAX).build(ui, &mut self.physics.mass);
+                    }
+                    ui.checkbox("连续碰撞检测(CCD)", &mut self.physics.ccd);
+                }
+                ui.checkbox("触发器(Sensor)", &mut self.physics.sensor);
+                self.build_material_ui(ui);
+                self.build_collider_ui(ui);
+            });
+    }
+}
+
+impl GameObject {
+    fn build_material_ui(&mut self, ui: &imgui::Ui) {
+        let material = &mut self.physics.material;
+        let preview = PhysicsMaterial::PRESETS
+            .iter()
+            .find(|(_, preset)| preset == material)
+            .map_or("自定义", |(label, _)| label);
+        if let Some(_combo) = ui.begin_combo("物理材质", preview) {
+            for (label, preset) in PhysicsMaterial::PRESETS {
+                if ui.selectable_config(label).selected(preset == *material).build() {
+                    *material = preset;
                 }
+            }
+        }
+        Drag::new("静摩擦").speed(0.01).range(0.0, 2.0).build(ui, &mut material.static_friction);
+        Drag::new("动摩擦").speed(0.01).range(0.0, 2.0).build(ui, &mut material.dynamic_friction);
+        Drag::new("弹性系数").speed(0.01).range(0.0, 1.0).build(ui, &mut material.restitution);
+        Drag::new("密度").speed(10.0).range(1.0, 20000.0).build(ui, &mut material.density);
+        let rules = CombineRule::ALL;
+        let mut current = rules.iter().position(|r| *r == material.friction_combine).unwrap_or(0);
+        if ui.combo("摩擦合成", &mut current, &rules, |r| r.label().into()) {
+            material.friction_combine = rules[current];
+        }
+        let mut current = rules.iter().position(|r| *r == material.restitution_combine).unwrap_or(0);
+        if ui.combo("弹性合成", &mut current, &rules, |r| r.label().into()) {
+            material.restitution_combine = rules[current];
+        }
+    }
+
+    fn build_collider_ui(&mut self, ui: &imgui::Ui) {
+        let shapes = ColliderShape::ALL;
+        let mut current = shapes.iter().position(|s| *s == self.physics.shape).unwrap_or(0);
+        if ui.combo("碰撞形状", &mut current, &shapes, |s| s.label().into()) {
+            self.physics.shape = shapes[current];
+        }
+        match self.physics.effective_shape() {
+            ColliderShape::ConvexHull if self.physics.shape == ColliderShape::TriMesh => {
+                ui.text_disabled("动态物体使用凸包代替三角网格");
+            }
+            ColliderShape::ConvexHull if !self.collider.hull.is_empty() => {
+                ui.text(format!("凸包顶点: {}", self.collider.hull.len()));
+            }
+            ColliderShape::TriMesh if !self.collider.triangles.is_empty() => {
+                ui.text(format!("碰撞三角形: {}", self.collider.triangles.len()));
+            }
+            _ => {}
+        }
+    }
+
+    /// 细分修改器：不改动形状本身，只影响生成的网格
+    fn build_subdivision_ui(&mut self, ui: &imgui::Ui) {
+        let mut enabled = self.subdivision.is_some();
+        if ui.checkbox("细分修改器", &mut enabled) {
+            self.subdivision = enabled.then(|| SubdivisionModifier {
+                levels: 1,
+                ..Default::default()
             });
+            self.rendering.selected_vertex_index = None;
+            self.rendering.selection.clear();
+            self.regenerate_mesh();
+        }
+        let Some(modifier) = &mut self.subdivision else {
+            return;
+        };
+
+        let mut changed = false;
+        for (i, (label, scheme)) in [
+            ("自动", SubdivisionScheme::Auto),
+            ("Loop", SubdivisionScheme::Loop),
+            ("Catmull-Clark", SubdivisionScheme::CatmullClark),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if i > 0 {
+                ui.same_line();
+            }
+            if ui.radio_button_bool(label, modifier.scheme == scheme) && modifier.scheme != scheme {
+                modifier.scheme = scheme;
+                changed = true;
+            }
+        }
+        changed |= ui.slider("细分级数", 0, 4, &mut modifier.levels);
+        ui.text(format!("折痕边: {}", modifier.creases.len()));
+        if !modifier.creases.is_empty() {
+            ui.same_line();
+            if ui.button("清除折痕") {
+                modifier.creases.clear();
+                changed = true;
+            }
+        }
+        if changed {
+            self.regenerate_mesh();
+        }
+        if ui.button("应用修改器") {
+            let mesh = self.mesh.clone();
+            self.subdivision = None;
+            self.replace_mesh(mesh);
+        }
+        ui.separator();
+    }
+
+    /// 法线贴图：内置程序化贴图或 assets 目录下的贴图文件
+    fn build_normal_map_ui(&mut self, ui: &imgui::Ui) {
+        let preview = self
+            .rendering
+            .normal_map
+            .as_ref()
+            .map_or("无", NormalMap::label)
+            .to_string();
+        if let Some(_combo) = ui.begin_combo("法线贴图", preview) {
+            if ui.selectable("无") {
+                self.rendering.normal_map = None;
+            }
+            for kind in NormalMap::BUILTIN {
+                if ui.selectable(kind.label()) {
+                    self.rendering.normal_map = Some(kind);
+                }
+            }
+            for path in ["assets/normal.png", "assets/normal.jpg"] {
+                if std::path::Path::new(path).exists() && ui.selectable(path) {
+                    self.rendering.normal_map = Some(NormalMap::File(path.to_string()));
+                }
+            }
+        }
+        if self.rendering.normal_map.is_some() {
+            ui.slider("法线强度", 0.0, 2.0, &mut self.rendering.normal_strength);
+        }
+    }
+
+    /// UV 修改器：投影或自动展开
+    fn build_uv_ui(&mut self, ui: &imgui::Ui) {
+        let mut enabled = self.uv_modifier.is_some();
+        if ui.checkbox("UV 投影", &mut enabled) {
+            self.uv_modifier = enabled.then(UvModifier::default);
+            self.rendering.selected_vertex_index = None;
+            self.rendering.selection.clear();
+            self.regenerate_mesh();
+        }
+        let Some(modifier) = &mut self.uv_modifier else {
+            return;
+        };
+
+        let mut changed = false;
+        let kinds = ["平面", "盒状", "柱面", "球面", "自动展开"];
+        let mut kind = match modifier.projection {
+            UvProjection::Planar(_) => 0,
+            UvProjection::Box => 1,
+            UvProjection::Cylindrical(_) => 2,
+            UvProjection::Spherical => 3,
+            UvProjection::Unwrap => 4,
+        };
+        let axis = match modifier.projection {
+            UvProjection::Planar(axis) | UvProjection::Cylindrical(axis) => axis,
+            _ => UvAxis::default(),
+        };
+        if ui.combo_simple_string("投影方式", &mut kind, &kinds) {
+            modifier.projection = match kind {
+                0 => UvProjection::Planar(axis),
+                1 => UvProjection::Box,
+                2 => UvProjection::Cylindrical(axis),
+                3 => UvProjection::Spherical,
+                _ => UvProjection::Unwrap,
+            };
+            changed = true;
+        }
+        if let UvProjection::Planar(axis) | UvProjection::Cylindrical(axis) = &mut modifier.projection {
+            for (i, (label, value)) in [("X 轴", UvAxis::X), ("Y 轴", UvAxis::Y), ("Z 轴", UvAxis::Z)]
+                .into_iter()
+                .enumerate()
+            {
+                if i > 0 {
+                    ui.same_line();
+                }
+                if ui.radio_button_bool(label, *axis == value) && *axis != value {
+                    *axis = value;
+                    changed = true;
+                }
+            }
+        }
+        if modifier.projection != UvProjection::Unwrap {
+            changed |= ui.checkbox("世界空间尺寸", &mut modifier.world_space);
+        }
+        changed |= Drag::new("UV 缩放").speed(0.01).range(0.001, 100.0).build(ui, &mut modifier.scale);
+        changed |= Drag::new("UV 偏移").speed(0.01).build_array(ui, &mut modifier.offset);
+        if changed {
+            self.regenerate_mesh();
+        }
+        if self.subdivision.is_none() && ui.button("应用 UV") {
+            // 只烘焙 UV，法线修改器仍作用于应用后的网格
+            if let Some(modifier) = self.uv_modifier.take() {
+                let mesh = modifier.apply(&self.shape.as_mesh(), self.transform.scale);
+                self.replace_mesh(mesh);
+            }
+        }
+        ui.separator();
+    }
+
+    /// 法线修改器：按平滑角度重新计算法线
+    fn build_normal_ui(&mut self, ui: &imgui::Ui) {
+        let mut enabled = self.normal_modifier.is_some();
+        if ui.checkbox("重算法线", &mut enabled) {
+            self.normal_modifier = enabled.then(NormalModifier::default);
+            self.rendering.selected_vertex_index = None;
+            self.rendering.selection.clear();
+            self.regenerate_mesh();
+        }
+        let Some(modifier) = &mut self.normal_modifier else {
+            return;
+        };
+
+        let mut changed = ui.slider("平滑角度", 0.0, 180.0, &mut modifier.crease_angle);
+        for (i, (label, weighting)) in [
+            ("均匀", NormalWeighting::Uniform),
+            ("面积加权", NormalWeighting::Area),
+            ("角度加权", NormalWeighting::Angle),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if i > 0 {
+                ui.same_line();
+            }
+            if ui.radio_button_bool(label, modifier.weighting == weighting) && modifier.weighting != weighting {
+                modifier.weighting = weighting;
+                changed = true;
+            }
+        }
+        if !self.mesh.smoothing_groups.is_empty() {
+            changed |= ui.checkbox("使用 OBJ 平滑组", &mut modifier.use_smoothing_groups);
+        }
+        if changed {
+            self.regenerate_mesh();
+        }
+        if self.subdivision.is_none() && ui.button("应用法线") {
+            let mesh = self.mesh.clone();
+            self.normal_modifier = None;
+            self.replace_mesh(mesh);
+        }
+        ui.separator();
+    }
+
+    /// LOD 链与网格简化
+    fn build_lod_ui(&mut self, ui: &imgui::Ui) {
+        let mut enabled = self.lod.is_some();
+        if ui.checkbox("LOD", &mut enabled) {
+            if enabled {
+                self.enable_lod(LodMetric::Distance);
+            } else {
+                self.lod = None;
+            }
+        }
+        if let Some(lod) = &mut self.lod {
+            for (i, (label, metric)) in [
+                ("按距离", LodMetric::Distance),
+                ("按屏幕占比", LodMetric::ScreenSize),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                if i > 0 {
+                    ui.same_line();
+                }
+                if ui.radio_button_bool(label, lod.metric == metric) && lod.metric != metric {
+                    let levels = std::mem::take(&mut lod.levels);
+                    *lod = LodChain {
+                        levels,
+                        ..LodChain::new(metric)
+                    };
+                }
+            }
+            for (i, t) in lod.thresholds.iter_mut().enumerate() {
+                Drag::new(format!("LOD{} 阈值", i + 1))
+                    .speed(0.01)
+                    .range(0.0, 1000.0)
+                    .build(ui, t);
+            }
+            for (i, level) in lod.levels.iter().enumerate() {
+                ui.text(format!("LOD{}: {} 三角形", i, level.indices.len() / 3));
+            }
+            if ui.button("重新生成 LOD") {
+                lod.generate(&self.mesh);
+            }
+        }
+
+        if !self.shape.intermediate_mesh() {
+            let cage = self.cage_mesh();
+            let sel = &mut self.rendering.selection;
+            if ui.radio_button_bool("按比例", !sel.simplify_by_error) {
+                sel.simplify_by_error = false;
+            }
+            ui.same_line();
+            if ui.radio_button_bool("按误差", sel.simplify_by_error) {
+                sel.simplify_by_error = true;
+            }
+            let target = if sel.simplify_by_error {
+                Drag::new("误差 (均方根距离)")
+                    .speed(0.001)
+                    .range(0.0, 10.0)
+                    .build(ui, &mut sel.simplify_error);
+                SimplifyTarget::Error(sel.simplify_error)
+            } else {
+                Drag::new("保留比例")
+                    .speed(0.01)
+                    .range(0.01, 1.0)
+                    .build(ui, &mut sel.simplify_ratio);
+                let triangles = cage.indices.len() / 3;
+                SimplifyTarget::Triangles((triangles as f32 * sel.simplify_ratio) as usize)
+            };
+            if ui.button("简化网格") {
+                let mesh = simplify::simplify(&cage, target);
+                self.replace_mesh(mesh);
+            }
+        }
+        ui.separator();
+    }
+
+    /// 网格编辑：选择模式与建模操作
+    fn build_edit_ui(&mut self, ui: &imgui::Ui) {
+        ui.text("网格编辑 (Shift+点击多选)");
+        let mode = self.rendering.selection.mode;
+        for (i, (label, m)) in [
+            ("顶点", SelectionMode::Vertex),
+            ("边", SelectionMode::Edge),
+            ("面", SelectionMode::Face),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if i > 0 {
+                ui.same_line();
+            }
+            if ui.radio_button_bool(label, mode == m) && mode != m {
+                self.rendering.selection.clear();
+                self.rendering.selected_vertex_index = None;
+                self.rendering.selection.mode = m;
+            }
+        }
+
+        let editable = !self.shape.intermediate_mesh();
+        let cage = self.cage_mesh();
+        let sel = &mut self.rendering.selection;
+        let mut result = None;
+        match sel.mode {
+            SelectionMode::Vertex => {
+                if let Some(idx) = self.rendering.selected_vertex_index
+                    && editable
+                    && self.subdivision.is_none()
+                    && ui.button("删除顶点")
+                {
+                    result = Some(edit::delete_vertex(&self.mesh, idx));
+                }
+            }
+            SelectionMode::Edge => {
+                ui.text(format!("已选中 {} 条边", sel.edges.len()));
+                if let Some(modifier) = &mut self.subdivision {
+                    Drag::new("折痕权重")
+                        .speed(0.05)
+                        .range(0.0, 10.0)
+                        .build(ui, &mut sel.crease_weight);
+                    if !sel.edges.is_empty() && ui.button("设置折痕") {
+                        let (topo, _) = edit::editable_topology(&cage);
+                        for &(a, b) in &sel.edges {
+                            modifier.set_crease(
+                                topo.vertices[a].position,
+                                topo.vertices[b].position,
+                                sel.crease_weight,
+                            );
+                        }
+                        self.mesh = modifier.apply(&cage);
+                        self.mesh.update_tangents();
+                        self.collider.invalidate();
+                    }
+                }
+                if editable {
+                    Drag::new("环切位置")
+                        .speed(0.01)
+                        .range(0.01, 0.99)
+                        .build(ui, &mut sel.cut_ratio);
+                    if let Some(&e) = sel.edges.first() {
+                        if ui.button("环切") {
+                            result = Some(edit::loop_cut(&cage, e, sel.cut_ratio));
+                        }
+                        ui.same_line();
+                        if ui.button("删除边") {
+                            result = Some(edit::delete_edges(&cage, &sel.edges));
+                        }
+                    }
+                }
+            }
+            SelectionMode::Face if !editable => {
+                ui.text(format!("已选中 {} 个面", sel.faces.len()));
+            }
+            SelectionMode::Face => {
+                ui.text(format!("已选中 {} 个面", sel.faces.len()));
+                Drag::new("挤出距离")
+                    .speed(0.01)
+                    .build(ui, &mut sel.extrude_distance);
+                Drag::new("内插比例")
+                    .speed(0.01)
+                    .range(0.0, 0.99)
+                    .build(ui, &mut sel.inset_amount);
+                Drag::new("倒角高度")
+                    .speed(0.01)
+                    .build(ui, &mut sel.bevel_height);
+                if !sel.faces.is_empty() {
+                    if ui.button("挤出") {
+                        result = Some(edit::extrude_faces(&cage, &sel.faces, sel.extrude_distance));
+                    }
+                    ui.same_line();
+                    if ui.button("内插") {
+                        result = Some(edit::inset_faces(&cage, &sel.faces, sel.inset_amount, 0.0));
+                    }
+                    ui.same_line();
+                    if ui.button("倒角") {
+                        result = Some(edit::inset_faces(
+                            &cage,
+                            &sel.faces,
+                            sel.inset_amount,
+                            sel.bevel_height,
+                        ));
+                    }
+                    ui.same_line();
+                    if ui.button("删除面") {
+                        result = Some(edit::delete_faces(&cage, &sel.faces));
+                    }
+                }
+            }
+        }
+        if !sel.is_empty() && ui.button("清除选择") {
+            sel.clear();
+        }
+        if sel.rejected {
+            ui.text_colored([1.0, 0.4, 0.4, 1.0], "结果顶点数超出 16 位索引上限，操作已取消");
+        }
+
+        match result {
+            Some(Some(mesh)) => {
+                self.replace_mesh(mesh);
+                self.rendering.selection.rejected = false;
+            }
+            Some(None) => sel.rejected = true,
+            None => {}
+        }
     }
 }
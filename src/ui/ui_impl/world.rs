@@ -1,11 +1,15 @@
+use crate::geometry::csg::CsgOp;
+use crate::geometry::edit::{self, SelectionMode};
+use crate::geometry::repair::MAX_HOLE_EDGES;
 use crate::geometry::shape::cone::Cone;
 use crate::geometry::shape::cube::Cube;
 use crate::geometry::shape::cylinder::Cylinder;
-use crate::geometry::shape::mesh::Mesh;
+use crate::geometry::shape::mesh::{Mesh, MeshShape};
 use crate::geometry::shape::nurbs::NurbsSurface;
 use crate::geometry::shape::sphere::Sphere;
+use crate::geometry::simplify::{AUTO_LOD_TRIANGLES, LodMetric};
 use crate::scene::camera::{self, MoveState};
-use crate::scene::world::{GameObject, World};
+use crate::scene::world::{BodyHandle, GameObject, World};
 use crate::ui::{UIBuild, UIHandle};
 use imgui::Condition;
 
@@ -23,6 +27,10 @@ impl UIBuild for World {
                 ui.checkbox("物体属性窗口", &mut self.debug_params.game_object_property);
                 ui.checkbox("灯光属性窗口", &mut self.debug_params.light_property);
                 ui.checkbox("相机属性窗口", &mut self.debug_params.camera_property);
+                ui.checkbox("碰撞层窗口", &mut self.debug_params.collision_layers);
+                ui.separator();
+                ui.slider("物理频率", 30.0, 240.0, &mut self.timestep.rate);
+                ui.slider("最大子步数", 1, 16, &mut self.timestep.max_substeps);
             } );
 
         if self.debug_params.new_object {
@@ -136,6 +144,8 @@ impl UIBuild for World {
                             selected_point_idx: 0,
                             u_knots, 
                             v_knots, 
+                            trims: vec![],
+                            selected_trim_idx: 0,
                         }),
                         self.default_mat,
                     );
@@ -163,14 +173,21 @@ impl UIBuild for World {
                             selected_point_idx: 0,
                             u_knots: vec![], 
                             v_knots: vec![], 
+                            trims: vec![],
+                            selected_trim_idx: 0,
                         }),
                         self.default_mat,
                     ));
                 }
                 if ui.button("导入模型")
                     && let Ok(mesh) = Mesh::load_obj("output.obj") {
-                        let obj = GameObject::new("Imported", Box::new(mesh), self.default_mat);
+                        let report = mesh.validate();
+                        let mut obj = GameObject::new("Imported", Box::new(MeshShape::new(mesh)), self.default_mat);
+                        if obj.mesh.indices.len() / 3 > AUTO_LOD_TRIANGLES {
+                            obj.enable_lod(LodMetric::Distance);
+                        }
                         self.add_object(obj);
+                        self.import_report = Some((self.objects.len() - 1, report, None));
                     }
 
                 ui.separator();
@@ -182,10 +199,45 @@ impl UIBuild for World {
                 if ui.button("生成窗 (按F击碎)") {
                     self.create_window(glam::vec3(2.0, 1.5, 0.0));
                 }
+                if ui.button("生成升降台") {
+                    self.create_platform(glam::vec3(0.0, 0.1, -3.0));
+                }
+                ui.same_line();
+                if ui.button("生成铰链门") {
+                    self.create_hinged_door(glam::vec3(-2.0, 1.05, 2.0));
+                }
+                ui.same_line();
+                if ui.button("生成吊灯") {
+                    self.create_hanging_lamp(glam::vec3(2.0, 3.5, 2.0), 4);
+                }
+                if ui.button("生成自动门 (走近打开)") {
+                    self.create_auto_door(glam::vec3(-2.0, 1.0, -2.0));
+                }
+
+                let mut removed = None;
+                for (i, joint) in self.physics_state.joints.iter().enumerate() {
+                    let name = |handle| match handle {
+                        BodyHandle::Object(idx) => self.objects[idx].name.as_str(),
+                        BodyHandle::Camera(idx) => self.cameras[idx].name.as_str(),
+                    };
+                    let other = joint.b.map_or("世界", name);
+                    ui.text(format!("{}: {} - {}", joint.kind.label(), name(joint.a), other));
+                    ui.same_line();
+                    if ui.small_button(format!("删除##joint{i}")) {
+                        removed = Some(i);
+                    }
+                }
+                if let Some(i) = removed {
+                    self.remove_joint(i);
+                }
 
             });
         }
         
+        if self.debug_params.collision_layers {
+            self.build_collision_layers_ui(ui);
+        }
+
         if self.debug_params.object_list {
             ui.window("场景列表 (Scene List)")
             .size([200.0, 400.0], Condition::FirstUseEver)
@@ -271,6 +323,10 @@ impl UIBuild for World {
 
         if self.debug_params.game_object_property && let Some(obj) = self.get_selected_mut() { obj.build_ui(ui); }
 
+        if self.debug_params.game_object_property { self.build_csg_ui(ui); }
+
+        self.build_import_report_ui(ui);
+
         if self.debug_params.light_property && let Some(obj) = self.get_selected_light() { obj.build_ui(ui); }
 
         // ui.window("调试操作")
@@ -345,7 +401,7 @@ impl UIHandle for World {
 
                 if camera.move_state == camera::MoveState::RigidBody {
                     
-                    camera.set_dynamic();
+                    camera.set_character();
                     self.camera_force[0] = ui.is_key_down(imgui::Key::W);
                     self.camera_force[1] = ui.is_key_down(imgui::Key::S);
                     self.camera_force[2] = ui.is_key_down(imgui::Key::A);
@@ -436,7 +492,32 @@ impl UIHandle for World {
             if let Some(origin) = mouse_click_near
                 && let Some(target) = mouse_click_far {
                     let dir = (target - origin).normalize();
+                    let additive = ui.io().key_shift;
+                    // 最近的命中是其它物体时选中它，否则拾取当前物体的顶点、边或面
+                    if let Some(i) = self.pick_object(origin, dir, origin.distance(target))
+                        && self.selected_index != Some(i)
+                    {
+                        self.selected_index = Some(i);
+                        return;
+                    }
                     self.get_selected_mut().map(|obj| {
+                        let mode = obj.rendering.selection.mode;
+                        if mode != SelectionMode::Vertex {
+                            // 射线变换到模型空间
+                            let inv = obj.transform.get_matrix().inverse();
+                            let o = inv.transform_point3(origin).to_array();
+                            let d = inv.transform_vector3(dir).to_array();
+                            let cage = obj.cage_mesh();
+                            if mode == SelectionMode::Face {
+                                if let Some((face, _)) = edit::pick_face(&cage, o, d) {
+                                    obj.rendering.selection.toggle_face(face, additive);
+                                }
+                            } else if let Some(e) = edit::pick_edge(&cage, o, d) {
+                                obj.rendering.selection.toggle_edge(e, additive);
+                            }
+                            obj.rendering.selection.update_highlight(&cage);
+                            return;
+                        }
                         if let Some((pt, costheta)) = obj.mesh.compute_closest_point(origin.to_array(), dir.to_array())
                         {
                         if costheta /* cos theta */ < 0.95 {
@@ -453,4 +534,156 @@ impl UIHandle for World {
                 }
         }
     }
-}
\ No newline at end of file
+}
+impl World {
+    /// 碰撞层：命名、层间碰撞矩阵，以及选中物体所在的层
+    fn build_collision_layers_ui(&mut self, ui: &imgui::Ui) {
+        ui.window("碰撞层 (Collision Layers)")
+            .size([320.0, 400.0], Condition::FirstUseEver)
+            .position([240.0, 150.0], Condition::FirstUseEver)
+            .build(|| {
+                let layers = &mut self.physics_state.layers;
+                let count = layers.names().len();
+                let selected = match self.selected_index {
+                    Some(idx) => Some(&mut self.objects[idx].physics.layer),
+                    None => self.selected_camera.map(|idx| &mut self.cameras[idx].camera.physics.layer),
+                };
+                if let Some(layer) = selected {
+                    let mut current = (*layer as usize).min(count - 1);
+                    let items: Vec<u32> = (0..count as u32).collect();
+                    if ui.combo("选中物体所在层", &mut current, &items, |&i| layers.name(i).to_string().into()) {
+                        *layer = current as u32;
+                    }
+                }
+
+                ui.separator();
+                ui.text("层名称:");
+                for i in 0..count as u32 {
+                    let mut name = layers.name(i).to_string();
+                    if ui.input_text(format!("{i}##layer_name"), &mut name).build() {
+                        layers.rename(i, &name);
+                    }
+                }
+                if ui.button("新增层") {
+                    layers.add(&format!("层{count}"));
+                }
+
+                // 矩阵对称，只显示下三角；第 i 行第 j 个勾选框表示第 i 层与第 j 层是否碰撞
+                ui.separator();
+                ui.text("层间碰撞:");
+                for i in 0..count as u32 {
+                    for j in 0..=i {
+                        let mut collides = layers.collides(i, j);
+                        if ui.checkbox(format!("##collide{i}_{j}"), &mut collides) {
+                            layers.set_collides(i, j, collides);
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(format!("{} - {}", layers.name(i), layers.name(j)));
+                        }
+                        ui.same_line();
+                    }
+                    ui.text(layers.name(i));
+                }
+            });
+    }
+
+    /// 导入模型后弹出检查报告，可直接修复
+    fn build_import_report_ui(&mut self, ui: &imgui::Ui) {
+        let Some((idx, report, stats)) = &self.import_report else { return };
+        let idx = *idx;
+        let mut repair = false;
+        let mut close = false;
+        ui.window("导入检查 (Import Report)")
+            .size([320.0, 260.0], Condition::FirstUseEver)
+            .position([600.0, 40.0], Condition::FirstUseEver)
+            .build(|| {
+                ui.text(format!("物体: {}", self.objects[idx].name));
+                report.ui(ui);
+                if let Some(stats) = stats {
+                    ui.separator();
+                    ui.text("上次修复:");
+                    stats.ui(ui);
+                }
+                ui.separator();
+                if !report.is_clean() && ui.button("一键修复") {
+                    repair = true;
+                }
+                ui.same_line();
+                if ui.button("关闭") {
+                    close = true;
+                }
+            });
+        if repair {
+            let obj = &mut self.objects[idx];
+            let mut mesh = obj.cage_mesh();
+            let stats = mesh.repair(MAX_HOLE_EDGES);
+            let report = mesh.validate();
+            obj.replace_mesh(mesh);
+            self.import_report = Some((idx, report, Some(stats)));
+        }
+        if close {
+            self.import_report = None;
+        }
+    }
+
+    /// 在属性面板中追加布尔运算区域：选择另一物体作为操作数 B
+    fn build_csg_ui(&mut self, ui: &imgui::Ui) {
+        let Some(sel) = self.selected_index else { return };
+        if sel >= self.objects.len() {
+            return;
+        }
+        let mut request = None;
+        ui.window("属性面板 (Inspector)").build(|| {
+            if !ui.collapsing_header("布尔运算 (CSG)", imgui::TreeNodeFlags::empty()) {
+                return;
+            }
+            let preview = match self.csg_operand {
+                Some(idx) if idx != sel && idx < self.objects.len() => self.objects[idx].name.clone(),
+                _ => "(选择物体)".to_string(),
+            };
+            if let Some(_combo) = ui.begin_combo("操作数 B", preview) {
+                for (i, obj) in self.objects.iter().enumerate() {
+                    if i == sel {
+                        continue;
+                    }
+                    let _id = ui.push_id_usize(i);
+                    if ui.selectable_config(&obj.name).selected(self.csg_operand == Some(i)).build() {
+                        self.csg_operand = Some(i);
+                        self.csg_error = None;
+                    }
+                }
+            }
+            let Some(other) = self.csg_operand.filter(|&i| i != sel && i < self.objects.len()) else {
+                ui.text_disabled("选择另一物体后执行运算");
+                return;
+            };
+            for (i, (label, op)) in [
+                ("并集", CsgOp::Union),
+                ("差集", CsgOp::Difference),
+                ("交集", CsgOp::Intersection),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                if i > 0 {
+                    ui.same_line();
+                }
+                if ui.button(label) {
+                    request = Some((other, op));
+                }
+            }
+            if let Some(error) = &self.csg_error {
+                ui.text_colored([1.0, 0.4, 0.4, 1.0], error);
+            }
+        });
+        if let Some((other, op)) = request {
+            match self.combine_objects(sel, other, op) {
+                Ok(()) => {
+                    self.csg_operand = None;
+                    self.csg_error = None;
+                }
+                Err(e) => self.csg_error = Some(e),
+            }
+        }
+    }
+}